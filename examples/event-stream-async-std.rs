@@ -1,6 +1,8 @@
 //! Demonstrates how to read events asynchronously with async-std.
 //!
 //! cargo run --features="event-stream" --example event-stream-async-std
+//!
+//! Add the `async-io` feature to read the events without a helper thread on UNIX systems.
 
 use std::{io::stdout, time::Duration};
 
@@ -1,6 +1,8 @@
 //! Demonstrates how to read events asynchronously with tokio.
 //!
 //! cargo run --features="event-stream" --example event-stream-tokio
+//!
+//! Add the `tokio` feature to read the events without a helper thread on UNIX systems.
 
 use std::{io::stdout, time::Duration};
 
@@ -0,0 +1,23 @@
+//! Demonstrates how to read lines with the line editor.
+//!
+//! cargo run --example read-line
+
+use std::io;
+
+use crossterm::{line::LineEditor, style::Stylize};
+
+fn main() -> io::Result<()> {
+    println!("Enter some lines, `Ctrl+R` searches the history, `Ctrl+D` exits.");
+
+    let mut editor = LineEditor::new();
+    loop {
+        match editor.read_line(&mut io::stdout(), "> ".green()) {
+            Ok(line) => println!("{:?}", line),
+            Err(error) if error.kind() == io::ErrorKind::UnexpectedEof => break,
+            Err(error) if error.kind() == io::ErrorKind::Interrupted => continue,
+            Err(error) => return Err(error),
+        }
+    }
+
+    Ok(())
+}
@@ -1,7 +1,7 @@
 use std::fmt;
 use std::io::{self, Write};
 
-use crate::terminal::{BeginSynchronizedUpdate, EndSynchronizedUpdate};
+use crate::terminal::{modes, BeginSynchronizedUpdate, EndSynchronizedUpdate};
 
 /// An interface for a command that performs an action on the terminal.
 ///
@@ -34,6 +34,14 @@ pub trait Command {
     fn is_ansi_code_supported(&self) -> bool {
         super::ansi_support::supports_ansi()
     }
+
+    /// Records the terminal mode changed by this command, so that it can be restored when the
+    /// process is suspended or a [`TerminalGuard`](terminal/struct.TerminalGuard.html) is
+    /// dropped.
+    ///
+    /// This is called when the command is queued or executed, not when it's only formatted.
+    #[doc(hidden)]
+    fn record_mode(&self) {}
 }
 
 impl<T: Command + ?Sized> Command for &T {
@@ -52,6 +60,11 @@ impl<T: Command + ?Sized> Command for &T {
     fn is_ansi_code_supported(&self) -> bool {
         T::is_ansi_code_supported(self)
     }
+
+    #[inline]
+    fn record_mode(&self) {
+        T::record_mode(self)
+    }
 }
 
 /// An interface for types that can queue commands for further execution.
@@ -124,12 +137,20 @@ impl<T: Write + ?Sized> QueueableCommand for T {
             // There may be queued commands in this writer, but `execute_winapi` will execute the
             // command immediately. To prevent commands being executed out of order we flush the
             // writer now.
-            self.flush()?;
-            command.execute_winapi()?;
+            modes::queue_command(
+                || {
+                    self.flush()?;
+                    command.execute_winapi()
+                },
+                || command.record_mode(),
+            )?;
             return Ok(self);
         }
 
-        write_command_ansi(self, command)?;
+        modes::queue_command(
+            || write_command_ansi(self, &command),
+            || command.record_mode(),
+        )?;
         Ok(self)
     }
 }
@@ -44,10 +44,16 @@
 
 use std::fmt;
 
-use crate::{csi, impl_display, Command};
+use crate::{
+    csi, impl_display,
+    terminal::modes::{self, Modes},
+    Command,
+};
 
+mod planner;
 pub(crate) mod sys;
 
+pub use planner::{plan_movement, PlannedMovement};
 #[cfg(feature = "events")]
 pub use sys::position;
 
@@ -301,6 +307,10 @@ impl Command for Hide {
         f.write_str(csi!("?25l"))
     }
 
+    fn record_mode(&self) {
+        modes::set(Modes::HIDDEN_CURSOR, true);
+    }
+
     #[cfg(windows)]
     fn execute_winapi(&self) -> std::io::Result<()> {
         sys::show_cursor(false)
@@ -320,6 +330,10 @@ impl Command for Show {
         f.write_str(csi!("?25h"))
     }
 
+    fn record_mode(&self) {
+        modes::set(Modes::HIDDEN_CURSOR, false);
+    }
+
     #[cfg(windows)]
     fn execute_winapi(&self) -> std::io::Result<()> {
         sys::show_cursor(true)
@@ -0,0 +1,212 @@
+use std::fmt;
+
+use crate::{csi, Command};
+
+#[cfg(windows)]
+use crate::cursor::MoveTo;
+
+/// A cursor movement returned by [`plan_movement`](fn.plan_movement.html).
+///
+/// It's queued and executed like the other commands. With WinAPI, it moves the cursor with
+/// [`MoveTo`](struct.MoveTo.html).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PlannedMovement {
+    ansi: String,
+    #[cfg_attr(not(windows), allow(dead_code))]
+    target: (u16, u16),
+}
+
+impl PlannedMovement {
+    /// Returns the bytes moving the cursor.
+    pub fn as_str(&self) -> &str {
+        &self.ansi
+    }
+
+    /// Returns the number of bytes moving the cursor.
+    pub fn len(&self) -> usize {
+        self.ansi.len()
+    }
+
+    /// Returns whether the cursor is already at the target position.
+    pub fn is_empty(&self) -> bool {
+        self.ansi.is_empty()
+    }
+}
+
+impl Command for PlannedMovement {
+    fn write_ansi(&self, f: &mut impl fmt::Write) -> fmt::Result {
+        f.write_str(&self.ansi)
+    }
+
+    #[cfg(windows)]
+    fn execute_winapi(&self) -> std::io::Result<()> {
+        MoveTo(self.target.0, self.target.1).execute_winapi()
+    }
+}
+
+/// Returns the shortest sequence of bytes that moves the cursor from one position
+/// (column, row) to another one.
+///
+/// The candidates are carriage returns, line feeds and backspaces, the relative movements of
+/// [`MoveUp`](struct.MoveUp.html), [`MoveDown`](struct.MoveDown.html),
+/// [`MoveLeft`](struct.MoveLeft.html), [`MoveRight`](struct.MoveRight.html),
+/// [`MoveToNextLine`](struct.MoveToNextLine.html) and
+/// [`MoveToPreviousLine`](struct.MoveToPreviousLine.html), the absolute movements of
+/// [`MoveToColumn`](struct.MoveToColumn.html), [`MoveToRow`](struct.MoveToRow.html) and
+/// [`MoveTo`](struct.MoveTo.html), and combinations of them.
+///
+/// `reprint` is the text shown between the two positions on the row, if it's known and written
+/// with the current style. Printing it again is a candidate for moving to the right. Line feeds
+/// are always preceded by a carriage return, so that the movement doesn't depend on the output
+/// processing of the terminal.
+///
+/// The cursor mustn't be in the pending wrap state after writing to the last column, where the
+/// terminals disagree about its position.
+///
+/// # Examples
+///
+/// ```
+/// use crossterm::cursor::plan_movement;
+///
+/// assert_eq!(plan_movement((10, 5), (8, 5), None).as_str(), "\x08\x08");
+/// assert_eq!(plan_movement((10, 5), (0, 6), None).as_str(), "\r\n");
+/// assert_eq!(plan_movement((10, 5), (12, 5), Some("ab")).as_str(), "ab");
+/// assert_eq!(plan_movement((10, 5), (40, 20), None).as_str(), "\x1b[21;41H");
+/// ```
+pub fn plan_movement(from: (u16, u16), to: (u16, u16), reprint: Option<&str>) -> PlannedMovement {
+    let (from_column, from_row) = from;
+    let (column, row) = to;
+
+    let mut candidates = vec![absolute(column, row)];
+    if row == from_row {
+        candidates.push(horizontal(from_column, column));
+        if let Some(reprint) = reprint.filter(|_| column > from_column) {
+            candidates.push(reprint.to_string());
+        }
+    } else {
+        let rows = from_row.abs_diff(row);
+        let (relative, line) = if row > from_row {
+            (param(rows, 'B'), param(rows, 'E'))
+        } else {
+            (param(rows, 'A'), param(rows, 'F'))
+        };
+        let horizontal_from_column = horizontal(from_column, column);
+        let horizontal_from_start = horizontal(0, column);
+        // More line feeds than the bytes of the relative movement never win.
+        if row > from_row && usize::from(rows) < relative.len() {
+            let carriage_return = if from_column == 0 { "" } else { "\r" };
+            candidates.push(format!(
+                "{}{}{}",
+                carriage_return,
+                "\n".repeat(usize::from(rows)),
+                horizontal_from_start
+            ));
+        }
+        candidates.push(relative + &horizontal_from_column);
+        candidates.push(param(row + 1, 'd') + &horizontal_from_column);
+        candidates.push(line + &horizontal_from_start);
+    }
+
+    PlannedMovement {
+        ansi: shortest(candidates),
+        target: to,
+    }
+}
+
+/// Returns the shortest movement on the same row.
+fn horizontal(from: u16, to: u16) -> String {
+    if from == to {
+        return String::new();
+    }
+
+    let mut candidates = vec![param(to + 1, 'G')];
+    if to == 0 {
+        candidates.push("\r".to_string());
+    }
+    if to > from {
+        candidates.push(param(to - from, 'C'));
+    } else {
+        let relative = param(from - to, 'D');
+        // More backspaces than the bytes of the relative movement never win.
+        if usize::from(from - to) < relative.len() {
+            candidates.push("\x08".repeat(usize::from(from - to)));
+        }
+        candidates.push(relative);
+    }
+    shortest(candidates)
+}
+
+/// Returns the shortest candidate, the first one of the shortest ones.
+fn shortest(candidates: Vec<String>) -> String {
+    candidates
+        .into_iter()
+        .min_by_key(|candidate| candidate.len())
+        .unwrap()
+}
+
+/// Returns a CSI sequence with a single parameter, leaving out the default `1`.
+fn param(value: u16, action: char) -> String {
+    if value == 1 {
+        format!(csi!("{}"), action)
+    } else {
+        format!(csi!("{}{}"), value, action)
+    }
+}
+
+/// Returns the CUP sequence, leaving out the default `1`s.
+fn absolute(column: u16, row: u16) -> String {
+    match (column + 1, row + 1) {
+        (1, 1) => csi!("H").to_string(),
+        (1, row) => format!(csi!("{}H"), row),
+        (column, 1) => format!(csi!(";{}H"), column),
+        (column, row) => format!(csi!("{};{}H"), row, column),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::plan_movement;
+
+    fn plan(from: (u16, u16), to: (u16, u16)) -> String {
+        plan_movement(from, to, None).as_str().to_string()
+    }
+
+    #[test]
+    fn test_plan_movement_on_the_same_row() {
+        assert_eq!(plan((5, 3), (5, 3)), "");
+        assert_eq!(plan((5, 3), (6, 3)), "\x1b[C");
+        assert_eq!(plan((95, 3), (105, 3)), "\x1b[10C");
+        assert_eq!(plan((5, 3), (4, 3)), "\x08");
+        assert_eq!(plan((150, 3), (140, 3)), "\x1b[10D");
+        assert_eq!(plan((50, 3), (0, 3)), "\r");
+        assert_eq!(plan((500, 3), (2, 3)), "\x1b[3G");
+    }
+
+    #[test]
+    fn test_plan_movement_to_another_row() {
+        assert_eq!(plan((5, 3), (5, 4)), "\x1b[B");
+        assert_eq!(plan((5, 3), (5, 1)), "\x1b[2A");
+        assert_eq!(plan((5, 3), (0, 5)), "\r\n\n");
+        assert_eq!(plan((0, 3), (0, 5)), "\n\n");
+        assert_eq!(plan((5, 95), (0, 105)), "\x1b[10E");
+        assert_eq!(plan((5, 1005), (0, 995)), "\x1b[10F");
+        assert_eq!(plan((5, 300), (5, 0)), "\x1b[d");
+        assert_eq!(plan((500, 3), (0, 0)), "\x1b[H");
+        assert_eq!(plan((5, 3), (50, 100)), "\x1b[101;51H");
+    }
+
+    #[test]
+    fn test_plan_movement_reprints_short_gaps() {
+        assert_eq!(plan_movement((5, 3), (7, 3), Some("ab")).as_str(), "ab");
+        assert_eq!(
+            plan_movement((95, 3), (105, 3), Some("abcdefghij")).as_str(),
+            "\x1b[10C"
+        );
+        // The text isn't printed to move to the left or to another row.
+        assert_eq!(plan_movement((5, 3), (4, 3), Some("a")).as_str(), "\x08");
+        assert_eq!(
+            plan_movement((5, 3), (6, 4), Some("a")).as_str(),
+            "\x1b[5;7H"
+        );
+    }
+}
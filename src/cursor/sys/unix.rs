@@ -4,7 +4,10 @@ use std::{
 };
 
 use crate::{
-    event::{filter::CursorPositionFilter, poll_internal, read_internal, InternalEvent},
+    event::{
+        check_blocking_read, filter::CursorPositionFilter, poll_internal, read_internal,
+        timeout::PollTimeout, InternalEvent,
+    },
     terminal::{disable_raw_mode, enable_raw_mode, sys::is_raw_mode_enabled},
 };
 
@@ -14,7 +17,10 @@ use crate::{
 ///
 /// On unix systems, this function will block and possibly time out while
 /// [`crossterm::event::read`](crate::event::read) or [`crossterm::event::poll`](crate::event::poll) are being called.
+/// Use [`EventStream::cursor_position`](../event/struct.EventStream.html#method.cursor_position)
+/// in async code.
 pub fn position() -> io::Result<(u16, u16)> {
+    check_blocking_read()?;
     if is_raw_mode_enabled() {
         read_position_raw()
     } else {
@@ -35,8 +41,9 @@ fn read_position_raw() -> io::Result<(u16, u16)> {
     stdout.write_all(b"\x1B[6n")?;
     stdout.flush()?;
 
+    let timeout = PollTimeout::new(Some(Duration::from_millis(2000)));
     loop {
-        match poll_internal(Some(Duration::from_millis(2000)), &CursorPositionFilter) {
+        match poll_internal(timeout.leftover(), &CursorPositionFilter) {
             Ok(true) => {
                 if let Ok(InternalEvent::CursorPosition(x, y)) =
                     read_internal(&CursorPositionFilter)
@@ -44,6 +51,8 @@ fn read_position_raw() -> io::Result<(u16, u16)> {
                     return Ok((x, y));
                 }
             }
+            // Polling also stops early when the event reader is woken up, e.g. by an `EventStream`.
+            Ok(false) if !timeout.elapsed() => {}
             Ok(false) => {
                 return Err(Error::new(
                     ErrorKind::Other,
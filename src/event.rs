@@ -53,6 +53,8 @@
 //!             #[cfg(feature = "bracketed-paste")]
 //!             Event::Paste(data) => println!("{:?}", data),
 //!             Event::Resize(width, height) => println!("New size {}x{}", width, height),
+//!             Event::Resumed => println!("Resumed"),
+//!             Event::Signal(signal) => println!("{:?}", signal),
 //!         }
 //!     }
 //!     execute!(
@@ -98,6 +100,8 @@
 //!                 #[cfg(feature = "bracketed-paste")]
 //!                 Event::Paste(data) => println!("Pasted {:?}", data),
 //!                 Event::Resize(width, height) => println!("New size {}x{}", width, height),
+//!                 Event::Resumed => println!("Resumed"),
+//!                 Event::Signal(signal) => println!("{:?}", signal),
 //!             }
 //!         } else {
 //!             // Timeout expired and no `Event` is available
@@ -116,26 +120,35 @@
 //! Check the [examples](https://github.com/crossterm-rs/crossterm/tree/master/examples) folder for more of
 //! them (`event-*`).
 
+pub(crate) mod click;
 pub(crate) mod filter;
+pub(crate) mod keymap;
+pub(crate) mod notation;
 pub(crate) mod read;
 pub(crate) mod source;
 #[cfg(feature = "event-stream")]
 pub(crate) mod stream;
+pub(crate) mod subscription;
 pub(crate) mod sys;
 pub(crate) mod timeout;
 
+pub use click::{ClickCountConfig, ClickCounter};
+pub use keymap::{Keymap, KeymapMatch};
+pub use notation::KeyNotation;
 #[cfg(feature = "event-stream")]
 pub use stream::EventStream;
+pub use subscription::{subscribe, subscribe_consuming, Subscription};
 
 use crate::event::{
     filter::{EventFilter, Filter},
     read::InternalEventReader,
     timeout::PollTimeout,
 };
+use crate::terminal::modes::{self, Modes};
 use crate::{csi, Command};
 use parking_lot::{MappedMutexGuard, Mutex, MutexGuard};
 use std::fmt::{self, Display};
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
 use bitflags::bitflags;
 use std::hash::{Hash, Hasher};
@@ -198,6 +211,7 @@ fn try_lock_internal_event_reader_for(
 /// }
 /// ```
 pub fn poll(timeout: Duration) -> std::io::Result<bool> {
+    check_blocking_read()?;
     poll_internal(Some(timeout), &EventFilter)
 }
 
@@ -243,13 +257,124 @@ pub fn poll(timeout: Duration) -> std::io::Result<bool> {
 /// }
 /// ```
 pub fn read() -> std::io::Result<Event> {
-    match read_internal(&EventFilter)? {
-        InternalEvent::Event(event) => Ok(event),
+    read_timed().map(|(event, _)| event)
+}
+
+/// Reads a single [`Event`](enum.Event.html) together with the time it was read from the
+/// terminal.
+///
+/// This function behaves like [`read`](fn.read.html), but additionally returns the moment the
+/// event was parsed from the terminal input. Events can be buffered for a while before they're
+/// returned (e.g. when several events arrive at once), so this is more accurate than calling
+/// `Instant::now()` after `read` returns.
+///
+/// # Examples
+///
+/// ```no_run
+/// use std::io;
+///
+/// use crossterm::event::read_timed;
+///
+/// fn print_event_latency() -> io::Result<()> {
+///     loop {
+///         let (event, time) = read_timed()?;
+///         println!("{:?} handled {:?} after it was read", event, time.elapsed());
+///     }
+/// }
+/// ```
+pub fn read_timed() -> std::io::Result<(Event, Instant)> {
+    check_blocking_read()?;
+    match read_internal_timed(&EventFilter)? {
+        (InternalEvent::Event(event), time) => Ok((event, time)),
         #[cfg(unix)]
         _ => unreachable!(),
     }
 }
 
+/// Reports the given signals as [`Event::Signal`] events instead of running their default
+/// action, which terminates the process.
+///
+/// The events are read with [`read`](fn.read.html) or the
+/// [`EventStream`](struct.EventStream.html) like any other event, so the application can clean up
+/// and save its state in its event loop. Calling this function again enables more signals.
+///
+/// # Examples
+///
+/// ```no_run
+/// use std::io;
+///
+/// use crossterm::event::{enable_signal_events, read, Event, SignalKind};
+///
+/// fn run() -> io::Result<()> {
+///     enable_signal_events(&[SignalKind::Terminate, SignalKind::Hangup])?;
+///     loop {
+///         match read()? {
+///             Event::Signal(_) => {
+///                 // Save the state.
+///                 return Ok(());
+///             }
+///             event => println!("{:?}", event),
+///         }
+///     }
+/// }
+/// ```
+///
+/// # Platform-specific Notes
+///
+/// Signals are not supported on Windows, an error of the kind
+/// [`Unsupported`](std::io::ErrorKind::Unsupported) is returned there and no
+/// [`Event::Signal`] is ever read.
+pub fn enable_signal_events(signals: &[SignalKind]) -> std::io::Result<()> {
+    source::enable_signal_events(signals)
+}
+
+/// Runs the default action of the signals enabled with
+/// [`enable_signal_events`](fn.enable_signal_events.html) again.
+///
+/// Signals received before and not read yet are discarded. This does nothing on Windows.
+pub fn disable_signal_events() {
+    source::disable_signal_events()
+}
+
+/// Restores the terminal when the process is stopped with `SIGTSTP` (`Ctrl+Z` outside of raw
+/// mode, `kill -TSTP`) and reports an [`Event::Resumed`] when it's continued (UNIX only).
+///
+/// The stop is handled like [`suspend`](../terminal/fn.suspend.html) does, but only while events
+/// are read: the process isn't stopped until [`read`](fn.read.html), [`poll`](fn.poll.html) or the
+/// [`EventStream`](struct.EventStream.html) are called. By default, `SIGTSTP` and `SIGCONT` keep
+/// their default actions.
+#[cfg(unix)]
+pub fn enable_suspend_handling() -> std::io::Result<()> {
+    source::unix::enable_suspend_handling()
+}
+
+/// Runs the default actions of `SIGTSTP` and `SIGCONT` again after
+/// [`enable_suspend_handling`](fn.enable_suspend_handling.html).
+#[cfg(unix)]
+pub fn disable_suspend_handling() {
+    source::unix::disable_suspend_handling()
+}
+
+/// Sets how long a lone `ESC` byte is held before it's reported as an Esc key press.
+///
+/// Terminals send Alt+key as `ESC` followed by the key and most special keys as escape
+/// sequences starting with `ESC`. By default, a lone `ESC` at the end of the input read from the
+/// terminal is reported as an Esc key press right away, which breaks these sequences when they
+/// are split across reads (e.g. over slow SSH connections). With a non-zero timeout, a trailing
+/// `ESC` is kept until more input arrives or the timeout expires, similar to vim's `ttimeoutlen`.
+///
+/// Defaults to zero (no waiting).
+///
+/// # Platform-specific Notes
+///
+/// This only affects UNIX systems, Windows reports key events directly.
+pub fn set_escape_timeout(timeout: Duration) {
+    #[cfg(unix)]
+    source::unix::set_escape_timeout(timeout);
+    #[cfg(windows)]
+    let _ = timeout;
+}
+
 /// Polls to check if there are any `InternalEvent`s that can be read within the given duration.
 pub(crate) fn poll_internal<F>(timeout: Option<Duration>, filter: &F) -> std::io::Result<bool>
 where
@@ -268,6 +393,36 @@ where
     reader.poll(timeout, filter)
 }
 
+/// Fails while the terminal input is read by an `EventStream` registered with a reactor, which
+/// the blocking functions would race with.
+pub(crate) fn check_blocking_read() -> std::io::Result<()> {
+    #[cfg(all(unix, any(feature = "tokio", feature = "async-io")))]
+    if stream::native_stream_is_reading() {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::Other,
+            "The terminal input is read by an `EventStream`, use its methods instead.",
+        ));
+    }
+    Ok(())
+}
+
+/// Discards the input events read from the terminal but not returned yet.
+pub(crate) fn discard_input() {
+    let discard = || {
+        if let Some(reader) = INTERNAL_EVENT_READER.lock().as_mut() {
+            reader.discard_input();
+        }
+    };
+    // A helper thread of an `EventStream` may hold the reader while it waits for events.
+    #[cfg(feature = "event-stream")]
+    stream::pause_helper_threads(discard);
+    #[cfg(not(feature = "event-stream"))]
+    discard();
+
+    #[cfg(all(unix, any(feature = "tokio", feature = "async-io")))]
+    stream::discard_native_input();
+}
+
 /// Reads a single `InternalEvent`.
 pub(crate) fn read_internal<F>(filter: &F) -> std::io::Result<InternalEvent>
 where
@@ -277,6 +432,15 @@ where
     reader.read(filter)
 }
 
+/// Reads a single `InternalEvent` together with the time it was read.
+pub(crate) fn read_internal_timed<F>(filter: &F) -> std::io::Result<(InternalEvent, Instant)>
+where
+    F: Filter,
+{
+    let mut reader = lock_internal_event_reader();
+    reader.read_timed(filter)
+}
+
 bitflags! {
     /// Represents special flags that tell compatible terminals to add extra information to keyboard events.
     ///
@@ -330,6 +494,10 @@ impl Command for EnableMouseCapture {
         ))
     }
 
+    fn record_mode(&self) {
+        modes::set(Modes::MOUSE_CAPTURE, true);
+    }
+
     #[cfg(windows)]
     fn execute_winapi(&self) -> std::io::Result<()> {
         sys::windows::enable_mouse_capture()
@@ -359,6 +527,10 @@ impl Command for DisableMouseCapture {
         ))
     }
 
+    fn record_mode(&self) {
+        modes::set(Modes::MOUSE_CAPTURE, false);
+    }
+
     #[cfg(windows)]
     fn execute_winapi(&self) -> std::io::Result<()> {
         sys::windows::disable_mouse_capture()
@@ -383,6 +555,10 @@ impl Command for EnableFocusChange {
         f.write_str(csi!("?1004h"))
     }
 
+    fn record_mode(&self) {
+        modes::set(Modes::FOCUS_CHANGE, true);
+    }
+
     #[cfg(windows)]
     fn execute_winapi(&self) -> std::io::Result<()> {
         // Focus events are always enabled on Windows
@@ -399,6 +575,10 @@ impl Command for DisableFocusChange {
         f.write_str(csi!("?1004l"))
     }
 
+    fn record_mode(&self) {
+        modes::set(Modes::FOCUS_CHANGE, false);
+    }
+
     #[cfg(windows)]
     fn execute_winapi(&self) -> std::io::Result<()> {
         // Focus events can't be disabled on Windows
@@ -422,6 +602,10 @@ impl Command for EnableBracketedPaste {
         f.write_str(csi!("?2004h"))
     }
 
+    fn record_mode(&self) {
+        modes::set(Modes::BRACKETED_PASTE, true);
+    }
+
     #[cfg(windows)]
     fn execute_winapi(&self) -> std::io::Result<()> {
         Err(std::io::Error::new(
@@ -442,6 +626,10 @@ impl Command for DisableBracketedPaste {
         f.write_str(csi!("?2004l"))
     }
 
+    fn record_mode(&self) {
+        modes::set(Modes::BRACKETED_PASTE, false);
+    }
+
     #[cfg(windows)]
     fn execute_winapi(&self) -> std::io::Result<()> {
         Ok(())
@@ -493,6 +681,10 @@ impl Command for PushKeyboardEnhancementFlags {
         write!(f, "{}{}u", csi!(">"), self.0.bits())
     }
 
+    fn record_mode(&self) {
+        modes::push_keyboard_enhancement_flags(self.0.bits());
+    }
+
     #[cfg(windows)]
     fn execute_winapi(&self) -> std::io::Result<()> {
         use std::io;
@@ -522,6 +714,10 @@ impl Command for PopKeyboardEnhancementFlags {
         f.write_str(csi!("<1u"))
     }
 
+    fn record_mode(&self) {
+        modes::pop_keyboard_enhancement_flags();
+    }
+
     #[cfg(windows)]
     fn execute_winapi(&self) -> std::io::Result<()> {
         use std::io;
@@ -558,6 +754,32 @@ pub enum Event {
     /// An resize event with new dimensions after resize (columns, rows).
     /// **Note** that resize events can occur in batches.
     Resize(u16, u16),
+    /// The process was continued after it had been suspended (UNIX only).
+    ///
+    /// Only reported after [`enable_suspend_handling`](fn.enable_suspend_handling.html). The
+    /// terminal modes enabled with crossterm's commands have been restored, the screen should be
+    /// redrawn. See [`suspend`](../terminal/fn.suspend.html) for more information.
+    Resumed,
+    /// A signal was received (UNIX only).
+    ///
+    /// Only reported for the signals enabled with
+    /// [`enable_signal_events`](fn.enable_signal_events.html), which fails on Windows, so this
+    /// is never read there.
+    Signal(SignalKind),
+}
+
+/// Represents a signal that can be reported as an [`Event::Signal`].
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, PartialOrd, PartialEq, Eq, Clone, Copy, Hash)]
+pub enum SignalKind {
+    /// `SIGINT`, sent on `Ctrl+C` when the raw mode is disabled.
+    Interrupt,
+    /// `SIGTERM`, sent to ask the process to terminate.
+    Terminate,
+    /// `SIGHUP`, sent when the terminal is closed, e.g. when an SSH session ends.
+    Hangup,
+    /// `SIGQUIT`, sent on `Ctrl+\` when the raw mode is disabled.
+    Quit,
 }
 
 /// Represents a mouse event.
@@ -628,6 +850,18 @@ pub enum MouseButton {
     Right,
     /// Middle mouse button.
     Middle,
+    /// Back mouse button (X11 button 8, `XBUTTON1` on Windows).
+    Back,
+    /// Forward mouse button (X11 button 9, `XBUTTON2` on Windows).
+    Forward,
+    /// Extra mouse button 10.
+    ///
+    /// **Note:** this button is not reported on Windows.
+    Button10,
+    /// Extra mouse button 11.
+    ///
+    /// **Note:** this button is not reported on Windows.
+    Button11,
 }
 
 bitflags! {
@@ -1181,6 +1415,22 @@ pub(crate) enum InternalEvent {
     /// Attributes and architectural class of the terminal.
     #[cfg(unix)]
     PrimaryDeviceAttributes,
+    /// The setting of a DEC private mode (`mode`, `setting`), reported after a DECRQM query.
+    #[cfg(unix)]
+    ModeReport(u16, u8),
+}
+
+impl InternalEvent {
+    /// Returns `true` for the events of the user's input, which are discarded by
+    /// [`flush_input`](../terminal/fn.flush_input.html).
+    pub(crate) fn is_input(&self) -> bool {
+        match self {
+            InternalEvent::Event(Event::Key(_) | Event::Mouse(_)) => true,
+            #[cfg(feature = "bracketed-paste")]
+            InternalEvent::Event(Event::Paste(_)) => true,
+            _ => false,
+        }
+    }
 }
 
 #[cfg(test)]
@@ -0,0 +1,233 @@
+use std::{
+    io,
+    time::{Duration, Instant},
+};
+
+use crate::event::{read_timed, Event, MouseButton, MouseEvent, MouseEventKind};
+
+/// Configures how consecutive mouse clicks are grouped together.
+///
+/// See [`ClickCounter`] for more information.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ClickCountConfig {
+    /// The maximum time between two presses of the same button for them to count as consecutive
+    /// clicks.
+    pub interval: Duration,
+    /// The maximum distance (in cells, on either axis) between two presses of the same button
+    /// for them to count as consecutive clicks.
+    pub max_distance: u16,
+}
+
+impl Default for ClickCountConfig {
+    fn default() -> Self {
+        ClickCountConfig {
+            interval: Duration::from_millis(500),
+            max_distance: 1,
+        }
+    }
+}
+
+/// The last press of a mouse button.
+#[derive(Debug, Clone, Copy)]
+struct Press {
+    button: MouseButton,
+    column: u16,
+    row: u16,
+    time: Instant,
+    count: u8,
+}
+
+/// Counts the consecutive clicks of mouse events.
+///
+/// The count of a [`MouseEventKind::Down`] or [`MouseEventKind::Up`] event is the number of
+/// consecutive clicks of the same button it belongs to (`1` for a single click, `2` for a double
+/// click, etc.). A press counts as a follow-up click if it uses the same button as the previous
+/// press, happens within [`ClickCountConfig::interval`] of it and is at most
+/// [`ClickCountConfig::max_distance`] cells away from it.
+///
+/// Use [`ClickCounter::read`] instead of [`read`](fn.read.html), or pass the events of an
+/// [`EventStream`](struct.EventStream.html) to [`ClickCounter::count`] as they're received. Every
+/// counter keeps its own state, so the events should be passed to one counter in the order they
+/// were read.
+///
+/// # Examples
+///
+/// ```no_run
+/// use std::io;
+///
+/// use crossterm::event::{ClickCountConfig, ClickCounter, Event, MouseEventKind};
+///
+/// fn print_double_clicks() -> io::Result<()> {
+///     let mut counter = ClickCounter::new(ClickCountConfig::default());
+///     loop {
+///         if let (Event::Mouse(event), 2) = counter.read()? {
+///             if let MouseEventKind::Down(button) = event.kind {
+///                 println!("Double click with {:?}", button);
+///             }
+///         }
+///     }
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct ClickCounter {
+    config: ClickCountConfig,
+    last_press: Option<Press>,
+}
+
+impl Default for ClickCounter {
+    fn default() -> Self {
+        ClickCounter::new(ClickCountConfig::default())
+    }
+}
+
+impl ClickCounter {
+    /// Creates a counter grouping the clicks with the given configuration.
+    pub fn new(config: ClickCountConfig) -> ClickCounter {
+        ClickCounter {
+            config,
+            last_press: None,
+        }
+    }
+
+    /// Reads an event with [`read_timed`](fn.read_timed.html) and returns it together with its
+    /// click count, which is `0` for all other events.
+    pub fn read(&mut self) -> io::Result<(Event, u8)> {
+        let (event, time) = read_timed()?;
+        let count = match &event {
+            Event::Mouse(mouse_event) => self.count(mouse_event, time),
+            _ => 0,
+        };
+        Ok((event, count))
+    }
+
+    /// Counts the clicks of a mouse event read at the given time and returns its click count,
+    /// which is `0` for other mouse events than presses and releases.
+    pub fn count(&mut self, event: &MouseEvent, time: Instant) -> u8 {
+        match event.kind {
+            MouseEventKind::Down(button) => {
+                let count = match self.last_press {
+                    Some(press) if self.is_consecutive(&press, button, event, time) => {
+                        press.count.saturating_add(1)
+                    }
+                    _ => 1,
+                };
+
+                self.last_press = Some(Press {
+                    button,
+                    column: event.column,
+                    row: event.row,
+                    time,
+                    count,
+                });
+                count
+            }
+            MouseEventKind::Up(button) => {
+                // The release belongs to the last press of the same button. Some terminals do not
+                // report which button was released, so we fall back to a single click if we
+                // can't match it.
+                match self.last_press {
+                    Some(press) if press.button == button => press.count,
+                    _ => 1,
+                }
+            }
+            _ => 0,
+        }
+    }
+
+    fn is_consecutive(
+        &self,
+        press: &Press,
+        button: MouseButton,
+        event: &MouseEvent,
+        time: Instant,
+    ) -> bool {
+        press.button == button
+            && time.saturating_duration_since(press.time) <= self.config.interval
+            && press.column.abs_diff(event.column) <= self.config.max_distance
+            && press.row.abs_diff(event.row) <= self.config.max_distance
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, Instant};
+
+    use super::{ClickCountConfig, ClickCounter};
+    use crate::event::{KeyModifiers, MouseButton, MouseEvent, MouseEventKind};
+
+    fn mouse_event(kind: MouseEventKind, column: u16, row: u16) -> MouseEvent {
+        MouseEvent {
+            kind,
+            column,
+            row,
+            modifiers: KeyModifiers::NONE,
+        }
+    }
+
+    fn count(counter: &mut ClickCounter, kind: MouseEventKind, column: u16, time: Instant) -> u8 {
+        counter.count(&mouse_event(kind, column, 0), time)
+    }
+
+    #[test]
+    fn test_consecutive_clicks_are_counted() {
+        let mut counter = ClickCounter::new(ClickCountConfig::default());
+        let start = Instant::now();
+        let down = MouseEventKind::Down(MouseButton::Left);
+        let up = MouseEventKind::Up(MouseButton::Left);
+
+        assert_eq!(count(&mut counter, down, 5, start), 1);
+        assert_eq!(count(&mut counter, up, 5, start), 1);
+        let start = start + Duration::from_millis(100);
+        assert_eq!(count(&mut counter, down, 5, start), 2);
+        assert_eq!(count(&mut counter, up, 5, start), 2);
+        let start = start + Duration::from_millis(100);
+        assert_eq!(count(&mut counter, down, 6, start), 3);
+        assert_eq!(count(&mut counter, up, 6, start), 3);
+    }
+
+    #[test]
+    fn test_click_count_resets_after_interval() {
+        let mut counter = ClickCounter::new(ClickCountConfig::default());
+        let start = Instant::now();
+        let down = MouseEventKind::Down(MouseButton::Left);
+
+        assert_eq!(count(&mut counter, down, 5, start), 1);
+        assert_eq!(
+            count(&mut counter, down, 5, start + Duration::from_millis(600)),
+            1
+        );
+    }
+
+    #[test]
+    fn test_click_count_resets_after_distance() {
+        let mut counter = ClickCounter::new(ClickCountConfig::default());
+        let start = Instant::now();
+        let down = MouseEventKind::Down(MouseButton::Left);
+
+        assert_eq!(count(&mut counter, down, 5, start), 1);
+        assert_eq!(count(&mut counter, down, 7, start), 1);
+    }
+
+    #[test]
+    fn test_click_count_resets_on_button_change() {
+        let mut counter = ClickCounter::new(ClickCountConfig::default());
+        let start = Instant::now();
+        let left_down = MouseEventKind::Down(MouseButton::Left);
+        let right_down = MouseEventKind::Down(MouseButton::Right);
+        let left_up = MouseEventKind::Up(MouseButton::Left);
+
+        assert_eq!(count(&mut counter, left_down, 5, start), 1);
+        assert_eq!(count(&mut counter, right_down, 5, start), 1);
+        assert_eq!(count(&mut counter, left_up, 5, start), 1);
+        assert_eq!(count(&mut counter, left_down, 5, start), 1);
+    }
+
+    #[test]
+    fn test_other_mouse_events_are_not_counted() {
+        let mut counter = ClickCounter::new(ClickCountConfig::default());
+        let start = Instant::now();
+
+        assert_eq!(count(&mut counter, MouseEventKind::Moved, 5, start), 0);
+        assert_eq!(count(&mut counter, MouseEventKind::ScrollUp, 5, start), 0);
+    }
+}
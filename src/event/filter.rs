@@ -46,6 +46,22 @@ impl Filter for PrimaryDeviceAttributesFilter {
     }
 }
 
+#[cfg(unix)]
+#[derive(Debug, Clone)]
+pub(crate) struct ModeReportFilter;
+
+#[cfg(unix)]
+impl Filter for ModeReportFilter {
+    fn eval(&self, event: &InternalEvent) -> bool {
+        // Terminals that don't support DECRQM only reply to the PrimaryDeviceAttributes query
+        // sent after it.
+        matches!(
+            *event,
+            InternalEvent::ModeReport(_, _) | InternalEvent::PrimaryDeviceAttributes
+        )
+    }
+}
+
 #[derive(Debug, Clone)]
 pub(crate) struct EventFilter;
 
@@ -75,7 +91,8 @@ impl Filter for InternalEventFilter {
 mod tests {
     use super::{
         super::Event, CursorPositionFilter, EventFilter, Filter, InternalEvent,
-        InternalEventFilter, KeyboardEnhancementFlagsFilter, PrimaryDeviceAttributesFilter,
+        InternalEventFilter, KeyboardEnhancementFlagsFilter, ModeReportFilter,
+        PrimaryDeviceAttributesFilter,
     };
 
     #[test]
@@ -101,6 +118,13 @@ mod tests {
         assert!(PrimaryDeviceAttributesFilter.eval(&InternalEvent::PrimaryDeviceAttributes));
     }
 
+    #[test]
+    fn test_mode_report_filter_filters_mode_report() {
+        assert!(!ModeReportFilter.eval(&InternalEvent::Event(Event::Resize(10, 10))));
+        assert!(ModeReportFilter.eval(&InternalEvent::ModeReport(2027, 1)));
+        assert!(ModeReportFilter.eval(&InternalEvent::PrimaryDeviceAttributes));
+    }
+
     #[test]
     fn test_event_filter_filters_events() {
         assert!(EventFilter.eval(&InternalEvent::Event(Event::Resize(10, 10))));
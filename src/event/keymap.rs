@@ -0,0 +1,527 @@
+use std::{
+    collections::{HashMap, VecDeque},
+    io,
+    time::{Duration, Instant},
+};
+
+use crate::event::{Event, KeyCode, KeyEvent, KeyEventKind, KeyEventState, KeyNotation};
+
+/// The result of feeding an event to a [`Keymap`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum KeymapMatch<A> {
+    /// The keys read so far are the start of at least one binding, more keys are needed.
+    Pending,
+    /// The keys read so far match a binding, but they're also the start of longer bindings.
+    ///
+    /// The keymap waits for the next key or [`Keymap::flush`] to decide.
+    Ambiguous,
+    /// The keys read so far match the binding of the action.
+    Matched(A),
+    /// The keys read so far don't match any binding.
+    Unmatched(Vec<KeyEvent>),
+    /// The event doesn't affect the keymap, e.g. a mouse event or a key release.
+    Ignored,
+}
+
+/// Maps key sequences like `g g` or `C-x C-s` to actions.
+///
+/// Events are fed to the keymap one by one with [`Keymap::feed`], which tells whether a binding
+/// matched. When the read keys match a binding that is also the start of a longer one
+/// ([`KeymapMatch::Ambiguous`]), or when the user stops typing in the middle of a sequence,
+/// [`Keymap::pending_timeout`] tells how long to wait for the next key before calling
+/// [`Keymap::flush`].
+///
+/// Key releases are ignored, key repeats are handled like presses. Presses of modifier keys
+/// (reported with [`KeyboardEnhancementFlags::REPORT_ALL_KEYS_AS_ESCAPE_CODES`]) are ignored
+/// unless they continue a bound sequence.
+///
+/// [`KeyboardEnhancementFlags::REPORT_ALL_KEYS_AS_ESCAPE_CODES`]: struct.KeyboardEnhancementFlags.html#associatedconstant.REPORT_ALL_KEYS_AS_ESCAPE_CODES
+///
+/// # Examples
+///
+/// ```no_run
+/// use std::io;
+///
+/// use crossterm::event::{poll, read, Keymap, KeymapMatch, KeyNotation};
+///
+/// #[derive(Clone)]
+/// enum Action {
+///     Top,
+///     Save,
+/// }
+///
+/// fn main() -> io::Result<()> {
+///     let mut keymap = Keymap::new();
+///     keymap.bind_notation("g g", KeyNotation::Canonical, Action::Top)?;
+///     keymap.bind_notation("ctrl+x ctrl+s", KeyNotation::Canonical, Action::Save)?;
+///
+///     loop {
+///         let result = match keymap.pending_timeout() {
+///             Some(timeout) if !poll(timeout)? => keymap.flush(),
+///             _ => keymap.feed(&read()?),
+///         };
+///
+///         match result {
+///             KeymapMatch::Matched(Action::Top) => println!("top"),
+///             KeymapMatch::Matched(Action::Save) => println!("save"),
+///             KeymapMatch::Unmatched(keys) => println!("unbound keys: {:?}", keys),
+///             _ => {}
+///         }
+///     }
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct Keymap<A> {
+    root: Node<A>,
+    leader: Option<KeyEvent>,
+    timeout: Option<Duration>,
+    // The keys read since the last match and the time the last of them was read.
+    pending: Vec<KeyEvent>,
+    pending_since: Option<Instant>,
+    // Results that are ready, but couldn't be returned yet.
+    ready: VecDeque<KeymapMatch<A>>,
+}
+
+#[derive(Debug, Clone)]
+struct Node<A> {
+    action: Option<A>,
+    children: HashMap<KeyEvent, Node<A>>,
+}
+
+impl<A> Default for Node<A> {
+    fn default() -> Self {
+        Node {
+            action: None,
+            children: HashMap::new(),
+        }
+    }
+}
+
+impl<A> Default for Keymap<A> {
+    fn default() -> Self {
+        Keymap {
+            root: Node::default(),
+            leader: None,
+            timeout: Some(Duration::from_secs(1)),
+            pending: Vec::new(),
+            pending_since: None,
+            ready: VecDeque::new(),
+        }
+    }
+}
+
+impl<A> Keymap<A> {
+    /// Creates an empty keymap with a timeout of one second.
+    pub fn new() -> Keymap<A> {
+        Keymap::default()
+    }
+
+    /// Sets how long to wait for the next key of a pending sequence, `None` waits forever.
+    pub fn set_timeout(&mut self, timeout: Option<Duration>) {
+        self.timeout = timeout;
+    }
+
+    /// Sets the key used for `<leader>` in [`Keymap::bind_notation`].
+    ///
+    /// The leader key has to be set before binding sequences that use it.
+    pub fn set_leader(&mut self, leader: KeyEvent) {
+        self.leader = Some(normalize(leader));
+    }
+
+    /// Binds a sequence of keys to an action, replacing the previous action of the sequence.
+    ///
+    /// Binding an empty sequence does nothing.
+    pub fn bind(&mut self, keys: impl IntoIterator<Item = KeyEvent>, action: A) {
+        let mut keys = keys.into_iter().peekable();
+        if keys.peek().is_none() {
+            return;
+        }
+
+        let mut node = &mut self.root;
+        for key in keys {
+            node = node.children.entry(normalize(key)).or_default();
+        }
+        node.action = Some(action);
+    }
+
+    /// Binds a sequence of keys written in the given notation to an action.
+    ///
+    /// The keys of the sequence are separated by whitespace, e.g. `ctrl+x ctrl+s` or `C-x C-s`.
+    /// In the Vim notation the keys can also follow each other directly, e.g. `gg` or `<C-w>j`.
+    /// `<leader>` stands for the key set with [`Keymap::set_leader`].
+    pub fn bind_notation(
+        &mut self,
+        sequence: &str,
+        notation: KeyNotation,
+        action: A,
+    ) -> io::Result<()> {
+        let keys = match notation {
+            KeyNotation::Vim => split_vim_sequence(sequence)
+                .into_iter()
+                .map(|key| self.parse_key(key, notation))
+                .collect::<io::Result<Vec<_>>>()?,
+            _ => sequence
+                .split_whitespace()
+                .map(|key| self.parse_key(key, notation))
+                .collect::<io::Result<Vec<_>>>()?,
+        };
+
+        if keys.is_empty() {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "The key sequence is empty.",
+            ));
+        }
+
+        self.bind(keys, action);
+        Ok(())
+    }
+
+    fn parse_key(&self, key: &str, notation: KeyNotation) -> io::Result<KeyEvent> {
+        if key.eq_ignore_ascii_case("<leader>") {
+            return self.leader.ok_or_else(|| {
+                io::Error::new(io::ErrorKind::InvalidInput, "No leader key is set.")
+            });
+        }
+        KeyEvent::from_notation(key, notation)
+    }
+
+    /// Returns the keys read since the last match, e.g. to show them in a status line.
+    pub fn pending_keys(&self) -> &[KeyEvent] {
+        &self.pending
+    }
+
+    /// Returns how long to wait for the next event before calling [`Keymap::flush`], or `None` if
+    /// there's nothing to flush.
+    pub fn pending_timeout(&self) -> Option<Duration> {
+        if !self.ready.is_empty() {
+            return Some(Duration::ZERO);
+        }
+        let pending_since = self.pending_since?;
+        self.timeout
+            .map(|timeout| timeout.saturating_sub(pending_since.elapsed()))
+    }
+
+    fn lookup(&self, keys: &[KeyEvent]) -> Option<&Node<A>> {
+        keys.iter()
+            .try_fold(&self.root, |node, key| node.children.get(key))
+    }
+
+    fn reset(&mut self) -> Vec<KeyEvent> {
+        self.pending_since = None;
+        std::mem::take(&mut self.pending)
+    }
+}
+
+impl<A: Clone> Keymap<A> {
+    /// Feeds an event to the keymap.
+    ///
+    /// Events other than key events are ignored.
+    pub fn feed(&mut self, event: &Event) -> KeymapMatch<A> {
+        match event {
+            Event::Key(key) => self.feed_key(*key),
+            _ => KeymapMatch::Ignored,
+        }
+    }
+
+    /// Feeds a key event to the keymap.
+    pub fn feed_key(&mut self, key: KeyEvent) -> KeymapMatch<A> {
+        if key.kind == KeyEventKind::Release {
+            return KeymapMatch::Ignored;
+        }
+
+        // Results that are still queued have to be returned first.
+        let queued = !self.ready.is_empty();
+        let result = self.advance(normalize(key));
+        match result {
+            KeymapMatch::Matched(_) | KeymapMatch::Unmatched(_) if queued => {
+                self.ready.push_back(result);
+                self.ready.pop_front().unwrap()
+            }
+            result => result,
+        }
+    }
+
+    fn advance(&mut self, key: KeyEvent) -> KeymapMatch<A> {
+        self.pending.push(key);
+
+        match self.lookup(&self.pending) {
+            Some(node) if node.children.is_empty() => {
+                let action = node.action.clone().expect("leaves always have an action");
+                self.reset();
+                KeymapMatch::Matched(action)
+            }
+            Some(node) => {
+                let ambiguous = node.action.is_some();
+                self.pending_since = Some(Instant::now());
+                if ambiguous {
+                    KeymapMatch::Ambiguous
+                } else {
+                    KeymapMatch::Pending
+                }
+            }
+            None => {
+                self.pending.pop();
+                if matches!(key.code, KeyCode::Modifier(_)) {
+                    return KeymapMatch::Ignored;
+                }
+
+                let prefix_action = self
+                    .lookup(&self.pending)
+                    .and_then(|node| node.action.clone());
+                let mut keys = self.reset();
+                match prefix_action {
+                    Some(action) => {
+                        // The read keys matched a shorter binding, the new key starts a new
+                        // sequence.
+                        let result = self.advance(key);
+                        if let KeymapMatch::Matched(_) | KeymapMatch::Unmatched(_) = result {
+                            self.ready.push_back(result);
+                        }
+                        KeymapMatch::Matched(action)
+                    }
+                    None => {
+                        keys.push(key);
+                        KeymapMatch::Unmatched(keys)
+                    }
+                }
+            }
+        }
+    }
+
+    /// Resolves the pending keys without waiting for more, usually after
+    /// [`Keymap::pending_timeout`] elapsed.
+    ///
+    /// Returns [`KeymapMatch::Matched`] if the pending keys match a binding,
+    /// [`KeymapMatch::Unmatched`] if they don't and [`KeymapMatch::Ignored`] if there are no
+    /// pending keys.
+    pub fn flush(&mut self) -> KeymapMatch<A> {
+        if let Some(result) = self.ready.pop_front() {
+            return result;
+        }
+        if self.pending.is_empty() {
+            return KeymapMatch::Ignored;
+        }
+
+        let action = self
+            .lookup(&self.pending)
+            .and_then(|node| node.action.clone());
+        let keys = self.reset();
+        match action {
+            Some(action) => KeymapMatch::Matched(action),
+            None => KeymapMatch::Unmatched(keys),
+        }
+    }
+}
+
+// Keys are stored as presses without state, so that presses, repeats and keys with e.g. the
+// keypad state all match.
+fn normalize(key: KeyEvent) -> KeyEvent {
+    let mut key = key.normalize_notation();
+    key.kind = KeyEventKind::Press;
+    key.state = KeyEventState::NONE;
+    key
+}
+
+// Splits a sequence in Vim's notation into keys, e.g. `<C-w>j` into `<C-w>` and `j`.
+fn split_vim_sequence(sequence: &str) -> Vec<&str> {
+    let mut keys = Vec::new();
+    let mut rest = sequence.trim_start();
+
+    while let Some(c) = rest.chars().next() {
+        let mut len = c.len_utf8();
+        if c == '<' {
+            // Take the shortest `<...>` that is a valid key, `<C->>` contains two `>`.
+            if let Some(end) = rest
+                .match_indices('>')
+                .map(|(index, _)| index + 1)
+                .find(|&end| {
+                    let key = &rest[..end];
+                    key.eq_ignore_ascii_case("<leader>")
+                        || KeyEvent::from_notation(key, KeyNotation::Vim).is_ok()
+                })
+            {
+                len = end;
+            }
+        }
+        keys.push(&rest[..len]);
+        rest = rest[len..].trim_start();
+    }
+
+    keys
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::{Keymap, KeymapMatch};
+    use crate::event::{
+        Event, KeyCode, KeyEvent, KeyEventKind, KeyEventState, KeyModifiers, KeyNotation,
+        ModifierKeyCode,
+    };
+
+    fn key(c: char) -> KeyEvent {
+        KeyEvent::from(KeyCode::Char(c))
+    }
+
+    fn ctrl(c: char) -> KeyEvent {
+        KeyEvent::new(KeyCode::Char(c), KeyModifiers::CONTROL)
+    }
+
+    fn keymap() -> Keymap<&'static str> {
+        let mut keymap = Keymap::new();
+        keymap
+            .bind_notation("g g", KeyNotation::Canonical, "top")
+            .unwrap();
+        keymap
+            .bind_notation("C-x C-s", KeyNotation::Emacs, "save")
+            .unwrap();
+        keymap
+            .bind_notation("d", KeyNotation::Vim, "delete")
+            .unwrap();
+        keymap
+            .bind_notation("dd", KeyNotation::Vim, "delete line")
+            .unwrap();
+        keymap
+    }
+
+    #[test]
+    fn test_match_sequence() {
+        let mut keymap = keymap();
+
+        assert_eq!(keymap.feed_key(key('g')), KeymapMatch::Pending);
+        assert_eq!(keymap.pending_keys(), &[key('g')]);
+        assert_eq!(keymap.feed_key(key('g')), KeymapMatch::Matched("top"));
+        assert!(keymap.pending_keys().is_empty());
+
+        assert_eq!(keymap.feed_key(ctrl('x')), KeymapMatch::Pending);
+        assert_eq!(keymap.feed_key(ctrl('s')), KeymapMatch::Matched("save"));
+    }
+
+    #[test]
+    fn test_unmatched_sequence() {
+        let mut keymap = keymap();
+
+        assert_eq!(
+            keymap.feed_key(key('x')),
+            KeymapMatch::Unmatched(vec![key('x')])
+        );
+        assert_eq!(keymap.feed_key(key('g')), KeymapMatch::Pending);
+        assert_eq!(
+            keymap.feed_key(key('x')),
+            KeymapMatch::Unmatched(vec![key('g'), key('x')])
+        );
+        assert_eq!(keymap.pending_timeout(), None);
+    }
+
+    #[test]
+    fn test_ambiguous_sequence() {
+        let mut keymap = keymap();
+
+        assert_eq!(keymap.feed_key(key('d')), KeymapMatch::Ambiguous);
+        assert_eq!(
+            keymap.feed_key(key('d')),
+            KeymapMatch::Matched("delete line")
+        );
+
+        // The timeout elapsed.
+        assert_eq!(keymap.feed_key(key('d')), KeymapMatch::Ambiguous);
+        assert!(keymap.pending_timeout().unwrap() <= Duration::from_secs(1));
+        assert_eq!(keymap.flush(), KeymapMatch::Matched("delete"));
+        assert_eq!(keymap.flush(), KeymapMatch::Ignored);
+
+        // A key that doesn't continue the sequence.
+        assert_eq!(keymap.feed_key(key('d')), KeymapMatch::Ambiguous);
+        assert_eq!(keymap.feed_key(key('g')), KeymapMatch::Matched("delete"));
+        assert_eq!(keymap.feed_key(key('g')), KeymapMatch::Matched("top"));
+    }
+
+    #[test]
+    fn test_ambiguous_sequence_followed_by_unbound_key() {
+        let mut keymap = keymap();
+
+        assert_eq!(keymap.feed_key(key('d')), KeymapMatch::Ambiguous);
+        assert_eq!(keymap.feed_key(key('x')), KeymapMatch::Matched("delete"));
+        assert_eq!(keymap.pending_timeout(), Some(Duration::ZERO));
+        assert_eq!(keymap.flush(), KeymapMatch::Unmatched(vec![key('x')]));
+        assert_eq!(keymap.pending_timeout(), None);
+    }
+
+    #[test]
+    fn test_flush_pending_sequence() {
+        let mut keymap = keymap();
+
+        assert_eq!(keymap.feed_key(ctrl('x')), KeymapMatch::Pending);
+        assert_eq!(keymap.flush(), KeymapMatch::Unmatched(vec![ctrl('x')]));
+
+        keymap.set_timeout(None);
+        assert_eq!(keymap.feed_key(ctrl('x')), KeymapMatch::Pending);
+        assert_eq!(keymap.pending_timeout(), None);
+    }
+
+    #[test]
+    fn test_key_kinds() {
+        let mut keymap = keymap();
+        let release = KeyEvent::new_with_kind(
+            KeyCode::Char('g'),
+            KeyModifiers::NONE,
+            KeyEventKind::Release,
+        );
+        let repeat = KeyEvent::new_with_kind_and_state(
+            KeyCode::Char('g'),
+            KeyModifiers::NONE,
+            KeyEventKind::Repeat,
+            KeyEventState::KEYPAD,
+        );
+        let shift = KeyEvent::new(
+            KeyCode::Modifier(ModifierKeyCode::LeftShift),
+            KeyModifiers::SHIFT,
+        );
+
+        assert_eq!(keymap.feed_key(key('g')), KeymapMatch::Pending);
+        assert_eq!(keymap.feed_key(release), KeymapMatch::Ignored);
+        assert_eq!(keymap.feed_key(shift), KeymapMatch::Ignored);
+        assert_eq!(keymap.feed(&Event::FocusGained), KeymapMatch::Ignored);
+        assert_eq!(keymap.feed_key(repeat), KeymapMatch::Matched("top"));
+    }
+
+    #[test]
+    fn test_leader() {
+        let mut keymap = Keymap::new();
+        assert!(keymap
+            .bind_notation("<leader> f", KeyNotation::Canonical, "find")
+            .is_err());
+
+        keymap.set_leader(key(' '));
+        keymap
+            .bind_notation("<leader> f", KeyNotation::Canonical, "find")
+            .unwrap();
+        keymap
+            .bind_notation("<Leader>w<C->>", KeyNotation::Vim, "widen")
+            .unwrap();
+
+        assert_eq!(keymap.feed_key(key(' ')), KeymapMatch::Pending);
+        assert_eq!(keymap.feed_key(key('f')), KeymapMatch::Matched("find"));
+        assert_eq!(keymap.feed_key(key(' ')), KeymapMatch::Pending);
+        assert_eq!(keymap.feed_key(key('w')), KeymapMatch::Pending);
+        assert_eq!(keymap.feed_key(ctrl('>')), KeymapMatch::Matched("widen"));
+    }
+
+    #[test]
+    fn test_bind_shifted_keys() {
+        let mut keymap = Keymap::new();
+        keymap
+            .bind_notation("shift+g", KeyNotation::Canonical, "bottom")
+            .unwrap();
+        keymap
+            .bind_notation("shift+tab", KeyNotation::Canonical, "previous")
+            .unwrap();
+
+        assert_eq!(keymap.feed_key(key('G')), KeymapMatch::Matched("bottom"));
+        assert_eq!(
+            keymap.feed_key(KeyEvent::new(KeyCode::BackTab, KeyModifiers::SHIFT)),
+            KeymapMatch::Matched("previous")
+        );
+    }
+}
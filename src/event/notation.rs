@@ -0,0 +1,679 @@
+use std::{fmt, io, str::FromStr};
+
+use crate::event::{KeyCode, KeyEvent, KeyModifiers, MediaKeyCode, ModifierKeyCode};
+
+/// A textual notation for key events, e.g. for key bindings in configuration files.
+///
+/// The [`Display`](std::fmt::Display) and [`FromStr`] implementations of [`KeyEvent`] use the
+/// [`KeyNotation::Canonical`] notation, the other notations are available through
+/// [`KeyEvent::from_notation`] and [`KeyEvent::to_notation`].
+///
+/// Only the key code and the modifiers are part of the notation. Parsed key events are
+/// normalized the same way [`KeyEvent`]'s `PartialEq` implementation does, so they compare equal
+/// to the events returned by [`read`](fn.read.html) (e.g. `shift+a` and `shift+A` both result in
+/// `KeyCode::Char('A')` with the `SHIFT` modifier, `shift+tab` results in `KeyCode::BackTab`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum KeyNotation {
+    /// Modifiers and the key joined by `+`, e.g. `ctrl+shift+a`, `alt+enter` or `f5`.
+    ///
+    /// Modifiers are `ctrl`, `alt`, `shift`, `super`, `hyper` and `meta`. Keys are either a
+    /// single character or a name like `enter`, `esc`, `space`, `pageup`, `f1`, `mediaplay` or
+    /// `leftshift`. Names and modifiers are case-insensitive, single characters are not.
+    Canonical,
+    /// Vim's key notation, e.g. `a`, `<C-x>`, `<C-S-a>`, `<CR>` or `<F5>`.
+    ///
+    /// Modifiers are `C-` (ctrl), `A-` or `M-` (alt), `S-` (shift), `D-` (super), `H-` (hyper)
+    /// and `T-` (meta).
+    Vim,
+    /// Emacs' key notation, e.g. `a`, `C-x`, `M-RET`, `C-<f5>` or `<prior>`.
+    ///
+    /// Modifiers are `C-` (ctrl), `M-` (alt), `S-` (shift), `s-` (super), `H-` (hyper) and
+    /// `A-` (meta).
+    Emacs,
+}
+
+impl KeyNotation {
+    fn modifiers(self) -> &'static [(KeyModifiers, &'static str)] {
+        match self {
+            KeyNotation::Canonical => &[
+                (KeyModifiers::CONTROL, "ctrl"),
+                (KeyModifiers::ALT, "alt"),
+                (KeyModifiers::SHIFT, "shift"),
+                (KeyModifiers::SUPER, "super"),
+                (KeyModifiers::HYPER, "hyper"),
+                (KeyModifiers::META, "meta"),
+            ],
+            KeyNotation::Vim => &[
+                (KeyModifiers::CONTROL, "C"),
+                (KeyModifiers::ALT, "A"),
+                (KeyModifiers::SHIFT, "S"),
+                (KeyModifiers::SUPER, "D"),
+                (KeyModifiers::HYPER, "H"),
+                (KeyModifiers::META, "T"),
+            ],
+            KeyNotation::Emacs => &[
+                (KeyModifiers::CONTROL, "C"),
+                (KeyModifiers::ALT, "M"),
+                (KeyModifiers::SHIFT, "S"),
+                (KeyModifiers::SUPER, "s"),
+                (KeyModifiers::HYPER, "H"),
+                (KeyModifiers::META, "A"),
+            ],
+        }
+    }
+}
+
+impl KeyEvent {
+    /// Parses a key event written in the given notation.
+    ///
+    /// See [`KeyNotation`] for the supported notations.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use crossterm::event::{KeyCode, KeyEvent, KeyModifiers, KeyNotation};
+    ///
+    /// let ctrl_x = KeyEvent::new(KeyCode::Char('x'), KeyModifiers::CONTROL);
+    ///
+    /// assert_eq!("ctrl+x".parse::<KeyEvent>().unwrap(), ctrl_x);
+    /// assert_eq!(KeyEvent::from_notation("<C-x>", KeyNotation::Vim).unwrap(), ctrl_x);
+    /// assert_eq!(KeyEvent::from_notation("C-x", KeyNotation::Emacs).unwrap(), ctrl_x);
+    /// ```
+    pub fn from_notation(s: &str, notation: KeyNotation) -> io::Result<KeyEvent> {
+        let event = match notation {
+            KeyNotation::Canonical => parse_canonical(s),
+            KeyNotation::Vim => parse_vim(s),
+            KeyNotation::Emacs => parse_emacs(s),
+        }
+        .ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("Could not parse `{}` as a key event.", s),
+            )
+        })?;
+
+        Ok(event.normalize_notation())
+    }
+
+    /// Formats the key event in the given notation.
+    ///
+    /// The result can be parsed back with [`KeyEvent::from_notation`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use crossterm::event::{KeyCode, KeyEvent, KeyModifiers, KeyNotation};
+    ///
+    /// let event = KeyEvent::new(KeyCode::Enter, KeyModifiers::CONTROL | KeyModifiers::ALT);
+    ///
+    /// assert_eq!(event.to_string(), "ctrl+alt+enter");
+    /// assert_eq!(event.to_notation(KeyNotation::Vim), "<C-A-CR>");
+    /// assert_eq!(event.to_notation(KeyNotation::Emacs), "C-M-RET");
+    /// ```
+    pub fn to_notation(&self, notation: KeyNotation) -> String {
+        let event = self.normalize_notation();
+        let (code, modifiers) = match event.code {
+            // Shift+Tab is reported as BackTab, write it the way people type it.
+            KeyCode::BackTab => (KeyCode::Tab, event.modifiers),
+            code => (code, event.modifiers),
+        };
+
+        match notation {
+            KeyNotation::Canonical => {
+                let mut result = String::new();
+                for (modifier, name) in notation.modifiers() {
+                    if modifiers.contains(*modifier) {
+                        result.push_str(name);
+                        result.push('+');
+                    }
+                }
+                result.push_str(&canonical_key_name(code));
+                result
+            }
+            KeyNotation::Vim => {
+                let name = vim_key_name(code);
+                if let KeyCode::Char(c) = code {
+                    // Plain characters (including shifted ones) are written as they are.
+                    let plain = modifiers.is_empty()
+                        || (modifiers == KeyModifiers::SHIFT && c.is_ascii_uppercase());
+                    if plain && name.chars().count() == 1 {
+                        return name;
+                    }
+                    if modifiers.contains(KeyModifiers::SHIFT) && c.is_ascii_uppercase() {
+                        return format!(
+                            "<{}{}>",
+                            modifier_prefixes(notation, modifiers),
+                            c.to_ascii_lowercase()
+                        );
+                    }
+                }
+                format!("<{}{}>", modifier_prefixes(notation, modifiers), name)
+            }
+            KeyNotation::Emacs => {
+                let modifiers = match code {
+                    KeyCode::Char(c) if c.is_ascii_uppercase() => modifiers - KeyModifiers::SHIFT,
+                    _ => modifiers,
+                };
+                format!(
+                    "{}{}",
+                    modifier_prefixes(notation, modifiers),
+                    emacs_key_name(code)
+                )
+            }
+        }
+    }
+
+    // Applies `normalize_case` and reports Shift+Tab as BackTab with the shift modifier, like
+    // the terminals do.
+    pub(crate) fn normalize_notation(&self) -> KeyEvent {
+        let mut event = self.normalize_case();
+        if event.code == KeyCode::Tab && event.modifiers.contains(KeyModifiers::SHIFT) {
+            event.code = KeyCode::BackTab;
+        }
+        if event.code == KeyCode::BackTab {
+            event.modifiers.insert(KeyModifiers::SHIFT);
+        }
+        event
+    }
+}
+
+impl FromStr for KeyEvent {
+    type Err = io::Error;
+
+    /// Parses a key event written in the [`KeyNotation::Canonical`] notation.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        KeyEvent::from_notation(s, KeyNotation::Canonical)
+    }
+}
+
+impl fmt::Display for KeyEvent {
+    /// Formats the key event in the [`KeyNotation::Canonical`] notation.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.to_notation(KeyNotation::Canonical))
+    }
+}
+
+fn modifier_prefixes(notation: KeyNotation, modifiers: KeyModifiers) -> String {
+    notation
+        .modifiers()
+        .iter()
+        .filter(|(modifier, _)| modifiers.contains(*modifier))
+        .map(|(_, name)| format!("{}-", name))
+        .collect()
+}
+
+fn single_char(s: &str) -> Option<char> {
+    let mut chars = s.chars();
+    match (chars.next(), chars.next()) {
+        (Some(c), None) => Some(c),
+        _ => None,
+    }
+}
+
+fn parse_canonical(s: &str) -> Option<KeyEvent> {
+    // The key itself can be a `+`, e.g. `ctrl++`.
+    let (modifiers, key) = if s == "+" {
+        ("", "+")
+    } else if let Some(modifiers) = s.strip_suffix("++") {
+        (modifiers, "+")
+    } else {
+        match s.rfind('+') {
+            Some(index) => (&s[..index], &s[index + 1..]),
+            None => ("", s),
+        }
+    };
+
+    let mut event = KeyEvent::from(parse_canonical_key_name(key)?);
+    if !modifiers.is_empty() {
+        for modifier in modifiers.split('+') {
+            event.modifiers |= match modifier.to_ascii_lowercase().as_str() {
+                "ctrl" | "control" => KeyModifiers::CONTROL,
+                "alt" | "option" => KeyModifiers::ALT,
+                "shift" => KeyModifiers::SHIFT,
+                "super" | "cmd" | "command" | "win" | "windows" => KeyModifiers::SUPER,
+                "hyper" => KeyModifiers::HYPER,
+                "meta" => KeyModifiers::META,
+                _ => return None,
+            };
+        }
+    }
+    Some(event)
+}
+
+// Splits single character modifier prefixes like `C-` from the key, e.g. `C-S-a`.
+fn parse_prefixed(
+    mut s: &str,
+    parse_modifier: impl Fn(char) -> Option<KeyModifiers>,
+) -> Option<(KeyModifiers, &str)> {
+    let mut modifiers = KeyModifiers::NONE;
+    loop {
+        let mut chars = s.chars();
+        match (chars.next(), chars.next(), chars.as_str()) {
+            (Some(modifier), Some('-'), rest) if !rest.is_empty() => {
+                modifiers |= parse_modifier(modifier)?;
+                s = rest;
+            }
+            _ => return Some((modifiers, s)),
+        }
+    }
+}
+
+fn parse_vim(s: &str) -> Option<KeyEvent> {
+    let inner = match s.strip_prefix('<').and_then(|s| s.strip_suffix('>')) {
+        Some(inner) if !inner.is_empty() => inner,
+        _ => return single_char(s).map(|c| KeyEvent::from(KeyCode::Char(c))),
+    };
+
+    let (modifiers, key) = parse_prefixed(inner, |modifier| match modifier.to_ascii_uppercase() {
+        'C' => Some(KeyModifiers::CONTROL),
+        'A' | 'M' => Some(KeyModifiers::ALT),
+        'S' => Some(KeyModifiers::SHIFT),
+        'D' => Some(KeyModifiers::SUPER),
+        'H' => Some(KeyModifiers::HYPER),
+        'T' => Some(KeyModifiers::META),
+        _ => None,
+    })?;
+
+    let code = match single_char(key) {
+        Some(c) => KeyCode::Char(c),
+        None => match key.to_ascii_lowercase().as_str() {
+            "bs" => KeyCode::Backspace,
+            "cr" | "return" => KeyCode::Enter,
+            "del" => KeyCode::Delete,
+            "nul" => KeyCode::Null,
+            "lt" => KeyCode::Char('<'),
+            "bslash" => KeyCode::Char('\\'),
+            "bar" => KeyCode::Char('|'),
+            _ => parse_canonical_key_name(key)?,
+        },
+    };
+
+    Some(KeyEvent::new(code, modifiers))
+}
+
+fn parse_emacs(s: &str) -> Option<KeyEvent> {
+    let (modifiers, key) = parse_prefixed(s, |modifier| match modifier {
+        'C' => Some(KeyModifiers::CONTROL),
+        'M' => Some(KeyModifiers::ALT),
+        'S' => Some(KeyModifiers::SHIFT),
+        's' => Some(KeyModifiers::SUPER),
+        'H' => Some(KeyModifiers::HYPER),
+        'A' => Some(KeyModifiers::META),
+        _ => None,
+    })?;
+
+    let code = match key.strip_prefix('<').and_then(|key| key.strip_suffix('>')) {
+        Some(name) if !name.is_empty() => match name.to_ascii_lowercase().as_str() {
+            "return" => KeyCode::Enter,
+            "escape" => KeyCode::Esc,
+            "prior" => KeyCode::PageUp,
+            "next" => KeyCode::PageDown,
+            _ => parse_canonical_key_name(name)?,
+        },
+        _ => match key {
+            "RET" => KeyCode::Enter,
+            "SPC" => KeyCode::Char(' '),
+            "TAB" => KeyCode::Tab,
+            "ESC" => KeyCode::Esc,
+            "DEL" => KeyCode::Backspace,
+            _ => KeyCode::Char(single_char(key)?),
+        },
+    };
+
+    Some(KeyEvent::new(code, modifiers))
+}
+
+fn parse_canonical_key_name(name: &str) -> Option<KeyCode> {
+    if let Some(c) = single_char(name) {
+        return Some(KeyCode::Char(c));
+    }
+
+    let name = name.to_ascii_lowercase();
+    let code = match name.as_str() {
+        "backspace" => KeyCode::Backspace,
+        "enter" => KeyCode::Enter,
+        "left" => KeyCode::Left,
+        "right" => KeyCode::Right,
+        "up" => KeyCode::Up,
+        "down" => KeyCode::Down,
+        "home" => KeyCode::Home,
+        "end" => KeyCode::End,
+        "pageup" => KeyCode::PageUp,
+        "pagedown" => KeyCode::PageDown,
+        "tab" => KeyCode::Tab,
+        "backtab" => KeyCode::BackTab,
+        "delete" => KeyCode::Delete,
+        "insert" => KeyCode::Insert,
+        "space" => KeyCode::Char(' '),
+        "null" => KeyCode::Null,
+        "esc" => KeyCode::Esc,
+        "capslock" => KeyCode::CapsLock,
+        "scrolllock" => KeyCode::ScrollLock,
+        "numlock" => KeyCode::NumLock,
+        "printscreen" => KeyCode::PrintScreen,
+        "pause" => KeyCode::Pause,
+        "menu" => KeyCode::Menu,
+        "begin" => KeyCode::KeypadBegin,
+        name => {
+            if let Some(number) = name.strip_prefix('f') {
+                if number.starts_with('0') {
+                    return None;
+                }
+                return number.parse::<u8>().ok().map(KeyCode::F);
+            }
+            if let Some(media) = name.strip_prefix("media") {
+                return MEDIA_KEYS
+                    .iter()
+                    .find(|(_, name)| *name == media)
+                    .map(|(code, _)| KeyCode::Media(*code));
+            }
+            return MODIFIER_KEYS
+                .iter()
+                .find(|(_, modifier_name)| *modifier_name == name)
+                .map(|(code, _)| KeyCode::Modifier(*code));
+        }
+    };
+    Some(code)
+}
+
+const MEDIA_KEYS: &[(MediaKeyCode, &str)] = &[
+    (MediaKeyCode::Play, "play"),
+    (MediaKeyCode::Pause, "pause"),
+    (MediaKeyCode::PlayPause, "playpause"),
+    (MediaKeyCode::Reverse, "reverse"),
+    (MediaKeyCode::Stop, "stop"),
+    (MediaKeyCode::FastForward, "fastforward"),
+    (MediaKeyCode::Rewind, "rewind"),
+    (MediaKeyCode::TrackNext, "tracknext"),
+    (MediaKeyCode::TrackPrevious, "trackprevious"),
+    (MediaKeyCode::Record, "record"),
+    (MediaKeyCode::LowerVolume, "lowervolume"),
+    (MediaKeyCode::RaiseVolume, "raisevolume"),
+    (MediaKeyCode::MuteVolume, "mutevolume"),
+];
+
+const MODIFIER_KEYS: &[(ModifierKeyCode, &str)] = &[
+    (ModifierKeyCode::LeftShift, "leftshift"),
+    (ModifierKeyCode::LeftControl, "leftctrl"),
+    (ModifierKeyCode::LeftAlt, "leftalt"),
+    (ModifierKeyCode::LeftSuper, "leftsuper"),
+    (ModifierKeyCode::LeftHyper, "lefthyper"),
+    (ModifierKeyCode::LeftMeta, "leftmeta"),
+    (ModifierKeyCode::RightShift, "rightshift"),
+    (ModifierKeyCode::RightControl, "rightctrl"),
+    (ModifierKeyCode::RightAlt, "rightalt"),
+    (ModifierKeyCode::RightSuper, "rightsuper"),
+    (ModifierKeyCode::RightHyper, "righthyper"),
+    (ModifierKeyCode::RightMeta, "rightmeta"),
+    (ModifierKeyCode::IsoLevel3Shift, "isolevel3shift"),
+    (ModifierKeyCode::IsoLevel5Shift, "isolevel5shift"),
+];
+
+fn canonical_key_name(code: KeyCode) -> String {
+    let name = match code {
+        KeyCode::Backspace => "backspace",
+        KeyCode::Enter => "enter",
+        KeyCode::Left => "left",
+        KeyCode::Right => "right",
+        KeyCode::Up => "up",
+        KeyCode::Down => "down",
+        KeyCode::Home => "home",
+        KeyCode::End => "end",
+        KeyCode::PageUp => "pageup",
+        KeyCode::PageDown => "pagedown",
+        KeyCode::Tab => "tab",
+        KeyCode::BackTab => "backtab",
+        KeyCode::Delete => "delete",
+        KeyCode::Insert => "insert",
+        KeyCode::F(number) => return format!("f{}", number),
+        KeyCode::Char(' ') => "space",
+        KeyCode::Char(c) => return c.to_string(),
+        KeyCode::Null => "null",
+        KeyCode::Esc => "esc",
+        KeyCode::CapsLock => "capslock",
+        KeyCode::ScrollLock => "scrolllock",
+        KeyCode::NumLock => "numlock",
+        KeyCode::PrintScreen => "printscreen",
+        KeyCode::Pause => "pause",
+        KeyCode::Menu => "menu",
+        KeyCode::KeypadBegin => "begin",
+        KeyCode::Media(media) => {
+            let (_, name) = MEDIA_KEYS.iter().find(|(code, _)| *code == media).unwrap();
+            return format!("media{}", name);
+        }
+        KeyCode::Modifier(modifier) => {
+            let (_, name) = MODIFIER_KEYS
+                .iter()
+                .find(|(code, _)| *code == modifier)
+                .unwrap();
+            name
+        }
+    };
+    name.to_string()
+}
+
+fn vim_key_name(code: KeyCode) -> String {
+    let name = match code {
+        KeyCode::Backspace => "BS",
+        KeyCode::Enter => "CR",
+        KeyCode::Left => "Left",
+        KeyCode::Right => "Right",
+        KeyCode::Up => "Up",
+        KeyCode::Down => "Down",
+        KeyCode::Home => "Home",
+        KeyCode::End => "End",
+        KeyCode::PageUp => "PageUp",
+        KeyCode::PageDown => "PageDown",
+        KeyCode::Tab => "Tab",
+        KeyCode::Delete => "Del",
+        KeyCode::Insert => "Insert",
+        KeyCode::F(number) => return format!("F{}", number),
+        KeyCode::Char(' ') => "Space",
+        KeyCode::Char('<') => "lt",
+        KeyCode::Null => "Nul",
+        KeyCode::Esc => "Esc",
+        code => return canonical_key_name(code),
+    };
+    name.to_string()
+}
+
+fn emacs_key_name(code: KeyCode) -> String {
+    match code {
+        KeyCode::Backspace => "DEL".to_string(),
+        KeyCode::Enter => "RET".to_string(),
+        KeyCode::Tab => "TAB".to_string(),
+        KeyCode::Esc => "ESC".to_string(),
+        KeyCode::Char(' ') => "SPC".to_string(),
+        KeyCode::Char(c) => c.to_string(),
+        KeyCode::PageUp => "<prior>".to_string(),
+        KeyCode::PageDown => "<next>".to_string(),
+        code => format!("<{}>", canonical_key_name(code)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::KeyNotation;
+    use crate::event::{KeyCode, KeyEvent, KeyModifiers, MediaKeyCode, ModifierKeyCode};
+
+    fn key(code: KeyCode, modifiers: KeyModifiers) -> KeyEvent {
+        KeyEvent::new(code, modifiers)
+    }
+
+    #[test]
+    fn test_parse_canonical() {
+        assert_eq!(
+            "ctrl+shift+a".parse::<KeyEvent>().unwrap(),
+            key(
+                KeyCode::Char('A'),
+                KeyModifiers::CONTROL | KeyModifiers::SHIFT
+            )
+        );
+        assert_eq!(
+            "Ctrl+Alt+Enter".parse::<KeyEvent>().unwrap(),
+            key(KeyCode::Enter, KeyModifiers::CONTROL | KeyModifiers::ALT)
+        );
+        assert_eq!(
+            "ctrl++".parse::<KeyEvent>().unwrap(),
+            key(KeyCode::Char('+'), KeyModifiers::CONTROL)
+        );
+        assert_eq!(
+            "+".parse::<KeyEvent>().unwrap(),
+            key(KeyCode::Char('+'), KeyModifiers::NONE)
+        );
+        assert_eq!(
+            "f12".parse::<KeyEvent>().unwrap(),
+            key(KeyCode::F(12), KeyModifiers::NONE)
+        );
+        assert_eq!(
+            "shift+tab".parse::<KeyEvent>().unwrap(),
+            key(KeyCode::BackTab, KeyModifiers::SHIFT)
+        );
+        assert_eq!(
+            "mediaplaypause".parse::<KeyEvent>().unwrap(),
+            key(KeyCode::Media(MediaKeyCode::PlayPause), KeyModifiers::NONE)
+        );
+        assert_eq!(
+            "rightalt".parse::<KeyEvent>().unwrap(),
+            key(
+                KeyCode::Modifier(ModifierKeyCode::RightAlt),
+                KeyModifiers::NONE
+            )
+        );
+    }
+
+    #[test]
+    fn test_parse_canonical_errors() {
+        assert!("".parse::<KeyEvent>().is_err());
+        assert!("ctrl+".parse::<KeyEvent>().is_err());
+        assert!("foo+a".parse::<KeyEvent>().is_err());
+        assert!("enterr".parse::<KeyEvent>().is_err());
+        assert!("f0".parse::<KeyEvent>().is_err());
+        assert!("f01".parse::<KeyEvent>().is_err());
+    }
+
+    #[test]
+    fn test_parse_canonical_normalizes_case() {
+        assert_eq!(
+            "shift+a".parse::<KeyEvent>().unwrap(),
+            "A".parse::<KeyEvent>().unwrap()
+        );
+        assert_eq!(
+            "A".parse::<KeyEvent>().unwrap().modifiers,
+            KeyModifiers::SHIFT
+        );
+    }
+
+    #[test]
+    fn test_display_canonical() {
+        assert_eq!(
+            key(
+                KeyCode::Char('a'),
+                KeyModifiers::CONTROL | KeyModifiers::SHIFT
+            )
+            .to_string(),
+            "ctrl+shift+A"
+        );
+        assert_eq!(
+            key(KeyCode::Char(' '), KeyModifiers::ALT).to_string(),
+            "alt+space"
+        );
+        assert_eq!(
+            key(KeyCode::BackTab, KeyModifiers::SHIFT).to_string(),
+            "shift+tab"
+        );
+        assert_eq!(key(KeyCode::F(5), KeyModifiers::NONE).to_string(), "f5");
+    }
+
+    #[test]
+    fn test_canonical_round_trip() {
+        let events = [
+            key(KeyCode::Char('a'), KeyModifiers::NONE),
+            key(KeyCode::Char('+'), KeyModifiers::CONTROL),
+            key(KeyCode::Char('Ž'), KeyModifiers::SHIFT),
+            key(KeyCode::PageDown, KeyModifiers::SUPER | KeyModifiers::META),
+            key(KeyCode::Media(MediaKeyCode::Stop), KeyModifiers::HYPER),
+            key(
+                KeyCode::Modifier(ModifierKeyCode::IsoLevel3Shift),
+                KeyModifiers::NONE,
+            ),
+            key(KeyCode::KeypadBegin, KeyModifiers::NONE),
+        ];
+        for event in events {
+            for notation in [KeyNotation::Canonical, KeyNotation::Vim, KeyNotation::Emacs] {
+                let text = event.to_notation(notation);
+                assert_eq!(
+                    KeyEvent::from_notation(&text, notation).unwrap(),
+                    event,
+                    "{:?}",
+                    text
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_vim_notation() {
+        let parse = |s| KeyEvent::from_notation(s, KeyNotation::Vim).unwrap();
+
+        assert_eq!(parse("x"), key(KeyCode::Char('x'), KeyModifiers::NONE));
+        assert_eq!(
+            parse("<C-x>"),
+            key(KeyCode::Char('x'), KeyModifiers::CONTROL)
+        );
+        assert_eq!(
+            parse("<c-s-a>"),
+            key(
+                KeyCode::Char('A'),
+                KeyModifiers::CONTROL | KeyModifiers::SHIFT
+            )
+        );
+        assert_eq!(parse("<M-CR>"), key(KeyCode::Enter, KeyModifiers::ALT));
+        assert_eq!(parse("<S-Tab>"), key(KeyCode::BackTab, KeyModifiers::SHIFT));
+        assert_eq!(parse("<lt>"), key(KeyCode::Char('<'), KeyModifiers::NONE));
+        assert_eq!(
+            parse("<C-->"),
+            key(KeyCode::Char('-'), KeyModifiers::CONTROL)
+        );
+        assert!(KeyEvent::from_notation("gg", KeyNotation::Vim).is_err());
+        assert!(KeyEvent::from_notation("<X-a>", KeyNotation::Vim).is_err());
+
+        let format = |event: KeyEvent| event.to_notation(KeyNotation::Vim);
+        assert_eq!(format(key(KeyCode::Char('A'), KeyModifiers::SHIFT)), "A");
+        assert_eq!(
+            format(key(
+                KeyCode::Char('A'),
+                KeyModifiers::CONTROL | KeyModifiers::SHIFT
+            )),
+            "<C-S-a>"
+        );
+        assert_eq!(
+            format(key(KeyCode::Char(' '), KeyModifiers::NONE)),
+            "<Space>"
+        );
+        assert_eq!(format(key(KeyCode::F(1), KeyModifiers::NONE)), "<F1>");
+    }
+
+    #[test]
+    fn test_emacs_notation() {
+        let parse = |s| KeyEvent::from_notation(s, KeyNotation::Emacs).unwrap();
+
+        assert_eq!(parse("C-x"), key(KeyCode::Char('x'), KeyModifiers::CONTROL));
+        assert_eq!(parse("M-RET"), key(KeyCode::Enter, KeyModifiers::ALT));
+        assert_eq!(parse("s-a"), key(KeyCode::Char('a'), KeyModifiers::SUPER));
+        assert_eq!(parse("S-a"), key(KeyCode::Char('A'), KeyModifiers::SHIFT));
+        assert_eq!(parse("C-<f5>"), key(KeyCode::F(5), KeyModifiers::CONTROL));
+        assert_eq!(parse("<prior>"), key(KeyCode::PageUp, KeyModifiers::NONE));
+        assert_eq!(parse("DEL"), key(KeyCode::Backspace, KeyModifiers::NONE));
+        assert_eq!(parse("-"), key(KeyCode::Char('-'), KeyModifiers::NONE));
+        assert!(KeyEvent::from_notation("C-x C-s", KeyNotation::Emacs).is_err());
+
+        let format = |event: KeyEvent| event.to_notation(KeyNotation::Emacs);
+        assert_eq!(
+            format(key(KeyCode::Char('s'), KeyModifiers::CONTROL)),
+            "C-s"
+        );
+        assert_eq!(format(key(KeyCode::Char('A'), KeyModifiers::ALT)), "M-A");
+        assert_eq!(format(key(KeyCode::Home, KeyModifiers::NONE)), "<home>");
+    }
+}
@@ -1,4 +1,8 @@
-use std::{collections::vec_deque::VecDeque, io, time::Duration};
+use std::{
+    collections::vec_deque::VecDeque,
+    io,
+    time::{Duration, Instant},
+};
 
 #[cfg(unix)]
 use crate::event::source::unix::UnixInternalEventSource;
@@ -6,13 +10,15 @@ use crate::event::source::unix::UnixInternalEventSource;
 use crate::event::source::windows::WindowsEventSource;
 #[cfg(feature = "event-stream")]
 use crate::event::sys::Waker;
-use crate::event::{filter::Filter, source::EventSource, timeout::PollTimeout, InternalEvent};
+use crate::event::{
+    filter::Filter, source::EventSource, subscription, timeout::PollTimeout, InternalEvent,
+};
 
 /// Can be used to read `InternalEvent`s.
 pub(crate) struct InternalEventReader {
-    events: VecDeque<InternalEvent>,
+    events: VecDeque<(InternalEvent, Instant)>,
     source: Option<Box<dyn EventSource>>,
-    skipped_events: Vec<InternalEvent>,
+    skipped_events: Vec<(InternalEvent, Instant)>,
 }
 
 impl Default for InternalEventReader {
@@ -43,7 +49,7 @@ impl InternalEventReader {
     where
         F: Filter,
     {
-        for event in &self.events {
+        for (event, _) in &self.events {
             if filter.eval(event) {
                 return Ok(true);
             }
@@ -64,11 +70,14 @@ impl InternalEventReader {
         loop {
             let maybe_event = match event_source.try_read(poll_timeout.leftover()) {
                 Ok(None) => None,
-                Ok(Some(event)) => {
-                    if filter.eval(&event) {
-                        Some(event)
+                Ok(Some((event, time))) => {
+                    if matches!(&event, InternalEvent::Event(event) if subscription::dispatch(event))
+                    {
+                        None
+                    } else if filter.eval(&event) {
+                        Some((event, time))
                     } else {
-                        self.skipped_events.push(event);
+                        self.skipped_events.push((event, time));
                         None
                     }
                 }
@@ -94,7 +103,24 @@ impl InternalEventReader {
         }
     }
 
+    /// Discards the input events read but not returned yet.
+    pub(crate) fn discard_input(&mut self) {
+        self.events.retain(|(event, _)| !event.is_input());
+        self.skipped_events.retain(|(event, _)| !event.is_input());
+        if let Some(source) = self.source.as_mut() {
+            source.discard_input();
+        }
+    }
+
     pub(crate) fn read<F>(&mut self, filter: &F) -> io::Result<InternalEvent>
+    where
+        F: Filter,
+    {
+        self.read_timed(filter).map(|(event, _)| event)
+    }
+
+    /// Reads an `InternalEvent` together with the time it was read from the event source.
+    pub(crate) fn read_timed<F>(&mut self, filter: &F) -> io::Result<(InternalEvent, Instant)>
     where
         F: Filter,
     {
@@ -102,7 +128,7 @@ impl InternalEventReader {
 
         loop {
             while let Some(event) = self.events.pop_front() {
-                if filter.eval(&event) {
+                if filter.eval(&event.0) {
                     while let Some(event) = skipped_events.pop_front() {
                         self.events.push_back(event);
                     }
@@ -128,14 +154,18 @@ impl InternalEventReader {
 #[cfg(test)]
 mod tests {
     use std::io;
-    use std::{collections::VecDeque, time::Duration};
+    use std::{
+        collections::VecDeque,
+        time::{Duration, Instant},
+    };
 
     #[cfg(unix)]
     use super::super::filter::CursorPositionFilter;
     use super::{
-        super::{filter::InternalEventFilter, Event},
+        super::{filter::InternalEventFilter, Event, KeyCode, KeyEvent, KeyModifiers},
         EventSource, InternalEvent, InternalEventReader,
     };
+    use crate::event::subscribe_consuming;
 
     #[test]
     fn test_poll_fails_without_event_source() {
@@ -157,7 +187,7 @@ mod tests {
     #[test]
     fn test_poll_returns_true_for_matching_event_in_queue_at_front() {
         let mut reader = InternalEventReader {
-            events: vec![InternalEvent::Event(Event::Resize(10, 10))].into(),
+            events: timed(&[InternalEvent::Event(Event::Resize(10, 10))]),
             source: None,
             skipped_events: Vec::with_capacity(32),
         };
@@ -169,11 +199,10 @@ mod tests {
     #[cfg(unix)]
     fn test_poll_returns_true_for_matching_event_in_queue_at_back() {
         let mut reader = InternalEventReader {
-            events: vec![
+            events: timed(&[
                 InternalEvent::Event(Event::Resize(10, 10)),
                 InternalEvent::CursorPosition(10, 20),
-            ]
-            .into(),
+            ]),
             source: None,
             skipped_events: Vec::with_capacity(32),
         };
@@ -186,7 +215,7 @@ mod tests {
         const EVENT: InternalEvent = InternalEvent::Event(Event::Resize(10, 10));
 
         let mut reader = InternalEventReader {
-            events: vec![EVENT].into(),
+            events: timed(&[EVENT]),
             source: None,
             skipped_events: Vec::with_capacity(32),
         };
@@ -200,7 +229,7 @@ mod tests {
         const CURSOR_EVENT: InternalEvent = InternalEvent::CursorPosition(10, 20);
 
         let mut reader = InternalEventReader {
-            events: vec![InternalEvent::Event(Event::Resize(10, 10)), CURSOR_EVENT].into(),
+            events: timed(&[InternalEvent::Event(Event::Resize(10, 10)), CURSOR_EVENT]),
             source: None,
             skipped_events: Vec::with_capacity(32),
         };
@@ -215,7 +244,7 @@ mod tests {
         const CURSOR_EVENT: InternalEvent = InternalEvent::CursorPosition(10, 20);
 
         let mut reader = InternalEventReader {
-            events: vec![SKIPPED_EVENT, CURSOR_EVENT].into(),
+            events: timed(&[SKIPPED_EVENT, CURSOR_EVENT]),
             source: None,
             skipped_events: Vec::with_capacity(32),
         };
@@ -224,6 +253,32 @@ mod tests {
         assert_eq!(reader.read(&InternalEventFilter).unwrap(), SKIPPED_EVENT);
     }
 
+    #[test]
+    fn test_discard_input_keeps_other_events() {
+        const KEY_EVENT: InternalEvent = InternalEvent::Event(Event::Key(KeyEvent::new(
+            KeyCode::Char('y'),
+            KeyModifiers::NONE,
+        )));
+        const RESIZE_EVENT: InternalEvent = InternalEvent::Event(Event::Resize(10, 10));
+
+        let mut reader = InternalEventReader {
+            events: timed(&[KEY_EVENT, RESIZE_EVENT]),
+            source: Some(Box::new(FakeSource::with_events(&[
+                KEY_EVENT,
+                RESIZE_EVENT,
+            ]))),
+            skipped_events: Vec::with_capacity(32),
+        };
+
+        reader.discard_input();
+
+        assert_eq!(reader.read(&InternalEventFilter).unwrap(), RESIZE_EVENT);
+        assert_eq!(reader.read(&InternalEventFilter).unwrap(), RESIZE_EVENT);
+        assert!(!reader
+            .poll(Some(Duration::from_secs(0)), &InternalEventFilter)
+            .unwrap());
+    }
+
     #[test]
     fn test_poll_timeouts_if_source_has_no_events() {
         let source = FakeSource::default();
@@ -255,6 +310,24 @@ mod tests {
             .unwrap());
     }
 
+    #[test]
+    fn test_poll_skips_consumed_event() {
+        const CONSUMED: Event = Event::Resize(4002, 1);
+        const EVENT: InternalEvent = InternalEvent::Event(Event::Resize(4002, 2));
+
+        let source = FakeSource::with_events(&[InternalEvent::Event(CONSUMED), EVENT]);
+
+        let mut reader = InternalEventReader {
+            events: VecDeque::new(),
+            source: Some(Box::new(source)),
+            skipped_events: Vec::with_capacity(32),
+        };
+
+        let subscription = subscribe_consuming(|event| *event == CONSUMED);
+        assert_eq!(reader.read(&InternalEventFilter).unwrap(), EVENT);
+        assert_eq!(subscription.try_read(), Some(CONSUMED));
+    }
+
     #[test]
     fn test_reads_returns_event_if_source_has_at_least_one_event() {
         const EVENT: InternalEvent = InternalEvent::Event(Event::Resize(10, 10));
@@ -377,6 +450,30 @@ mod tests {
         assert_eq!(reader.read(&InternalEventFilter).unwrap(), EVENT);
     }
 
+    #[test]
+    fn test_read_timed_returns_time_of_event() {
+        const EVENT: InternalEvent = InternalEvent::Event(Event::Resize(10, 10));
+
+        let before = Instant::now();
+        let source = FakeSource::with_events(&[EVENT]);
+
+        let mut reader = InternalEventReader {
+            events: VecDeque::new(),
+            source: Some(Box::new(source)),
+            skipped_events: Vec::with_capacity(32),
+        };
+
+        let (event, time) = reader.read_timed(&InternalEventFilter).unwrap();
+        assert_eq!(event, EVENT);
+        assert!(time >= before);
+        assert!(time <= Instant::now());
+    }
+
+    fn timed(events: &[InternalEvent]) -> VecDeque<(InternalEvent, Instant)> {
+        let now = Instant::now();
+        events.iter().map(|event| (event.clone(), now)).collect()
+    }
+
     #[derive(Default)]
     struct FakeSource {
         events: VecDeque<InternalEvent>,
@@ -400,7 +497,10 @@ mod tests {
     }
 
     impl EventSource for FakeSource {
-        fn try_read(&mut self, _timeout: Option<Duration>) -> io::Result<Option<InternalEvent>> {
+        fn try_read(
+            &mut self,
+            _timeout: Option<Duration>,
+        ) -> io::Result<Option<(InternalEvent, Instant)>> {
             // Return error if set in case there's just one remaining event
             if self.events.len() == 1 {
                 if let Some(error) = self.error.take() {
@@ -410,7 +510,7 @@ mod tests {
 
             // Return all events from the queue
             if let Some(event) = self.events.pop_front() {
-                return Ok(Some(event));
+                return Ok(Some((event, Instant::now())));
             }
 
             // Return error if there're no more events
@@ -422,6 +522,10 @@ mod tests {
             Ok(None)
         }
 
+        fn discard_input(&mut self) {
+            self.events.retain(|event| !event.is_input());
+        }
+
         #[cfg(feature = "event-stream")]
         fn waker(&self) -> super::super::sys::Waker {
             unimplemented!();
@@ -1,4 +1,7 @@
-use std::{io, time::Duration};
+use std::{
+    io,
+    time::{Duration, Instant},
+};
 
 #[cfg(feature = "event-stream")]
 use super::sys::Waker;
@@ -9,6 +12,11 @@ pub(crate) mod unix;
 #[cfg(windows)]
 pub(crate) mod windows;
 
+#[cfg(unix)]
+pub(crate) use self::unix::{disable_signal_events, enable_signal_events};
+#[cfg(windows)]
+pub(crate) use self::windows::{disable_signal_events, enable_signal_events};
+
 /// An interface for trying to read an `InternalEvent` within an optional `Duration`.
 pub(crate) trait EventSource: Sync + Send {
     /// Tries to read an `InternalEvent` within the given duration.
@@ -18,8 +26,15 @@ pub(crate) trait EventSource: Sync + Send {
     /// * `timeout` - `None` block indefinitely until an event is available, `Some(duration)` blocks
     ///               for the given timeout
     ///
-    /// Returns `Ok(None)` if there's no event available and timeout expires.
-    fn try_read(&mut self, timeout: Option<Duration>) -> io::Result<Option<InternalEvent>>;
+    /// Returns `Ok(None)` if there's no event available and timeout expires, otherwise the event
+    /// together with the time it was read from the terminal.
+    fn try_read(
+        &mut self,
+        timeout: Option<Duration>,
+    ) -> io::Result<Option<(InternalEvent, Instant)>>;
+
+    /// Discards the input events read but not returned yet, together with any incomplete input.
+    fn discard_input(&mut self);
 
     /// Returns a `Waker` allowing to wake/force the `try_read` method to return `Ok(None)`.
     #[cfg(feature = "event-stream")]
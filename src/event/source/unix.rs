@@ -4,8 +4,16 @@ pub(crate) mod tty;
 #[cfg(not(feature = "use-dev-tty"))]
 pub(crate) mod mio;
 
+pub(crate) mod parser;
+pub(crate) mod signal;
+
 #[cfg(feature = "use-dev-tty")]
 pub(crate) use self::tty::UnixInternalEventSource;
 
 #[cfg(not(feature = "use-dev-tty"))]
 pub(crate) use self::mio::UnixInternalEventSource;
+
+pub(crate) use self::parser::set_escape_timeout;
+pub(crate) use self::signal::{
+    disable_signal_events, disable_suspend_handling, enable_signal_events, enable_suspend_handling,
+};
@@ -1,12 +1,24 @@
-use std::{collections::VecDeque, io, time::Duration};
+use std::{
+    collections::VecDeque,
+    io,
+    os::unix::{io::AsRawFd, net::UnixStream},
+    time::{Duration, Instant},
+};
 
 use mio::{unix::SourceFd, Events, Interest, Poll, Token};
-use signal_hook_mio::v0_8::Signals;
 
 #[cfg(feature = "event-stream")]
 use crate::event::sys::Waker;
 use crate::event::{
-    source::EventSource, sys::unix::parse::parse_event, timeout::PollTimeout, Event, InternalEvent,
+    source::{
+        unix::{
+            parser::{escape_timeout, Parser},
+            signal::{read_signal_events, signal_event_receiver, signal_receivers, SignalReceiver},
+        },
+        EventSource,
+    },
+    timeout::PollTimeout,
+    InternalEvent,
 };
 use crate::terminal::sys::file_descriptor::{tty_fd, FileDesc};
 
@@ -15,6 +27,7 @@ const TTY_TOKEN: Token = Token(0);
 const SIGNAL_TOKEN: Token = Token(1);
 #[cfg(feature = "event-stream")]
 const WAKE_TOKEN: Token = Token(2);
+const SIGNAL_EVENT_TOKEN: Token = Token(3);
 
 // I (@zrzka) wasn't able to read more than 1_022 bytes when testing
 // reading on macOS/Linux -> we don't need bigger buffer and 1k of bytes
@@ -27,9 +40,15 @@ pub(crate) struct UnixInternalEventSource {
     parser: Parser,
     tty_buffer: [u8; TTY_BUFFER_SIZE],
     tty_fd: FileDesc<'static>,
-    signals: Signals,
+    signal_receivers: Vec<SignalReceiver>,
+    // Events of signals received together with another one
+    signal_events: VecDeque<(InternalEvent, Instant)>,
+    signal_event_receiver: UnixStream,
     #[cfg(feature = "event-stream")]
     waker: Waker,
+    // The poll was woken up together with other readiness events, which are handled first
+    #[cfg(feature = "event-stream")]
+    woken: bool,
 }
 
 impl UnixInternalEventSource {
@@ -45,35 +64,66 @@ impl UnixInternalEventSource {
         let mut tty_ev = SourceFd(&tty_raw_fd);
         registry.register(&mut tty_ev, TTY_TOKEN, Interest::READABLE)?;
 
-        let mut signals = Signals::new([signal_hook::consts::SIGWINCH])?;
-        registry.register(&mut signals, SIGNAL_TOKEN, Interest::READABLE)?;
+        let signal_receivers = signal_receivers()?;
+        for receiver in &signal_receivers {
+            registry.register(
+                &mut SourceFd(&receiver.as_raw_fd()),
+                SIGNAL_TOKEN,
+                Interest::READABLE,
+            )?;
+        }
+
+        let signal_event_receiver = signal_event_receiver()?;
+        registry.register(
+            &mut SourceFd(&signal_event_receiver.as_raw_fd()),
+            SIGNAL_EVENT_TOKEN,
+            Interest::READABLE,
+        )?;
 
         #[cfg(feature = "event-stream")]
         let waker = Waker::new(registry, WAKE_TOKEN)?;
 
         Ok(UnixInternalEventSource {
             poll,
-            events: Events::with_capacity(3),
+            events: Events::with_capacity(4),
             parser: Parser::default(),
             tty_buffer: [0u8; TTY_BUFFER_SIZE],
             tty_fd: input_fd,
-            signals,
+            signal_receivers,
+            signal_events: VecDeque::new(),
+            signal_event_receiver,
             #[cfg(feature = "event-stream")]
             waker,
+            #[cfg(feature = "event-stream")]
+            woken: false,
         })
     }
 }
 
 impl EventSource for UnixInternalEventSource {
-    fn try_read(&mut self, timeout: Option<Duration>) -> io::Result<Option<InternalEvent>> {
+    fn try_read(
+        &mut self,
+        timeout: Option<Duration>,
+    ) -> io::Result<Option<(InternalEvent, Instant)>> {
+        self.parser.set_escape_timeout(escape_timeout());
+        self.parser.resolve_pending_escape();
+
         if let Some(event) = self.parser.next() {
             return Ok(Some(event));
         }
+        if let Some(event) = self.signal_events.pop_front() {
+            return Ok(Some(event));
+        }
+        #[cfg(feature = "event-stream")]
+        if std::mem::take(&mut self.woken) {
+            return Err(woken_error());
+        }
 
         let timeout = PollTimeout::new(timeout);
 
         loop {
-            if let Err(e) = self.poll.poll(&mut self.events, timeout.leftover()) {
+            let poll_timeout = self.parser.poll_timeout(timeout.leftover());
+            if let Err(e) = self.poll.poll(&mut self.events, poll_timeout) {
                 // Mio will throw an interrupted error in case of cursor position retrieval. We need to retry until it succeeds.
                 // Previous versions of Mio (< 0.7) would automatically retry the poll call if it was interrupted (if EINTR was returned).
                 // https://docs.rs/mio/0.7.0/mio/struct.Poll.html#notes
@@ -85,8 +135,24 @@ impl EventSource for UnixInternalEventSource {
             };
 
             if self.events.is_empty() {
-                // No readiness events = timeout
-                return Ok(None);
+                // No readiness events = timeout, either ours or the one of a pending ESC
+                self.parser.resolve_pending_escape();
+                if let Some(event) = self.parser.next() {
+                    return Ok(Some(event));
+                }
+
+                if timeout.elapsed() {
+                    return Ok(None);
+                }
+
+                continue;
+            }
+
+            // The readiness events are edge-triggered, returning on the wake-up first would lose
+            // the others
+            #[cfg(feature = "event-stream")]
+            if self.events.iter().any(|event| event.token() == WAKE_TOKEN) {
+                self.woken = true;
             }
 
             for token in self.events.iter().map(|x| x.token()) {
@@ -120,31 +186,33 @@ impl EventSource for UnixInternalEventSource {
                         }
                     }
                     SIGNAL_TOKEN => {
-                        if self.signals.pending().next() == Some(signal_hook::consts::SIGWINCH) {
-                            // TODO Should we remove tput?
-                            //
-                            // This can take a really long time, because terminal::size can
-                            // launch new process (tput) and then it parses its output. It's
-                            // not a really long time from the absolute time point of view, but
-                            // it's a really long time from the mio, async-std/tokio executor, ...
-                            // point of view.
-                            let new_size = crate::terminal::size()?;
-                            return Ok(Some(InternalEvent::Event(Event::Resize(
-                                new_size.0, new_size.1,
-                            ))));
+                        for receiver in &self.signal_receivers {
+                            if let Some(event) = receiver.read()? {
+                                self.signal_events.push_back(event);
+                            }
+                        }
+                        if let Some(event) = self.signal_events.pop_front() {
+                            return Ok(Some(event));
                         }
                     }
-                    #[cfg(feature = "event-stream")]
-                    WAKE_TOKEN => {
-                        return Err(std::io::Error::new(
-                            std::io::ErrorKind::Interrupted,
-                            "Poll operation was woken up by `Waker::wake`",
-                        ));
+                    SIGNAL_EVENT_TOKEN => {
+                        self.signal_events
+                            .extend(read_signal_events(&self.signal_event_receiver)?);
+                        if let Some(event) = self.signal_events.pop_front() {
+                            return Ok(Some(event));
+                        }
                     }
+                    #[cfg(feature = "event-stream")]
+                    WAKE_TOKEN => {}
                     _ => unreachable!("Synchronize Evented handle registration & token handling"),
                 }
             }
 
+            #[cfg(feature = "event-stream")]
+            if std::mem::take(&mut self.woken) {
+                return Err(woken_error());
+            }
+
             // Processing above can take some time, check if timeout expired
             if timeout.elapsed() {
                 return Ok(None);
@@ -152,78 +220,20 @@ impl EventSource for UnixInternalEventSource {
         }
     }
 
+    fn discard_input(&mut self) {
+        self.parser.discard_input();
+    }
+
     #[cfg(feature = "event-stream")]
     fn waker(&self) -> Waker {
         self.waker.clone()
     }
 }
 
-//
-// Following `Parser` structure exists for two reasons:
-//
-//  * mimic anes Parser interface
-//  * move the advancing, parsing, ... stuff out of the `try_read` method
-//
-#[derive(Debug)]
-struct Parser {
-    buffer: Vec<u8>,
-    internal_events: VecDeque<InternalEvent>,
-}
-
-impl Default for Parser {
-    fn default() -> Self {
-        Parser {
-            // This buffer is used for -> 1 <- ANSI escape sequence. Are we
-            // aware of any ANSI escape sequence that is bigger? Can we make
-            // it smaller?
-            //
-            // Probably not worth spending more time on this as "there's a plan"
-            // to use the anes crate parser.
-            buffer: Vec::with_capacity(256),
-            // TTY_BUFFER_SIZE is 1_024 bytes. How many ANSI escape sequences can
-            // fit? What is an average sequence length? Let's guess here
-            // and say that the average ANSI escape sequence length is 8 bytes. Thus
-            // the buffer size should be 1024/8=128 to avoid additional allocations
-            // when processing large amounts of data.
-            //
-            // There's no need to make it bigger, because when you look at the `try_read`
-            // method implementation, all events are consumed before the next TTY_BUFFER
-            // is processed -> events pushed.
-            internal_events: VecDeque::with_capacity(128),
-        }
-    }
-}
-
-impl Parser {
-    fn advance(&mut self, buffer: &[u8], more: bool) {
-        for (idx, byte) in buffer.iter().enumerate() {
-            let more = idx + 1 < buffer.len() || more;
-
-            self.buffer.push(*byte);
-
-            match parse_event(&self.buffer, more) {
-                Ok(Some(ie)) => {
-                    self.internal_events.push_back(ie);
-                    self.buffer.clear();
-                }
-                Ok(None) => {
-                    // Event can't be parsed, because we don't have enough bytes for
-                    // the current sequence. Keep the buffer and process next bytes.
-                }
-                Err(_) => {
-                    // Event can't be parsed (not enough parameters, parameter is not a number, ...).
-                    // Clear the buffer and continue with another sequence.
-                    self.buffer.clear();
-                }
-            }
-        }
-    }
-}
-
-impl Iterator for Parser {
-    type Item = InternalEvent;
-
-    fn next(&mut self) -> Option<Self::Item> {
-        self.internal_events.pop_front()
-    }
+#[cfg(feature = "event-stream")]
+fn woken_error() -> io::Error {
+    std::io::Error::new(
+        std::io::ErrorKind::Interrupted,
+        "Poll operation was woken up by `Waker::wake`",
+    )
 }
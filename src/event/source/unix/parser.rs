@@ -0,0 +1,239 @@
+use std::{
+    collections::VecDeque,
+    time::{Duration, Instant},
+};
+
+use parking_lot::Mutex;
+
+use crate::event::{sys::unix::parse::parse_event, Event, InternalEvent, KeyCode};
+
+/// How long a lone `ESC` byte is held before it's reported as an Esc key press.
+static ESCAPE_TIMEOUT: Mutex<Duration> = parking_lot::const_mutex(Duration::ZERO);
+
+pub(crate) fn set_escape_timeout(timeout: Duration) {
+    *ESCAPE_TIMEOUT.lock() = timeout;
+}
+
+pub(crate) fn escape_timeout() -> Duration {
+    *ESCAPE_TIMEOUT.lock()
+}
+
+//
+// Following `Parser` structure exists for two reasons:
+//
+//  * mimic anes Parser interface
+//  * move the advancing, parsing, ... stuff out of the `try_read` method
+//
+#[derive(Debug)]
+pub(crate) struct Parser {
+    buffer: Vec<u8>,
+    internal_events: VecDeque<(InternalEvent, Instant)>,
+    escape_timeout: Duration,
+    // The time a lone ESC was read at the end of the input, if we're still waiting for more
+    // bytes to decide whether it's an Esc key press or the start of an escape sequence.
+    pending_escape: Option<Instant>,
+}
+
+impl Default for Parser {
+    fn default() -> Self {
+        Parser {
+            // This buffer is used for -> 1 <- ANSI escape sequence. Are we
+            // aware of any ANSI escape sequence that is bigger? Can we make
+            // it smaller?
+            //
+            // Probably not worth spending more time on this as "there's a plan"
+            // to use the anes crate parser.
+            buffer: Vec::with_capacity(256),
+            // TTY_BUFFER_SIZE is 1_024 bytes. How many ANSI escape sequences can
+            // fit? What is an average sequence length? Let's guess here
+            // and say that the average ANSI escape sequence length is 8 bytes. Thus
+            // the buffer size should be 1024/8=128 to avoid additional allocations
+            // when processing large amounts of data.
+            //
+            // There's no need to make it bigger, because when you look at the `try_read`
+            // method implementation, all events are consumed before the next TTY_BUFFER
+            // is processed -> events pushed.
+            internal_events: VecDeque::with_capacity(128),
+            escape_timeout: Duration::ZERO,
+            pending_escape: None,
+        }
+    }
+}
+
+impl Parser {
+    pub(crate) fn set_escape_timeout(&mut self, timeout: Duration) {
+        self.escape_timeout = timeout;
+    }
+
+    pub(crate) fn advance(&mut self, buffer: &[u8], more: bool) {
+        for (idx, byte) in buffer.iter().enumerate() {
+            let more = idx + 1 < buffer.len() || more;
+
+            if let Some(time) = self.pending_escape.take() {
+                // A second ESC can't continue the sequence, the pending one was a key press.
+                if *byte == b'\x1B' {
+                    self.buffer.clear();
+                    self.internal_events
+                        .push_back((InternalEvent::Event(Event::Key(KeyCode::Esc.into())), time));
+                }
+            }
+
+            self.buffer.push(*byte);
+
+            // A lone ESC at the end of the input is either an Esc key press or the start of an
+            // escape sequence that was split across reads. Wait for the escape timeout before
+            // deciding.
+            let wait_for_escape =
+                !more && self.buffer == [b'\x1B'] && !self.escape_timeout.is_zero();
+
+            match parse_event(&self.buffer, more || wait_for_escape) {
+                Ok(Some(ie)) => {
+                    self.internal_events.push_back((ie, Instant::now()));
+                    self.buffer.clear();
+                }
+                Ok(None) => {
+                    // Event can't be parsed, because we don't have enough bytes for
+                    // the current sequence. Keep the buffer and process next bytes.
+                    if wait_for_escape {
+                        self.pending_escape = Some(Instant::now());
+                    }
+                }
+                Err(_) => {
+                    // Event can't be parsed (not enough parameters, parameter is not a number, ...).
+                    // Clear the buffer and continue with another sequence.
+                    self.buffer.clear();
+                }
+            }
+        }
+    }
+
+    /// Discards the parsed input events and the bytes of an incomplete sequence.
+    pub(crate) fn discard_input(&mut self) {
+        self.buffer.clear();
+        self.pending_escape = None;
+        self.internal_events.retain(|(event, _)| !event.is_input());
+    }
+
+    /// Reports a pending ESC as an Esc key press once the escape timeout has expired.
+    pub(crate) fn resolve_pending_escape(&mut self) {
+        if let Some(time) = self.pending_escape {
+            if time.elapsed() >= self.escape_timeout {
+                self.pending_escape = None;
+                self.buffer.clear();
+                self.internal_events
+                    .push_back((InternalEvent::Event(Event::Key(KeyCode::Esc.into())), time));
+            }
+        }
+    }
+
+    /// Returns when a pending ESC is reported as an Esc key press, if there is one.
+    #[cfg(any(feature = "tokio", feature = "async-io"))]
+    pub(crate) fn pending_escape_deadline(&self) -> Option<Instant> {
+        self.pending_escape.map(|time| time + self.escape_timeout)
+    }
+
+    /// Returns the time to wait for input, shortened so that a pending ESC is resolved in time.
+    pub(crate) fn poll_timeout(&self, timeout: Option<Duration>) -> Option<Duration> {
+        let escape_leftover = self
+            .pending_escape
+            .map(|time| self.escape_timeout.saturating_sub(time.elapsed()));
+
+        match (timeout, escape_leftover) {
+            (Some(timeout), Some(escape_leftover)) => Some(timeout.min(escape_leftover)),
+            (timeout, None) => timeout,
+            (None, escape_leftover) => escape_leftover,
+        }
+    }
+}
+
+impl Iterator for Parser {
+    type Item = (InternalEvent, Instant);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.internal_events.pop_front()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{thread, time::Duration};
+
+    use super::Parser;
+    use crate::event::{Event, InternalEvent, KeyCode, KeyEvent, KeyModifiers};
+
+    fn next_event(parser: &mut Parser) -> Option<InternalEvent> {
+        parser.next().map(|(event, _)| event)
+    }
+
+    #[test]
+    fn test_lone_escape_without_timeout_is_esc_key() {
+        let mut parser = Parser::default();
+        parser.advance(b"\x1B", false);
+
+        assert_eq!(
+            next_event(&mut parser),
+            Some(InternalEvent::Event(Event::Key(KeyCode::Esc.into())))
+        );
+    }
+
+    #[test]
+    fn test_split_escape_sequence_within_timeout_is_alt_key() {
+        let mut parser = Parser::default();
+        parser.set_escape_timeout(Duration::from_secs(60));
+
+        parser.advance(b"\x1B", false);
+        parser.resolve_pending_escape();
+        assert_eq!(next_event(&mut parser), None);
+        assert!(parser.poll_timeout(None).unwrap() <= Duration::from_secs(60));
+
+        parser.advance(b"a", false);
+        assert_eq!(
+            next_event(&mut parser),
+            Some(InternalEvent::Event(Event::Key(KeyEvent::new(
+                KeyCode::Char('a'),
+                KeyModifiers::ALT
+            ))))
+        );
+        assert_eq!(parser.poll_timeout(None), None);
+    }
+
+    #[test]
+    fn test_pending_escape_is_esc_key_after_timeout() {
+        let mut parser = Parser::default();
+        parser.set_escape_timeout(Duration::from_millis(10));
+
+        parser.advance(b"\x1B", false);
+        thread::sleep(Duration::from_millis(20));
+        assert_eq!(parser.poll_timeout(None), Some(Duration::ZERO));
+        parser.resolve_pending_escape();
+
+        assert_eq!(
+            next_event(&mut parser),
+            Some(InternalEvent::Event(Event::Key(KeyCode::Esc.into())))
+        );
+
+        parser.advance(b"a", false);
+        assert_eq!(
+            next_event(&mut parser),
+            Some(InternalEvent::Event(Event::Key(KeyCode::Char('a').into())))
+        );
+    }
+
+    #[test]
+    fn test_pending_escape_followed_by_escape() {
+        let mut parser = Parser::default();
+        parser.set_escape_timeout(Duration::from_secs(60));
+
+        parser.advance(b"\x1B", false);
+        parser.advance(b"\x1B[A", false);
+
+        assert_eq!(
+            next_event(&mut parser),
+            Some(InternalEvent::Event(Event::Key(KeyCode::Esc.into())))
+        );
+        assert_eq!(
+            next_event(&mut parser),
+            Some(InternalEvent::Event(Event::Key(KeyCode::Up.into())))
+        );
+    }
+}
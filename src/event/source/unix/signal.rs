@@ -0,0 +1,305 @@
+use std::{
+    io::{self, Read},
+    os::{
+        raw::c_int,
+        unix::{
+            io::{AsFd, AsRawFd, BorrowedFd, RawFd},
+            net::UnixStream,
+        },
+    },
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    time::Instant,
+};
+
+use parking_lot::{MappedMutexGuard, Mutex, MutexGuard};
+use signal_hook::{
+    consts::{SIGCONT, SIGHUP, SIGINT, SIGQUIT, SIGTERM, SIGTSTP, SIGWINCH},
+    flag,
+    low_level::pipe,
+    SigId,
+};
+
+use crate::event::{Event, InternalEvent, SignalKind};
+
+/// The signals handled by the event sources.
+const SIGNALS: [c_int; 1] = [SIGWINCH];
+
+/// The receiving end of a pipe the handler of one of `SIGNALS` writes to.
+///
+/// Every event source has its own receivers, so that every source sees the signal. The handler
+/// is unregistered when the receiver is dropped.
+pub(crate) struct SignalReceiver {
+    signal: c_int,
+    receiver: UnixStream,
+    id: SigId,
+}
+
+impl SignalReceiver {
+    /// Handles the signal if it was received, returning the event it results in, if any.
+    pub(crate) fn read(&self) -> io::Result<Option<(InternalEvent, Instant)>> {
+        if drain(&self.receiver)? {
+            handle_signal(self.signal)
+        } else {
+            Ok(None)
+        }
+    }
+}
+
+impl AsRawFd for SignalReceiver {
+    fn as_raw_fd(&self) -> RawFd {
+        self.receiver.as_raw_fd()
+    }
+}
+
+impl AsFd for SignalReceiver {
+    fn as_fd(&self) -> BorrowedFd<'_> {
+        self.receiver.as_fd()
+    }
+}
+
+impl Drop for SignalReceiver {
+    fn drop(&mut self) {
+        signal_hook::low_level::unregister(self.id);
+    }
+}
+
+/// Returns receivers that become readable when one of `SIGNALS` is received.
+pub(crate) fn signal_receivers() -> io::Result<Vec<SignalReceiver>> {
+    SIGNALS
+        .iter()
+        .map(|&signal| {
+            let (receiver, sender) = nonblocking_unix_pair()?;
+            let id = pipe::register(signal, sender)?;
+            Ok(SignalReceiver {
+                signal,
+                receiver,
+                id,
+            })
+        })
+        .collect()
+}
+
+fn nonblocking_unix_pair() -> io::Result<(UnixStream, UnixStream)> {
+    let (receiver, sender) = UnixStream::pair()?;
+    receiver.set_nonblocking(true)?;
+    sender.set_nonblocking(true)?;
+    Ok((receiver, sender))
+}
+
+/// Reads everything from a non-blocking receiver, returning `true` if there was anything.
+fn drain(mut receiver: &UnixStream) -> io::Result<bool> {
+    let mut buffer = [0; 32];
+    let mut received = false;
+    loop {
+        match receiver.read(&mut buffer) {
+            Ok(0) => return Ok(received),
+            Ok(_) => received = true,
+            Err(e) if e.kind() == io::ErrorKind::WouldBlock => return Ok(received),
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+            Err(e) => return Err(e),
+        }
+    }
+}
+
+/// Handles a signal received by an event source, returning the event it results in, if any.
+fn handle_signal(signal: c_int) -> io::Result<Option<(InternalEvent, Instant)>> {
+    match signal {
+        SIGWINCH => {
+            // TODO Should we remove tput?
+            //
+            // This can take a really long time, because terminal::size can
+            // launch new process (tput) and then it parses its output. It's
+            // not a really long time from the absolute time point of view, but
+            // it's a really long time from the mio, async-std/tokio executor, ...
+            // point of view.
+            let time = Instant::now();
+            let new_size = crate::terminal::size()?;
+            Ok(Some((
+                InternalEvent::Event(Event::Resize(new_size.0, new_size.1)),
+                time,
+            )))
+        }
+        _ => Ok(None),
+    }
+}
+
+/// The signals delivered as `Event::Signal` after `enable_signal_events`.
+///
+/// A signal handler, once registered, stays registered. Disabling the events makes the handler
+/// run the default action of the signal again.
+struct SignalEvents {
+    // Written to by the signal handlers to wake up the event sources
+    receiver: UnixStream,
+    sender: UnixStream,
+    registrations: Vec<Registration>,
+    suspend_handling: Option<SuspendHandling>,
+}
+
+struct Registration {
+    kind: SignalKind,
+    // Set by the signal handler, cleared when the event is read
+    received: Arc<AtomicBool>,
+    // The signal handler runs the default action while set
+    disabled: Arc<AtomicBool>,
+}
+
+/// The handlers of `SIGTSTP` and `SIGCONT` registered by `enable_suspend_handling`.
+struct SuspendHandling {
+    // The `SIGTSTP` handler stops the process right away while set
+    disabled: Arc<AtomicBool>,
+    // Set by the signal handlers, cleared when the signals are handled
+    stopped: Arc<AtomicBool>,
+    continued: Arc<AtomicBool>,
+}
+
+static SIGNAL_EVENTS: Mutex<Option<SignalEvents>> = parking_lot::const_mutex(None);
+
+fn signal_events() -> io::Result<MappedMutexGuard<'static, SignalEvents>> {
+    let mut signal_events = SIGNAL_EVENTS.lock();
+    if signal_events.is_none() {
+        let (receiver, sender) = nonblocking_unix_pair()?;
+        *signal_events = Some(SignalEvents {
+            receiver,
+            sender,
+            registrations: Vec::new(),
+            suspend_handling: None,
+        });
+    }
+    Ok(MutexGuard::map(signal_events, |signal_events| {
+        signal_events.as_mut().unwrap()
+    }))
+}
+
+fn signal_number(kind: SignalKind) -> c_int {
+    match kind {
+        SignalKind::Interrupt => SIGINT,
+        SignalKind::Terminate => SIGTERM,
+        SignalKind::Hangup => SIGHUP,
+        SignalKind::Quit => SIGQUIT,
+    }
+}
+
+pub(crate) fn enable_signal_events(kinds: &[SignalKind]) -> io::Result<()> {
+    let mut signal_events = signal_events()?;
+    for &kind in kinds {
+        if let Some(registration) = signal_events
+            .registrations
+            .iter()
+            .find(|registration| registration.kind == kind)
+        {
+            registration.disabled.store(false, Ordering::SeqCst);
+            continue;
+        }
+
+        let signal = signal_number(kind);
+        let received = Arc::new(AtomicBool::new(false));
+        let disabled = Arc::new(AtomicBool::new(false));
+        // The handlers run in the order they're registered.
+        flag::register_conditional_default(signal, Arc::clone(&disabled))?;
+        flag::register(signal, Arc::clone(&received))?;
+        pipe::register(signal, signal_events.sender.try_clone()?)?;
+        signal_events.registrations.push(Registration {
+            kind,
+            received,
+            disabled,
+        });
+    }
+    Ok(())
+}
+
+pub(crate) fn disable_signal_events() {
+    if let Some(signal_events) = SIGNAL_EVENTS.lock().as_ref() {
+        for registration in &signal_events.registrations {
+            registration.disabled.store(true, Ordering::SeqCst);
+            registration.received.store(false, Ordering::SeqCst);
+        }
+    }
+}
+
+pub(crate) fn enable_suspend_handling() -> io::Result<()> {
+    let mut signal_events = signal_events()?;
+    if let Some(suspend_handling) = &signal_events.suspend_handling {
+        suspend_handling.disabled.store(false, Ordering::SeqCst);
+        return Ok(());
+    }
+
+    let disabled = Arc::new(AtomicBool::new(false));
+    let stopped = Arc::new(AtomicBool::new(false));
+    let continued = Arc::new(AtomicBool::new(false));
+    // The handlers run in the order they're registered.
+    flag::register_conditional_default(SIGTSTP, Arc::clone(&disabled))?;
+    flag::register(SIGTSTP, Arc::clone(&stopped))?;
+    pipe::register(SIGTSTP, signal_events.sender.try_clone()?)?;
+    flag::register(SIGCONT, Arc::clone(&continued))?;
+    pipe::register(SIGCONT, signal_events.sender.try_clone()?)?;
+    signal_events.suspend_handling = Some(SuspendHandling {
+        disabled,
+        stopped,
+        continued,
+    });
+    Ok(())
+}
+
+pub(crate) fn disable_suspend_handling() {
+    if let Some(suspend_handling) = SIGNAL_EVENTS
+        .lock()
+        .as_ref()
+        .and_then(|signal_events| signal_events.suspend_handling.as_ref())
+    {
+        suspend_handling.disabled.store(true, Ordering::SeqCst);
+        suspend_handling.stopped.store(false, Ordering::SeqCst);
+        suspend_handling.continued.store(false, Ordering::SeqCst);
+    }
+}
+
+/// Returns a stream that becomes readable when a signal event is received.
+pub(crate) fn signal_event_receiver() -> io::Result<UnixStream> {
+    signal_events()?.receiver.try_clone()
+}
+
+/// Drains the receiver and returns the events of the received signals.
+///
+/// A received `SIGTSTP` suspends the process, which results in an `Event::Resumed` once it's
+/// continued.
+pub(crate) fn read_signal_events(
+    receiver: &UnixStream,
+) -> io::Result<Vec<(InternalEvent, Instant)>> {
+    drain(receiver)?;
+
+    let (mut events, suspend_flags) = {
+        let signal_events = signal_events()?;
+        let time = Instant::now();
+        let events: Vec<_> = signal_events
+            .registrations
+            .iter()
+            .filter(|registration| registration.received.swap(false, Ordering::SeqCst))
+            .map(|registration| (InternalEvent::Event(Event::Signal(registration.kind)), time))
+            .collect();
+        let suspend_flags = signal_events.suspend_handling.as_ref().map(|handling| {
+            let enabled = !handling.disabled.load(Ordering::SeqCst);
+            (
+                handling.stopped.swap(false, Ordering::SeqCst) && enabled,
+                Arc::clone(&handling.continued),
+                enabled,
+            )
+        });
+        (events, suspend_flags)
+    };
+
+    if let Some((stopped, continued, enabled)) = suspend_flags {
+        if stopped {
+            // The process is continued when this returns.
+            crate::terminal::sys::suspend()?;
+        }
+        if continued.swap(false, Ordering::SeqCst) && enabled {
+            // The shell restores its own terminal settings while the process is stopped.
+            let time = Instant::now();
+            crate::terminal::sys::refresh_raw_mode()?;
+            events.push((InternalEvent::Event(Event::Resumed), time));
+        }
+    }
+    Ok(events)
+}
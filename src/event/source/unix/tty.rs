@@ -1,19 +1,30 @@
 #[cfg(feature = "libc")]
 use std::os::unix::prelude::AsRawFd;
-use std::{collections::VecDeque, io, os::unix::net::UnixStream, time::Duration};
+use std::{
+    collections::VecDeque,
+    io,
+    os::unix::net::UnixStream,
+    time::{Duration, Instant},
+};
 
 #[cfg(not(feature = "libc"))]
-use rustix::fd::{AsFd, AsRawFd};
-
-use signal_hook::low_level::pipe;
+use rustix::fd::AsRawFd;
 
 use crate::event::timeout::PollTimeout;
-use crate::event::Event;
 use filedescriptor::{poll, pollfd, POLLIN};
 
 #[cfg(feature = "event-stream")]
 use crate::event::sys::Waker;
-use crate::event::{source::EventSource, sys::unix::parse::parse_event, InternalEvent};
+use crate::event::{
+    source::{
+        unix::{
+            parser::{escape_timeout, Parser},
+            signal::{read_signal_events, signal_event_receiver, signal_receivers, SignalReceiver},
+        },
+        EventSource,
+    },
+    InternalEvent,
+};
 use crate::terminal::sys::file_descriptor::{tty_fd, FileDesc};
 
 /// Holds a prototypical Waker and a receiver we can wait on when doing select().
@@ -43,7 +54,10 @@ pub(crate) struct UnixInternalEventSource {
     parser: Parser,
     tty_buffer: [u8; TTY_BUFFER_SIZE],
     tty: FileDesc<'static>,
-    winch_signal_receiver: UnixStream,
+    signal_receivers: Vec<SignalReceiver>,
+    signal_event_receiver: UnixStream,
+    // Events of signals received together with another one
+    signal_events: VecDeque<(InternalEvent, Instant)>,
     #[cfg(feature = "event-stream")]
     wake_pipe: WakePipe,
 }
@@ -65,15 +79,9 @@ impl UnixInternalEventSource {
             parser: Parser::default(),
             tty_buffer: [0u8; TTY_BUFFER_SIZE],
             tty: input_fd,
-            winch_signal_receiver: {
-                let (receiver, sender) = nonblocking_unix_pair()?;
-                // Unregistering is unnecessary because EventSource is a singleton
-                #[cfg(feature = "libc")]
-                pipe::register(libc::SIGWINCH, sender)?;
-                #[cfg(not(feature = "libc"))]
-                pipe::register(rustix::process::Signal::Winch as i32, sender)?;
-                receiver
-            },
+            signal_receivers: signal_receivers()?,
+            signal_event_receiver: signal_event_receiver()?,
+            signal_events: VecDeque::new(),
             #[cfg(feature = "event-stream")]
             wake_pipe: WakePipe::new()?,
         })
@@ -99,7 +107,10 @@ fn read_complete(fd: &FileDesc, buf: &mut [u8]) -> io::Result<usize> {
 }
 
 impl EventSource for UnixInternalEventSource {
-    fn try_read(&mut self, timeout: Option<Duration>) -> io::Result<Option<InternalEvent>> {
+    fn try_read(
+        &mut self,
+        timeout: Option<Duration>,
+    ) -> io::Result<Option<(InternalEvent, Instant)>> {
         let timeout = PollTimeout::new(timeout);
 
         fn make_pollfd<F: AsRawFd>(fd: &F) -> pollfd {
@@ -110,25 +121,25 @@ impl EventSource for UnixInternalEventSource {
             }
         }
 
-        #[cfg(not(feature = "event-stream"))]
-        let mut fds = [
-            make_pollfd(&self.tty),
-            make_pollfd(&self.winch_signal_receiver),
-        ];
-
+        // The tty first, then the signal receivers and the wake pipe last
+        let mut fds = vec![make_pollfd(&self.tty)];
+        fds.extend(self.signal_receivers.iter().map(make_pollfd));
+        fds.push(make_pollfd(&self.signal_event_receiver));
         #[cfg(feature = "event-stream")]
-        let mut fds = [
-            make_pollfd(&self.tty),
-            make_pollfd(&self.winch_signal_receiver),
-            make_pollfd(&self.wake_pipe.receiver),
-        ];
+        fds.push(make_pollfd(&self.wake_pipe.receiver));
+
+        self.parser.set_escape_timeout(escape_timeout());
 
         while timeout.leftover().map_or(true, |t| !t.is_zero()) {
             // check if there are buffered events from the last read
+            self.parser.resolve_pending_escape();
             if let Some(event) = self.parser.next() {
                 return Ok(Some(event));
             }
-            match poll(&mut fds, timeout.leftover()) {
+            if let Some(event) = self.signal_events.pop_front() {
+                return Ok(Some(event));
+            }
+            match poll(&mut fds, self.parser.poll_timeout(timeout.leftover())) {
                 Err(filedescriptor::Error::Poll(e)) | Err(filedescriptor::Error::Io(e)) => {
                     match e.kind() {
                         // retry on EINTR
@@ -163,28 +174,25 @@ impl EventSource for UnixInternalEventSource {
                     }
                 }
             }
-            if fds[1].revents & POLLIN != 0 {
-                #[cfg(feature = "libc")]
-                let fd = FileDesc::new(self.winch_signal_receiver.as_raw_fd(), false);
-                #[cfg(not(feature = "libc"))]
-                let fd = FileDesc::Borrowed(self.winch_signal_receiver.as_fd());
-                // drain the pipe
-                while read_complete(&fd, &mut [0; 1024])? != 0 {}
-                // TODO Should we remove tput?
-                //
-                // This can take a really long time, because terminal::size can
-                // launch new process (tput) and then it parses its output. It's
-                // not a really long time from the absolute time point of view, but
-                // it's a really long time from the mio, async-std/tokio executor, ...
-                // point of view.
-                let new_size = crate::terminal::size()?;
-                return Ok(Some(InternalEvent::Event(Event::Resize(
-                    new_size.0, new_size.1,
-                ))));
+            for (index, receiver) in self.signal_receivers.iter().enumerate() {
+                if fds[index + 1].revents & POLLIN == 0 {
+                    continue;
+                }
+                if let Some(event) = receiver.read()? {
+                    return Ok(Some(event));
+                }
+            }
+
+            if fds[self.signal_receivers.len() + 1].revents & POLLIN != 0 {
+                self.signal_events
+                    .extend(read_signal_events(&self.signal_event_receiver)?);
+                if let Some(event) = self.signal_events.pop_front() {
+                    return Ok(Some(event));
+                }
             }
 
             #[cfg(feature = "event-stream")]
-            if fds[2].revents & POLLIN != 0 {
+            if fds[self.signal_receivers.len() + 2].revents & POLLIN != 0 {
                 let fd = FileDesc::new(self.wake_pipe.receiver.as_raw_fd(), false);
                 // drain the pipe
                 while read_complete(&fd, &mut [0; 1024])? != 0 {}
@@ -195,7 +203,12 @@ impl EventSource for UnixInternalEventSource {
                 ));
             }
         }
-        Ok(None)
+        self.parser.resolve_pending_escape();
+        Ok(self.parser.next())
+    }
+
+    fn discard_input(&mut self) {
+        self.parser.discard_input();
     }
 
     #[cfg(feature = "event-stream")]
@@ -203,73 +216,3 @@ impl EventSource for UnixInternalEventSource {
         self.wake_pipe.waker.clone()
     }
 }
-
-//
-// Following `Parser` structure exists for two reasons:
-//
-//  * mimic anes Parser interface
-//  * move the advancing, parsing, ... stuff out of the `try_read` method
-//
-#[derive(Debug)]
-struct Parser {
-    buffer: Vec<u8>,
-    internal_events: VecDeque<InternalEvent>,
-}
-
-impl Default for Parser {
-    fn default() -> Self {
-        Parser {
-            // This buffer is used for -> 1 <- ANSI escape sequence. Are we
-            // aware of any ANSI escape sequence that is bigger? Can we make
-            // it smaller?
-            //
-            // Probably not worth spending more time on this as "there's a plan"
-            // to use the anes crate parser.
-            buffer: Vec::with_capacity(256),
-            // TTY_BUFFER_SIZE is 1_024 bytes. How many ANSI escape sequences can
-            // fit? What is an average sequence length? Let's guess here
-            // and say that the average ANSI escape sequence length is 8 bytes. Thus
-            // the buffer size should be 1024/8=128 to avoid additional allocations
-            // when processing large amounts of data.
-            //
-            // There's no need to make it bigger, because when you look at the `try_read`
-            // method implementation, all events are consumed before the next TTY_BUFFER
-            // is processed -> events pushed.
-            internal_events: VecDeque::with_capacity(128),
-        }
-    }
-}
-
-impl Parser {
-    fn advance(&mut self, buffer: &[u8], more: bool) {
-        for (idx, byte) in buffer.iter().enumerate() {
-            let more = idx + 1 < buffer.len() || more;
-
-            self.buffer.push(*byte);
-
-            match parse_event(&self.buffer, more) {
-                Ok(Some(ie)) => {
-                    self.internal_events.push_back(ie);
-                    self.buffer.clear();
-                }
-                Ok(None) => {
-                    // Event can't be parsed, because we don't have enough bytes for
-                    // the current sequence. Keep the buffer and process next bytes.
-                }
-                Err(_) => {
-                    // Event can't be parsed (not enough parameters, parameter is not a number, ...).
-                    // Clear the buffer and continue with another sequence.
-                    self.buffer.clear();
-                }
-            }
-        }
-    }
-}
-
-impl Iterator for Parser {
-    type Item = InternalEvent;
-
-    fn next(&mut self) -> Option<Self::Item> {
-        self.internal_events.pop_front()
-    }
-}
@@ -1,4 +1,4 @@
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
 use crossterm_winapi::{Console, Handle, InputRecord};
 
@@ -13,9 +13,19 @@ use crate::event::{
     source::EventSource,
     sys::windows::parse::{handle_key_event, handle_mouse_event},
     timeout::PollTimeout,
-    InternalEvent,
+    InternalEvent, SignalKind,
 };
 
+/// Signals are a UNIX concept, there's nothing to report on Windows.
+pub(crate) fn enable_signal_events(_: &[SignalKind]) -> std::io::Result<()> {
+    Err(std::io::Error::new(
+        std::io::ErrorKind::Unsupported,
+        "Signal events are not supported on Windows.",
+    ))
+}
+
+pub(crate) fn disable_signal_events() {}
+
 pub(crate) struct WindowsEventSource {
     console: Console,
     poll: WinApiPoll,
@@ -41,25 +51,27 @@ impl WindowsEventSource {
 }
 
 impl EventSource for WindowsEventSource {
-    fn try_read(&mut self, timeout: Option<Duration>) -> std::io::Result<Option<InternalEvent>> {
+    fn try_read(
+        &mut self,
+        timeout: Option<Duration>,
+    ) -> std::io::Result<Option<(InternalEvent, Instant)>> {
         let poll_timeout = PollTimeout::new(timeout);
 
         loop {
             if let Some(event_ready) = self.poll.poll(poll_timeout.leftover())? {
                 let number = self.console.number_of_console_input_events()?;
                 if event_ready && number != 0 {
-                    let event = match self.console.read_single_input_event()? {
+                    let record = self.console.read_single_input_event()?;
+                    let time = Instant::now();
+                    let event = match record {
                         InputRecord::KeyEvent(record) => {
                             handle_key_event(record, &mut self.surrogate_buffer)
                         }
                         InputRecord::MouseEvent(record) => {
                             let mouse_event =
                                 handle_mouse_event(record, &self.mouse_buttons_pressed);
-                            self.mouse_buttons_pressed = MouseButtonsPressed {
-                                left: record.button_state.left_button(),
-                                right: record.button_state.right_button(),
-                                middle: record.button_state.middle_button(),
-                            };
+                            self.mouse_buttons_pressed =
+                                MouseButtonsPressed::from(record.button_state);
 
                             mouse_event
                         }
@@ -82,7 +94,7 @@ impl EventSource for WindowsEventSource {
                     };
 
                     if let Some(event) = event {
-                        return Ok(Some(InternalEvent::Event(event)));
+                        return Ok(Some((InternalEvent::Event(event), time)));
                     }
                 }
             }
@@ -93,6 +105,10 @@ impl EventSource for WindowsEventSource {
         }
     }
 
+    fn discard_input(&mut self) {
+        self.surrogate_buffer = None;
+    }
+
     #[cfg(feature = "event-stream")]
     fn waker(&self) -> Waker {
         self.poll.waker()
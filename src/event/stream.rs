@@ -1,4 +1,5 @@
 use std::{
+    future::Future,
     io,
     pin::Pin,
     sync::{
@@ -12,6 +13,15 @@ use std::{
 };
 
 use futures_core::stream::Stream;
+use parking_lot::{Condvar, Mutex};
+
+#[cfg(all(unix, any(feature = "tokio", feature = "async-io")))]
+mod native;
+
+#[cfg(all(unix, any(feature = "tokio", feature = "async-io")))]
+pub(crate) use self::native::{
+    discard_input as discard_native_input, is_reading as native_stream_is_reading,
+};
 
 use crate::event::{
     filter::EventFilter, lock_internal_event_reader, poll_internal, read_internal, sys::Waker,
@@ -29,8 +39,37 @@ use crate::event::{
 ///
 /// Check the [examples](https://github.com/crossterm-rs/crossterm/tree/master/examples) folder to see how to use
 /// it (`event-stream-*`).
+///
+/// By default, the stream waits for events on a helper thread. On UNIX systems, the `tokio` and
+/// `async-io` feature flags register the terminal with the reactor of the runtime instead, so
+/// that no thread is needed. A stream created inside of a tokio runtime uses tokio's reactor,
+/// other streams use async-io's reactor. All streams share the terminal input, every event is
+/// received by one of them.
+///
+/// Use [`cursor_position`](#method.cursor_position) and
+/// [`supports_keyboard_enhancement`](#method.supports_keyboard_enhancement) to query the terminal
+/// while streams are in use, the blocking query functions can race with the streams.
+///
+/// Reading the terminal input with both the reactor and the blocking functions is not
+/// supported. While a stream registered with the reactor exists, [`read`](fn.read.html),
+/// [`poll`](fn.poll.html), [`position`](../cursor/fn.position.html),
+/// [`supports_keyboard_enhancement`](../terminal/fn.supports_keyboard_enhancement.html) and
+/// [`query_grapheme_clustering`](../terminal/fn.query_grapheme_clustering.html) return an error.
 #[derive(Debug)]
 pub struct EventStream {
+    inner: Inner,
+}
+
+#[derive(Debug)]
+enum Inner {
+    Thread(ThreadStream),
+    #[cfg(all(unix, any(feature = "tokio", feature = "async-io")))]
+    Native(native::NativeEventStream),
+}
+
+/// A stream waiting for events on a helper thread.
+#[derive(Debug)]
+struct ThreadStream {
     poll_internal_waker: Waker,
     stream_wake_task_executed: Arc<AtomicBool>,
     stream_wake_task_should_shutdown: Arc<AtomicBool>,
@@ -39,11 +78,128 @@ pub struct EventStream {
 
 impl Default for EventStream {
     fn default() -> Self {
+        // Falls back to the helper thread if the terminal can't be registered with the reactor.
+        #[cfg(all(unix, any(feature = "tokio", feature = "async-io")))]
+        if let Ok(Some(stream)) = native::NativeEventStream::new() {
+            return EventStream {
+                inner: Inner::Native(stream),
+            };
+        }
+
+        EventStream {
+            inner: Inner::Thread(ThreadStream::new()),
+        }
+    }
+}
+
+impl EventStream {
+    /// Constructs a new instance of `EventStream`.
+    pub fn new() -> EventStream {
+        EventStream::default()
+    }
+
+    /// Returns the cursor position (column, row) without blocking the executor.
+    ///
+    /// This is the async version of [`position`](../cursor/fn.position.html). The events read
+    /// while waiting for the reply are returned by the streams afterwards, the future doesn't
+    /// borrow the stream so it can be polled meanwhile.
+    pub fn cursor_position(&self) -> impl Future<Output = io::Result<(u16, u16)>> + Send + 'static {
+        let inner = self.query_inner();
+        async move {
+            match inner {
+                QueryInner::Thread => ThreadQuery::spawn(crate::cursor::position).await,
+                #[cfg(all(unix, any(feature = "tokio", feature = "async-io")))]
+                QueryInner::Native(stream) => in_raw_mode(stream.cursor_position()).await,
+            }
+        }
+    }
+
+    /// Queries the terminal's support for progressive keyboard enhancement without blocking
+    /// the executor.
+    ///
+    /// This is the async version of
+    /// [`supports_keyboard_enhancement`](../terminal/fn.supports_keyboard_enhancement.html). The
+    /// events read while waiting for the reply are returned by the streams afterwards, the
+    /// future doesn't borrow the stream so it can be polled meanwhile.
+    pub fn supports_keyboard_enhancement(
+        &self,
+    ) -> impl Future<Output = io::Result<bool>> + Send + 'static {
+        let inner = self.query_inner();
+        async move {
+            match inner {
+                QueryInner::Thread => {
+                    ThreadQuery::spawn(crate::terminal::supports_keyboard_enhancement).await
+                }
+                #[cfg(all(unix, any(feature = "tokio", feature = "async-io")))]
+                QueryInner::Native(stream) => {
+                    in_raw_mode(stream.supports_keyboard_enhancement()).await
+                }
+            }
+        }
+    }
+
+    fn query_inner(&self) -> QueryInner {
+        match &self.inner {
+            Inner::Thread(_) => QueryInner::Thread,
+            #[cfg(all(unix, any(feature = "tokio", feature = "async-io")))]
+            Inner::Native(stream) => QueryInner::Native(stream.clone()),
+        }
+    }
+}
+
+/// What a query needs from the stream, so that its future doesn't borrow the stream.
+enum QueryInner {
+    Thread,
+    #[cfg(all(unix, any(feature = "tokio", feature = "async-io")))]
+    Native(native::NativeEventStream),
+}
+
+/// Enables raw mode while the query is running, like the blocking query functions.
+#[cfg(all(unix, any(feature = "tokio", feature = "async-io")))]
+async fn in_raw_mode<T>(query: impl Future<Output = io::Result<T>>) -> io::Result<T> {
+    if crate::terminal::is_raw_mode_enabled()? {
+        return query.await;
+    }
+    crate::terminal::enable_raw_mode()?;
+    let dropped = DisableRawModeOnDrop;
+    let result = query.await;
+    std::mem::forget(dropped);
+    crate::terminal::disable_raw_mode()?;
+    result
+}
+
+/// Disables raw mode if a query is dropped before it's done.
+#[cfg(all(unix, any(feature = "tokio", feature = "async-io")))]
+struct DisableRawModeOnDrop;
+
+#[cfg(all(unix, any(feature = "tokio", feature = "async-io")))]
+impl Drop for DisableRawModeOnDrop {
+    fn drop(&mut self) {
+        let _ = crate::terminal::disable_raw_mode();
+    }
+}
+
+impl Stream for EventStream {
+    type Item = io::Result<Event>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        match &mut self.get_mut().inner {
+            Inner::Thread(stream) => stream.poll_next(cx),
+            #[cfg(all(unix, any(feature = "tokio", feature = "async-io")))]
+            Inner::Native(stream) => stream.poll_next(cx).map(Some),
+        }
+    }
+}
+
+impl ThreadStream {
+    fn new() -> ThreadStream {
         let (task_sender, receiver) = mpsc::sync_channel::<Task>(1);
 
         thread::spawn(move || {
             while let Ok(task) = receiver.recv() {
                 loop {
+                    wait_for_thread_queries();
+
                     if let Ok(true) = poll_internal(None, &EventFilter) {
                         break;
                     }
@@ -58,8 +214,11 @@ impl Default for EventStream {
             }
         });
 
-        EventStream {
-            poll_internal_waker: lock_internal_event_reader().waker(),
+        let poll_internal_waker = lock_internal_event_reader().waker();
+        *HELPER_THREAD_WAKER.lock() = Some(poll_internal_waker.clone());
+
+        ThreadStream {
+            poll_internal_waker,
             stream_wake_task_executed: Arc::new(AtomicBool::new(false)),
             stream_wake_task_should_shutdown: Arc::new(AtomicBool::new(false)),
             task_sender,
@@ -67,13 +226,6 @@ impl Default for EventStream {
     }
 }
 
-impl EventStream {
-    /// Constructs a new instance of `EventStream`.
-    pub fn new() -> EventStream {
-        EventStream::default()
-    }
-}
-
 struct Task {
     stream_waker: std::task::Waker,
     stream_wake_task_executed: Arc<AtomicBool>,
@@ -98,10 +250,8 @@ struct Task {
 // is up and sitting in the poll_internal. User wants to drop the EventStream.
 // We have to wake up the poll_internal (force it to return Ok(false)) and quit
 // the thread before we drop.
-impl Stream for EventStream {
-    type Item = io::Result<Event>;
-
-    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+impl ThreadStream {
+    fn poll_next(&mut self, cx: &mut Context<'_>) -> Poll<Option<io::Result<Event>>> {
         let result = match poll_internal(Some(Duration::from_secs(0)), &EventFilter) {
             Ok(true) => match read_internal(&EventFilter) {
                 Ok(InternalEvent::Event(event)) => Poll::Ready(Some(Ok(event))),
@@ -137,10 +287,88 @@ impl Stream for EventStream {
     }
 }
 
-impl Drop for EventStream {
+impl Drop for ThreadStream {
     fn drop(&mut self) {
         self.stream_wake_task_should_shutdown
             .store(true, Ordering::SeqCst);
         let _ = self.poll_internal_waker.wake();
     }
 }
+
+/// The number of running thread queries, the helper threads don't read events while there are
+/// any, so that they don't take the replies.
+static THREAD_QUERIES: Mutex<usize> = parking_lot::const_mutex(0);
+static THREAD_QUERIES_DONE: Condvar = Condvar::new();
+
+/// Wakes the event reader shared by the helper threads, set by the helper thread streams.
+static HELPER_THREAD_WAKER: Mutex<Option<Waker>> = parking_lot::const_mutex(None);
+
+fn wait_for_thread_queries() {
+    let mut thread_queries = THREAD_QUERIES.lock();
+    while *thread_queries > 0 {
+        THREAD_QUERIES_DONE.wait(&mut thread_queries);
+    }
+}
+
+/// A blocking query function running on another thread.
+struct ThreadQuery<T> {
+    state: Arc<Mutex<ThreadQueryState<T>>>,
+}
+
+struct ThreadQueryState<T> {
+    result: Option<io::Result<T>>,
+    waker: Option<std::task::Waker>,
+}
+
+/// Runs `f` while the helper threads leave the event reader alone, so that the reader isn't
+/// locked by a helper thread waiting for events.
+pub(crate) fn pause_helper_threads<T>(f: impl FnOnce() -> T) -> T {
+    *THREAD_QUERIES.lock() += 1;
+    // Release the event reader if a helper thread is waiting for events.
+    if let Some(waker) = HELPER_THREAD_WAKER.lock().as_ref() {
+        let _ = waker.wake();
+    }
+
+    let result = f();
+
+    *THREAD_QUERIES.lock() -= 1;
+    THREAD_QUERIES_DONE.notify_all();
+    result
+}
+
+impl<T: Send + 'static> ThreadQuery<T> {
+    fn spawn(query: fn() -> io::Result<T>) -> ThreadQuery<T> {
+        let state = Arc::new(Mutex::new(ThreadQueryState {
+            result: None,
+            waker: None,
+        }));
+        let thread_state = state.clone();
+
+        thread::spawn(move || {
+            let query_result = pause_helper_threads(query);
+
+            let mut state = thread_state.lock();
+            state.result = Some(query_result);
+            if let Some(waker) = state.waker.take() {
+                waker.wake();
+            }
+        });
+
+        ThreadQuery { state }
+    }
+}
+
+impl<T> Future for ThreadQuery<T> {
+    type Output = io::Result<T>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let mut state = self.state.lock();
+        match state.result.take() {
+            Some(query_result) => Poll::Ready(query_result),
+            None => {
+                state.waker = Some(cx.waker().clone());
+                Poll::Pending
+            }
+        }
+    }
+}
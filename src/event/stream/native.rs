@@ -0,0 +1,744 @@
+//! An `EventStream` implementation that registers the terminal and the signal pipes with the
+//! reactor of the async runtime, instead of waiting for events on a helper thread.
+
+use std::{
+    collections::VecDeque,
+    fmt,
+    fs::File,
+    future::Future,
+    io::{self, Read, Write},
+    os::unix::{
+        io::{AsFd, AsRawFd},
+        net::UnixStream,
+    },
+    pin::Pin,
+    sync::{Arc, Weak},
+    task::{Context, Poll, Wake, Waker},
+    time::{Duration, Instant},
+};
+
+use parking_lot::Mutex;
+
+use crate::event::{
+    filter::{CursorPositionFilter, Filter, KeyboardEnhancementFlagsFilter},
+    source::unix::{
+        parser::{escape_timeout, Parser},
+        signal::{read_signal_events, signal_event_receiver, signal_receivers, SignalReceiver},
+    },
+    subscription, Event, InternalEvent,
+};
+
+// Same as the `TTY_BUFFER_SIZE` of the event sources.
+const TTY_BUFFER_SIZE: usize = 1_024;
+
+// Same as the timeout of the blocking query functions.
+const QUERY_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// The source shared by all native streams, dropped together with the last stream.
+static SHARED_SOURCE: Mutex<Option<Weak<SharedSource>>> = parking_lot::const_mutex(None);
+
+/// Returns `true` while a native stream reads the terminal input.
+pub(crate) fn is_reading() -> bool {
+    SHARED_SOURCE
+        .lock()
+        .as_ref()
+        .is_some_and(|source| source.strong_count() > 0)
+}
+
+/// Discards the input events read by the shared source but not returned yet.
+pub(crate) fn discard_input() {
+    let source = SHARED_SOURCE.lock().as_ref().and_then(Weak::upgrade);
+    if let Some(source) = source {
+        let mut state = source.state.lock();
+        state.events.retain(|(event, _)| !event.is_input());
+        state.parser.discard_input();
+    }
+}
+
+/// A stream of events driven by the reactor of the async runtime.
+#[derive(Clone)]
+pub(crate) struct NativeEventStream {
+    source: Arc<SharedSource>,
+}
+
+impl NativeEventStream {
+    /// Creates a stream, or returns `None` if there's no reactor to register with.
+    pub(crate) fn new() -> io::Result<Option<NativeEventStream>> {
+        NativeEventStream::with_tty(|| {
+            let tty = File::options().read(true).open("/dev/tty")?;
+            let tty_writer = File::options().write(true).open("/dev/tty")?;
+            Ok((tty, tty_writer))
+        })
+    }
+
+    /// Creates a stream sharing the source of the other streams, `open_tty` opens the terminal
+    /// for reading and for writing if there are none.
+    fn with_tty(
+        open_tty: impl FnOnce() -> io::Result<(File, File)>,
+    ) -> io::Result<Option<NativeEventStream>> {
+        let reactor = match Reactor::current() {
+            Some(reactor) => reactor,
+            None => return Ok(None),
+        };
+
+        let mut shared_source = SHARED_SOURCE.lock();
+        let source = match shared_source.as_ref().and_then(Weak::upgrade) {
+            Some(source) => source,
+            None => {
+                let (tty, tty_writer) = open_tty()?;
+                let source = Arc::new(SharedSource::new(reactor, tty, tty_writer)?);
+                *shared_source = Some(Arc::downgrade(&source));
+                source
+            }
+        };
+        Ok(Some(NativeEventStream { source }))
+    }
+
+    pub(crate) fn poll_next(&self, cx: &mut Context<'_>) -> Poll<io::Result<Event>> {
+        let source = &*self.source;
+
+        // Registered before polling, so that a wake-up between polling and returning
+        // `Poll::Pending` isn't lost.
+        source.waiting_tasks.push(cx.waker());
+
+        let mut state = source.state.lock();
+        let mut reactor_cx = Context::from_waker(&source.reactor_waker);
+        loop {
+            while let Some((event, _)) = state.events.pop_front() {
+                if let InternalEvent::Event(event) = event {
+                    if subscription::dispatch(&event) {
+                        continue;
+                    }
+                    source.stop_waiting(cx.waker(), &state);
+                    return Poll::Ready(Ok(event));
+                }
+            }
+
+            match state.poll_events(&mut reactor_cx) {
+                Poll::Ready(Ok(())) => continue,
+                Poll::Ready(Err(e)) => {
+                    source.stop_waiting(cx.waker(), &state);
+                    return Poll::Ready(Err(e));
+                }
+                Poll::Pending => return Poll::Pending,
+            }
+        }
+    }
+
+    pub(crate) async fn cursor_position(self) -> io::Result<(u16, u16)> {
+        // ESC [ 6 n        Query the cursor position.
+        match self.query(b"\x1B[6n", CursorPositionFilter)?.await? {
+            Some(InternalEvent::CursorPosition(x, y)) => Ok((x, y)),
+            _ => Err(io::Error::new(
+                io::ErrorKind::Other,
+                "The cursor position could not be read within a normal duration",
+            )),
+        }
+    }
+
+    pub(crate) async fn supports_keyboard_enhancement(self) -> io::Result<bool> {
+        // ESC [ ? u        Query progressive keyboard enhancement flags (kitty protocol).
+        // ESC [ c          Query primary device attributes.
+        //
+        // The primary device attributes are only received if the flags are not supported, see
+        // `terminal::supports_keyboard_enhancement`. If both are received, the primary device
+        // attributes are dropped by the next query.
+        match self
+            .query(b"\x1B[?u\x1B[c", KeyboardEnhancementFlagsFilter)?
+            .await?
+        {
+            Some(InternalEvent::KeyboardEnhancementFlags(_)) => Ok(true),
+            Some(_) => Ok(false),
+            None => Err(io::Error::new(
+                io::ErrorKind::Other,
+                "The keyboard enhancement status could not be read within a normal duration",
+            )),
+        }
+    }
+
+    /// Writes a query to the terminal and returns a future waiting for the reply.
+    fn query<F: Filter + Unpin>(&self, query: &[u8], filter: F) -> io::Result<Reply<F>> {
+        let reactor = {
+            let mut state = self.source.state.lock();
+            // Replies to earlier queries that timed out or to the blocking query functions.
+            state.replies.retain(|reply| !filter.eval(reply));
+            state.reactor
+        };
+
+        let mut tty = &self.source.tty_writer;
+        tty.write_all(query)?;
+        tty.flush()?;
+
+        Ok(Reply {
+            source: self.source.clone(),
+            filter,
+            timeout: Timer::at(reactor, Instant::now() + QUERY_TIMEOUT),
+        })
+    }
+}
+
+/// Waits for the reply to a query, the events read meanwhile are left to the streams.
+///
+/// Returns `None` if the terminal doesn't reply in time.
+struct Reply<F> {
+    source: Arc<SharedSource>,
+    filter: F,
+    timeout: Timer,
+}
+
+impl<F: Filter + Unpin> Future for Reply<F> {
+    type Output = io::Result<Option<InternalEvent>>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let reply = self.get_mut();
+        let source = &*reply.source;
+        source.waiting_tasks.push(cx.waker());
+
+        let mut state = source.state.lock();
+        let mut reactor_cx = Context::from_waker(&source.reactor_waker);
+        loop {
+            let filter = &reply.filter;
+            if let Some(index) = state.replies.iter().position(|event| filter.eval(event)) {
+                let event = state.replies.remove(index);
+                source.stop_waiting(cx.waker(), &state);
+                return Poll::Ready(Ok(event));
+            }
+
+            match state.poll_events(&mut reactor_cx) {
+                Poll::Ready(Ok(())) => continue,
+                Poll::Ready(Err(e)) => {
+                    source.stop_waiting(cx.waker(), &state);
+                    return Poll::Ready(Err(e));
+                }
+                Poll::Pending => break,
+            }
+        }
+
+        match reply.timeout.poll(cx) {
+            Poll::Ready(()) => {
+                source.stop_waiting(cx.waker(), &state);
+                Poll::Ready(Ok(None))
+            }
+            Poll::Pending => Poll::Pending,
+        }
+    }
+}
+
+impl fmt::Debug for NativeEventStream {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("NativeEventStream").finish_non_exhaustive()
+    }
+}
+
+struct SharedSource {
+    state: Mutex<State>,
+    // A blocking file to write the queries to.
+    tty_writer: File,
+    waiting_tasks: Arc<WaitingTasks>,
+    // Wakes all waiting tasks, the reactor is polled with it so that it doesn't matter which
+    // task polled it last.
+    reactor_waker: Waker,
+}
+
+impl SharedSource {
+    fn new(reactor: Reactor, tty: File, tty_writer: File) -> io::Result<SharedSource> {
+        set_nonblocking(&tty)?;
+
+        let state = State {
+            tty: Registration::new(reactor, tty)?,
+            signal_receivers: signal_receivers()?
+                .into_iter()
+                .map(|receiver| Registration::new(reactor, receiver))
+                .collect::<io::Result<_>>()?,
+            signal_event_receiver: Registration::new(reactor, signal_event_receiver()?)?,
+            reactor,
+            parser: Parser::default(),
+            events: VecDeque::new(),
+            replies: VecDeque::new(),
+            escape_timer: None,
+            tty_buffer: [0u8; TTY_BUFFER_SIZE],
+        };
+
+        let waiting_tasks = Arc::new(WaitingTasks::default());
+        Ok(SharedSource {
+            state: Mutex::new(state),
+            tty_writer,
+            reactor_waker: Waker::from(waiting_tasks.clone()),
+            waiting_tasks,
+        })
+    }
+}
+
+impl SharedSource {
+    /// Removes the waker of a task that's done waiting.
+    fn stop_waiting(&self, waker: &Waker, state: &State) {
+        self.waiting_tasks.remove(waker);
+        if !state.events.is_empty() {
+            // Let the other streams take the remaining events.
+            Waker::from(self.waiting_tasks.clone()).wake();
+        }
+    }
+}
+
+/// The wakers of the streams and queries waiting for the terminal.
+#[derive(Default)]
+struct WaitingTasks(Mutex<Vec<Waker>>);
+
+impl WaitingTasks {
+    fn push(&self, waker: &Waker) {
+        let mut wakers = self.0.lock();
+        if !wakers.iter().any(|w| w.will_wake(waker)) {
+            wakers.push(waker.clone());
+        }
+    }
+
+    fn remove(&self, waker: &Waker) {
+        self.0.lock().retain(|w| !w.will_wake(waker));
+    }
+}
+
+impl Wake for WaitingTasks {
+    fn wake(self: Arc<Self>) {
+        self.wake_by_ref();
+    }
+
+    fn wake_by_ref(self: &Arc<Self>) {
+        let wakers = std::mem::take(&mut *self.0.lock());
+        for waker in wakers {
+            waker.wake();
+        }
+    }
+}
+
+struct State {
+    tty: Registration<File>,
+    signal_receivers: Vec<Registration<SignalReceiver>>,
+    signal_event_receiver: Registration<UnixStream>,
+    reactor: Reactor,
+    parser: Parser,
+    events: VecDeque<(InternalEvent, Instant)>,
+    // The replies to the queries, kept apart so that they're not mixed up with the events.
+    replies: VecDeque<InternalEvent>,
+    // Fires when the pending ESC of the parser is reported as an Esc key press.
+    escape_timer: Option<(Instant, Timer)>,
+    tty_buffer: [u8; TTY_BUFFER_SIZE],
+}
+
+impl State {
+    /// Reads the terminal input and the signals until there are new events or replies.
+    fn poll_events(&mut self, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        self.parser.set_escape_timeout(escape_timeout());
+
+        loop {
+            let received = self.events.len() + self.replies.len();
+
+            // Everything is read until it would block, so that the reactor wakes us up again.
+            loop {
+                let mut tty = self.tty.get_ref();
+                match tty.read(&mut self.tty_buffer) {
+                    Ok(0) => {
+                        return Poll::Ready(Err(io::Error::new(
+                            io::ErrorKind::UnexpectedEof,
+                            "The terminal was closed.",
+                        )))
+                    }
+                    Ok(read_count) => self.parser.advance(
+                        &self.tty_buffer[..read_count],
+                        read_count == TTY_BUFFER_SIZE,
+                    ),
+                    Err(e) if e.kind() == io::ErrorKind::Interrupted => {}
+                    Err(e) if e.kind() == io::ErrorKind::WouldBlock => {
+                        match self.tty.poll_readable(cx)? {
+                            Poll::Ready(()) => {}
+                            Poll::Pending => break,
+                        }
+                    }
+                    Err(e) => return Poll::Ready(Err(e)),
+                }
+            }
+            self.parser.resolve_pending_escape();
+            for (event, time) in &mut self.parser {
+                match event {
+                    InternalEvent::Event(_) => self.events.push_back((event, time)),
+                    reply => self.replies.push_back(reply),
+                }
+            }
+
+            for receiver in &self.signal_receivers {
+                loop {
+                    if let Some(event) = receiver.get_ref().read()? {
+                        self.events.push_back(event);
+                    }
+                    match receiver.poll_readable(cx)? {
+                        Poll::Ready(()) => {}
+                        Poll::Pending => break,
+                    }
+                }
+            }
+
+            loop {
+                self.events
+                    .extend(read_signal_events(self.signal_event_receiver.get_ref())?);
+                match self.signal_event_receiver.poll_readable(cx)? {
+                    Poll::Ready(()) => {}
+                    Poll::Pending => break,
+                }
+            }
+
+            if self.events.len() + self.replies.len() > received {
+                return Poll::Ready(Ok(()));
+            }
+
+            let deadline = match self.parser.pending_escape_deadline() {
+                Some(deadline) => deadline,
+                None => return Poll::Pending,
+            };
+            if !matches!(&self.escape_timer, Some((at, _)) if *at == deadline) {
+                self.escape_timer = Some((deadline, Timer::at(self.reactor, deadline)));
+            }
+            if let Some((_, timer)) = &mut self.escape_timer {
+                match timer.poll(cx) {
+                    Poll::Ready(()) => self.escape_timer = None,
+                    Poll::Pending => return Poll::Pending,
+                }
+            }
+        }
+    }
+}
+
+/// The reactor the streams are registered with.
+#[derive(Debug, Clone, Copy)]
+enum Reactor {
+    #[cfg(feature = "tokio")]
+    Tokio,
+    #[cfg(feature = "async-io")]
+    AsyncIo,
+}
+
+impl Reactor {
+    /// Returns tokio's reactor inside of a tokio runtime, async-io's reactor otherwise.
+    fn current() -> Option<Reactor> {
+        #[cfg(feature = "tokio")]
+        if tokio::runtime::Handle::try_current().is_ok() {
+            return Some(Reactor::Tokio);
+        }
+        #[cfg(feature = "async-io")]
+        return Some(Reactor::AsyncIo);
+        #[cfg(not(feature = "async-io"))]
+        None
+    }
+}
+
+/// A non-blocking file registered with a reactor.
+enum Registration<T: AsRawFd> {
+    #[cfg(feature = "tokio")]
+    Tokio(tokio::io::unix::AsyncFd<T>),
+    #[cfg(feature = "async-io")]
+    AsyncIo(async_io::Async<T>),
+}
+
+impl<T: AsRawFd + AsFd> Registration<T> {
+    fn new(reactor: Reactor, io: T) -> io::Result<Registration<T>> {
+        match reactor {
+            #[cfg(feature = "tokio")]
+            // SAFETY: the registered files own their file descriptors, which stay open until
+            // the registration is dropped.
+            Reactor::Tokio => Ok(Registration::Tokio(unsafe {
+                tokio::io::unix::AsyncFd::register(io)?
+            })),
+            #[cfg(feature = "async-io")]
+            Reactor::AsyncIo => Ok(Registration::AsyncIo(async_io::Async::new(io)?)),
+        }
+    }
+
+    fn get_ref(&self) -> &T {
+        match self {
+            #[cfg(feature = "tokio")]
+            Registration::Tokio(io) => io.get_ref(),
+            #[cfg(feature = "async-io")]
+            Registration::AsyncIo(io) => io.get_ref(),
+        }
+    }
+
+    /// Polls the reactor for readability after a read returned `WouldBlock`.
+    ///
+    /// `Poll::Ready` means that the file may be readable again, the caller has to try reading.
+    fn poll_readable(&self, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        match self {
+            #[cfg(feature = "tokio")]
+            Registration::Tokio(io) => match io.poll_read_ready(cx) {
+                Poll::Ready(Ok(mut guard)) => {
+                    guard.clear_ready();
+                    Poll::Ready(Ok(()))
+                }
+                Poll::Ready(Err(e)) => Poll::Ready(Err(e)),
+                Poll::Pending => Poll::Pending,
+            },
+            #[cfg(feature = "async-io")]
+            Registration::AsyncIo(io) => io.poll_readable(cx),
+        }
+    }
+}
+
+enum Timer {
+    #[cfg(feature = "tokio")]
+    Thread(ThreadTimer),
+    #[cfg(feature = "async-io")]
+    AsyncIo(async_io::Timer),
+}
+
+impl Timer {
+    fn at(reactor: Reactor, deadline: Instant) -> Timer {
+        match reactor {
+            #[cfg(feature = "tokio")]
+            Reactor::Tokio => Timer::Thread(ThreadTimer::at(deadline)),
+            #[cfg(feature = "async-io")]
+            Reactor::AsyncIo => Timer::AsyncIo(async_io::Timer::at(deadline)),
+        }
+    }
+
+    fn poll(&mut self, cx: &mut Context<'_>) -> Poll<()> {
+        match self {
+            #[cfg(feature = "tokio")]
+            Timer::Thread(timer) => timer.poll(cx),
+            #[cfg(feature = "async-io")]
+            Timer::AsyncIo(timer) => Pin::new(timer).poll(cx).map(|_| ()),
+        }
+    }
+}
+
+/// The waker of the task waiting for a thread timer.
+#[cfg(feature = "tokio")]
+type TimerWaker = Mutex<Option<Waker>>;
+
+/// The deadlines of the thread timers and the wakers of the tasks waiting for them.
+#[cfg(feature = "tokio")]
+static THREAD_TIMERS: Mutex<Vec<(Instant, Weak<TimerWaker>)>> =
+    parking_lot::const_mutex(Vec::new());
+#[cfg(feature = "tokio")]
+static THREAD_TIMERS_CHANGED: parking_lot::Condvar = parking_lot::Condvar::new();
+
+/// A timer kept by a helper thread shared by all timers.
+///
+/// Used with tokio's reactor instead of tokio's timers, which panic in a runtime built without
+/// the time driver. There's no way to check for the time driver beforehand.
+#[cfg(feature = "tokio")]
+struct ThreadTimer {
+    deadline: Instant,
+    waker: Arc<TimerWaker>,
+}
+
+#[cfg(feature = "tokio")]
+impl ThreadTimer {
+    fn at(deadline: Instant) -> ThreadTimer {
+        static SPAWN_THREAD: std::sync::Once = std::sync::Once::new();
+        SPAWN_THREAD.call_once(|| {
+            std::thread::spawn(run_thread_timers);
+        });
+
+        let waker = Arc::new(Mutex::new(None));
+        THREAD_TIMERS
+            .lock()
+            .push((deadline, Arc::downgrade(&waker)));
+        THREAD_TIMERS_CHANGED.notify_one();
+        ThreadTimer { deadline, waker }
+    }
+
+    fn poll(&mut self, cx: &mut Context<'_>) -> Poll<()> {
+        // Stored before checking the deadline, so that the thread can't miss it.
+        *self.waker.lock() = Some(cx.waker().clone());
+        if Instant::now() >= self.deadline {
+            return Poll::Ready(());
+        }
+        Poll::Pending
+    }
+}
+
+/// Wakes the tasks waiting for the thread timers when their deadlines are reached.
+#[cfg(feature = "tokio")]
+fn run_thread_timers() {
+    let mut timers = THREAD_TIMERS.lock();
+    loop {
+        let now = Instant::now();
+        timers.retain(|(deadline, waker)| {
+            if *deadline > now {
+                // Dropped timers are removed.
+                return waker.strong_count() > 0;
+            }
+            if let Some(waker) = waker.upgrade().and_then(|waker| waker.lock().take()) {
+                waker.wake();
+            }
+            false
+        });
+
+        match timers.iter().map(|(deadline, _)| *deadline).min() {
+            Some(deadline) => {
+                THREAD_TIMERS_CHANGED.wait_until(&mut timers, deadline);
+            }
+            None => THREAD_TIMERS_CHANGED.wait(&mut timers),
+        }
+    }
+}
+
+#[cfg(feature = "libc")]
+fn set_nonblocking(file: &File) -> io::Result<()> {
+    let mut nonblocking: std::os::raw::c_int = 1;
+    if unsafe { libc::ioctl(file.as_raw_fd(), libc::FIONBIO, &mut nonblocking) } == -1 {
+        return Err(io::Error::last_os_error());
+    }
+    Ok(())
+}
+
+#[cfg(not(feature = "libc"))]
+fn set_nonblocking(file: &File) -> io::Result<()> {
+    rustix::io::ioctl_fionbio(file, true)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        fs::File,
+        future::Future,
+        io::{Read, Write},
+        os::{fd::OwnedFd, unix::net::UnixStream},
+        sync::{
+            atomic::{AtomicBool, Ordering},
+            Arc,
+        },
+        task::{Context, Poll, Wake, Waker},
+        time::{Duration, Instant},
+    };
+
+    use serial_test::serial;
+
+    use crate::event::{
+        check_blocking_read,
+        source::unix::{parser::escape_timeout, set_escape_timeout},
+        Event, KeyCode,
+    };
+
+    use super::NativeEventStream;
+
+    /// Runs the test with each of the reactors.
+    fn block_on_each<F: Future<Output = ()>>(test: impl Fn() -> F) {
+        // Built without the time driver, which the streams don't need.
+        #[cfg(feature = "tokio")]
+        tokio::runtime::Builder::new_current_thread()
+            .enable_io()
+            .build()
+            .unwrap()
+            .block_on(test());
+        #[cfg(feature = "async-io")]
+        async_io::block_on(test());
+    }
+
+    /// Creates a stream reading from the returned socket instead of the terminal.
+    ///
+    /// The streams share a global source, so the tests creating them run serially.
+    fn stream() -> (NativeEventStream, UnixStream) {
+        let (tty, terminal) = UnixStream::pair().unwrap();
+        let stream = NativeEventStream::with_tty(|| {
+            let tty_writer = tty.try_clone()?;
+            Ok((
+                File::from(OwnedFd::from(tty)),
+                File::from(OwnedFd::from(tty_writer)),
+            ))
+        })
+        .unwrap()
+        .unwrap();
+        (stream, terminal)
+    }
+
+    async fn next(stream: &NativeEventStream) -> Event {
+        std::future::poll_fn(|cx| stream.poll_next(cx))
+            .await
+            .unwrap()
+    }
+
+    fn key(c: char) -> Event {
+        Event::Key(KeyCode::Char(c).into())
+    }
+
+    #[derive(Default)]
+    struct Woken(AtomicBool);
+
+    impl Wake for Woken {
+        fn wake(self: Arc<Self>) {
+            self.0.store(true, Ordering::SeqCst);
+        }
+    }
+
+    #[test]
+    #[serial]
+    fn test_pending_escape_is_reported_after_the_timeout() {
+        set_escape_timeout(Duration::from_millis(50));
+        block_on_each(|| async {
+            let (stream, mut terminal) = stream();
+            let start = Instant::now();
+            terminal.write_all(b"\x1B").unwrap();
+
+            assert_eq!(next(&stream).await, Event::Key(KeyCode::Esc.into()));
+            assert!(start.elapsed() >= escape_timeout());
+        });
+        set_escape_timeout(Duration::ZERO);
+    }
+
+    #[test]
+    #[serial]
+    fn test_reply_is_returned_to_the_query() {
+        block_on_each(|| async {
+            let (stream, mut terminal) = stream();
+            let other = stream.clone();
+            terminal.write_all(b"a\x1B[3;5Rb").unwrap();
+
+            assert_eq!(stream.clone().cursor_position().await.unwrap(), (4, 2));
+            let mut query = [0; 4];
+            terminal.read_exact(&mut query).unwrap();
+            assert_eq!(&query, b"\x1B[6n");
+
+            // The events read by the query are left to the streams.
+            assert_eq!(next(&other).await, key('a'));
+            assert_eq!(next(&stream).await, key('b'));
+        });
+    }
+
+    #[test]
+    #[serial]
+    fn test_events_are_shared_by_the_streams() {
+        block_on_each(|| async {
+            let (stream, mut terminal) = stream();
+            let other = stream.clone();
+
+            let woken = Arc::new(Woken::default());
+            let waker = Waker::from(woken.clone());
+            let mut cx = Context::from_waker(&waker);
+            assert!(other.poll_next(&mut cx).is_pending());
+
+            terminal.write_all(b"ab").unwrap();
+            assert_eq!(next(&stream).await, key('a'));
+
+            // The waiting stream is woken for the event left by the other one.
+            assert!(woken.0.load(Ordering::SeqCst));
+            match other.poll_next(&mut cx) {
+                Poll::Ready(Ok(event)) => assert_eq!(event, key('b')),
+                _ => panic!("the event isn't returned by the waiting stream"),
+            }
+        });
+    }
+
+    #[test]
+    #[serial]
+    fn test_blocking_read_is_rejected_while_a_stream_exists() {
+        block_on_each(|| async {
+            let (stream, _terminal) = stream();
+            let other = stream.clone();
+            assert!(check_blocking_read().is_err());
+
+            drop(stream);
+            assert!(check_blocking_read().is_err());
+            drop(other);
+            assert!(check_blocking_read().is_ok());
+        });
+    }
+}
@@ -0,0 +1,275 @@
+use std::{collections::VecDeque, fmt, sync::Arc, time::Duration};
+
+#[cfg(feature = "event-stream")]
+use std::{
+    pin::Pin,
+    task::{Context, Poll, Waker},
+};
+
+use parking_lot::{Condvar, Mutex};
+
+use crate::event::{timeout::PollTimeout, Event};
+
+/// The number of events a subscription keeps, see `Subscription`.
+const CAPACITY: usize = 1_024;
+
+/// The subscribers in the order they subscribed.
+static SUBSCRIBERS: Mutex<Vec<Arc<Subscriber>>> = parking_lot::const_mutex(Vec::new());
+
+/// A queue receiving a copy of every event read from the terminal.
+///
+/// Subscriptions are created with [`subscribe`](fn.subscribe.html) and
+/// [`subscribe_consuming`](fn.subscribe_consuming.html). A subscription doesn't read from the
+/// terminal itself, it receives the events read by [`read`](fn.read.html),
+/// [`poll`](fn.poll.html) and the [`EventStream`](struct.EventStream.html)s, starting with the
+/// events read after it was created. Several parts of an application can observe the input this
+/// way, e.g. a logger or a macro recorder next to the main event loop.
+///
+/// A subscription keeps up to 1024 events that weren't read yet. If another event is received
+/// while it's full, the oldest event is dropped, so that a subscription that isn't read doesn't
+/// grow without bounds.
+///
+/// The subscription is removed when it's dropped.
+///
+/// With the `event-stream` feature, the subscription is a
+/// [Stream](https://docs.rs/futures-core/latest/futures_core/stream/trait.Stream.html) of events
+/// too.
+pub struct Subscription {
+    subscriber: Arc<Subscriber>,
+}
+
+type Consume = Box<dyn Fn(&Event) -> bool + Send + Sync>;
+
+struct Subscriber {
+    events: Mutex<Events>,
+    available: Condvar,
+    consume: Option<Consume>,
+}
+
+#[derive(Default)]
+struct Events {
+    queue: VecDeque<Event>,
+    #[cfg(feature = "event-stream")]
+    waker: Option<Waker>,
+}
+
+/// Subscribes to the events read from the terminal.
+///
+/// The subscription should be read continuously, once it holds 1024 events the oldest ones are
+/// dropped. See [`Subscription`](struct.Subscription.html) for more information.
+///
+/// # Examples
+///
+/// ```no_run
+/// use std::{io, thread};
+///
+/// use crossterm::event::{read, subscribe};
+///
+/// fn main() -> io::Result<()> {
+///     let log = subscribe();
+///     thread::spawn(move || loop {
+///         eprintln!("{:?}", log.read());
+///     });
+///
+///     loop {
+///         // Every event is logged too.
+///         println!("{:?}", read()?);
+///     }
+/// }
+/// ```
+pub fn subscribe() -> Subscription {
+    register(None)
+}
+
+/// Subscribes to the events read from the terminal, consuming the events for which `consume`
+/// returns `true`.
+///
+/// The consumed events are only received by this subscription and the subscriptions created
+/// before it, they're not returned by [`read`](fn.read.html), [`poll`](fn.poll.html) and the
+/// [`EventStream`](struct.EventStream.html)s.
+///
+/// # Examples
+///
+/// ```no_run
+/// use std::thread;
+///
+/// use crossterm::event::{subscribe_consuming, Event, KeyCode, KeyEvent};
+///
+/// let hotkey = Event::Key(KeyEvent::from(KeyCode::F(12)));
+/// let recorder = subscribe_consuming(move |event| *event == hotkey);
+/// thread::spawn(move || loop {
+///     // F12 toggles the recording, the application doesn't see it.
+///     let event = recorder.read();
+/// });
+/// ```
+pub fn subscribe_consuming(
+    consume: impl Fn(&Event) -> bool + Send + Sync + 'static,
+) -> Subscription {
+    register(Some(Box::new(consume)))
+}
+
+fn register(consume: Option<Consume>) -> Subscription {
+    let subscriber = Arc::new(Subscriber::new(consume));
+    SUBSCRIBERS.lock().push(subscriber.clone());
+    Subscription { subscriber }
+}
+
+/// Hands an event read from the terminal to the subscriptions, returning `true` if one of them
+/// consumed it.
+pub(crate) fn dispatch(event: &Event) -> bool {
+    // Cloned, so that the `consume` functions can subscribe and unsubscribe.
+    let subscribers = SUBSCRIBERS.lock().clone();
+    for subscriber in subscribers {
+        subscriber.push(event.clone());
+        if let Some(consume) = &subscriber.consume {
+            if consume(event) {
+                return true;
+            }
+        }
+    }
+    false
+}
+
+impl Subscriber {
+    fn new(consume: Option<Consume>) -> Subscriber {
+        Subscriber {
+            events: Mutex::new(Events::default()),
+            available: Condvar::new(),
+            consume,
+        }
+    }
+
+    fn push(&self, event: Event) {
+        let mut events = self.events.lock();
+        if events.queue.len() == CAPACITY {
+            events.queue.pop_front();
+        }
+        events.queue.push_back(event);
+        #[cfg(feature = "event-stream")]
+        if let Some(waker) = events.waker.take() {
+            waker.wake();
+        }
+        self.available.notify_all();
+    }
+}
+
+impl Subscription {
+    /// Checks if there's an event available within the given duration.
+    pub fn poll(&self, timeout: Duration) -> bool {
+        let timeout = PollTimeout::new(Some(timeout));
+        let mut events = self.subscriber.events.lock();
+        while events.queue.is_empty() {
+            match timeout.leftover() {
+                Some(leftover) if !leftover.is_zero() => {
+                    self.subscriber.available.wait_for(&mut events, leftover);
+                }
+                _ => return false,
+            }
+        }
+        true
+    }
+
+    /// Reads the next event, blocking until there's one.
+    pub fn read(&self) -> Event {
+        let mut events = self.subscriber.events.lock();
+        loop {
+            if let Some(event) = events.queue.pop_front() {
+                return event;
+            }
+            self.subscriber.available.wait(&mut events);
+        }
+    }
+
+    /// Reads the next event if there's one.
+    pub fn try_read(&self) -> Option<Event> {
+        self.subscriber.events.lock().queue.pop_front()
+    }
+}
+
+impl Drop for Subscription {
+    fn drop(&mut self) {
+        SUBSCRIBERS
+            .lock()
+            .retain(|subscriber| !Arc::ptr_eq(subscriber, &self.subscriber));
+    }
+}
+
+impl fmt::Debug for Subscription {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Subscription")
+            .field("consuming", &self.subscriber.consume.is_some())
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(feature = "event-stream")]
+impl futures_core::stream::Stream for Subscription {
+    type Item = Event;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Event>> {
+        let mut events = self.subscriber.events.lock();
+        match events.queue.pop_front() {
+            Some(event) => Poll::Ready(Some(event)),
+            None => {
+                events.waker = Some(cx.waker().clone());
+                Poll::Pending
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use crate::event::Event;
+
+    use super::{dispatch, subscribe, subscribe_consuming, Subscriber, Subscription, CAPACITY};
+
+    // The subscriptions receive the events of the other tests too, the width tells them apart.
+    const WIDTH: u16 = 4001;
+
+    fn received(subscription: &Subscription) -> Vec<u16> {
+        std::iter::from_fn(|| subscription.try_read())
+            .filter_map(|event| match event {
+                Event::Resize(WIDTH, height) => Some(height),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_dispatch_stops_at_consuming_subscription() {
+        let first = subscribe();
+        let consuming = subscribe_consuming(|event| *event == Event::Resize(WIDTH, 1));
+        let last = subscribe();
+
+        assert!(dispatch(&Event::Resize(WIDTH, 1)));
+        assert!(!dispatch(&Event::Resize(WIDTH, 2)));
+
+        assert_eq!(received(&first), [1, 2]);
+        assert_eq!(received(&consuming), [1, 2]);
+        assert_eq!(received(&last), [2]);
+
+        drop(consuming);
+        assert!(!dispatch(&Event::Resize(WIDTH, 1)));
+        assert_eq!(received(&first), [1]);
+        assert_eq!(received(&last), [1]);
+    }
+
+    #[test]
+    fn test_full_subscription_drops_the_oldest_events() {
+        // Not registered, so that it doesn't receive the events of the other tests.
+        let subscription = Subscription {
+            subscriber: Arc::new(Subscriber::new(None)),
+        };
+        for height in 0..CAPACITY as u16 + 2 {
+            subscription.subscriber.push(Event::Resize(WIDTH, height));
+        }
+
+        let heights = received(&subscription);
+        assert_eq!(heights.len(), CAPACITY);
+        assert_eq!(heights.first(), Some(&2));
+        assert_eq!(heights.last(), Some(&(CAPACITY as u16 + 1)));
+    }
+}
@@ -94,11 +94,17 @@ pub(crate) fn parse_event(
         )))),
         // Issue #371: \n = 0xA, which is also the keycode for Ctrl+J. The only reason we get
         // newlines as input is because the terminal converts \r into \n for us. When we
-        // enter raw mode, we disable that, so \n no longer has any meaning - it's better to
-        // use Ctrl+J. Waiting to handle it here means it gets picked up later
-        b'\n' if !crate::terminal::sys::is_raw_mode_enabled() => Ok(Some(InternalEvent::Event(
-            Event::Key(KeyCode::Enter.into()),
-        ))),
+        // enter raw mode, we disable that (unless it's kept with `RawModeOptions`), so \n no
+        // longer has any meaning - it's better to use Ctrl+J. Waiting to handle it here means it
+        // gets picked up later
+        b'\n'
+            if crate::terminal::sys::raw_mode_options()
+                .map_or(true, |options| options.keep_carriage_return_translation) =>
+        {
+            Ok(Some(InternalEvent::Event(Event::Key(
+                KeyCode::Enter.into(),
+            ))))
+        }
         b'\t' => Ok(Some(InternalEvent::Event(Event::Key(KeyCode::Tab.into())))),
         b'\x7F' => Ok(Some(InternalEvent::Event(Event::Key(
             KeyCode::Backspace.into(),
@@ -180,6 +186,8 @@ pub(crate) fn parse_csi(buffer: &[u8]) -> io::Result<Option<InternalEvent>> {
         b'?' => match buffer[buffer.len() - 1] {
             b'u' => return parse_csi_keyboard_enhancement_flags(buffer),
             b'c' => return parse_csi_primary_device_attributes(buffer),
+            b'y' if buffer.ends_with(b"$y") => return parse_csi_mode_report(buffer),
+            b'y' => return Err(could_not_parse_event_error()),
             _ => None,
         },
         b'0'..=b'9' => {
@@ -300,6 +308,24 @@ fn parse_csi_primary_device_attributes(buffer: &[u8]) -> io::Result<Option<Inter
     Ok(Some(InternalEvent::PrimaryDeviceAttributes))
 }
 
+fn parse_csi_mode_report(buffer: &[u8]) -> io::Result<Option<InternalEvent>> {
+    // ESC [ ? Pd ; Ps $ y
+    //   Pd - mode number
+    //   Ps - setting of the mode
+    assert!(buffer.starts_with(b"\x1B[?")); // ESC [ ?
+    assert!(buffer.ends_with(b"$y"));
+
+    let s = std::str::from_utf8(&buffer[3..buffer.len() - 2])
+        .map_err(|_| could_not_parse_event_error())?;
+
+    let mut split = s.split(';');
+
+    let mode = next_parsed::<u16>(&mut split)?;
+    let setting = next_parsed::<u8>(&mut split)?;
+
+    Ok(Some(InternalEvent::ModeReport(mode, setting)))
+}
+
 fn parse_modifiers(mask: u8) -> KeyModifiers {
     let modifier_mask = mask.saturating_sub(1);
     let mut modifiers = KeyModifiers::empty();
@@ -790,6 +816,14 @@ fn parse_cb(cb: u8) -> io::Result<(MouseEventKind, KeyModifiers)> {
         (5, false) => MouseEventKind::ScrollDown,
         (6, false) => MouseEventKind::ScrollLeft,
         (7, false) => MouseEventKind::ScrollRight,
+        (8, false) => MouseEventKind::Down(MouseButton::Back),
+        (9, false) => MouseEventKind::Down(MouseButton::Forward),
+        (10, false) => MouseEventKind::Down(MouseButton::Button10),
+        (11, false) => MouseEventKind::Down(MouseButton::Button11),
+        (8, true) => MouseEventKind::Drag(MouseButton::Back),
+        (9, true) => MouseEventKind::Drag(MouseButton::Forward),
+        (10, true) => MouseEventKind::Drag(MouseButton::Button10),
+        (11, true) => MouseEventKind::Drag(MouseButton::Button11),
         // We do not support other buttons.
         _ => return Err(could_not_parse_event_error()),
     };
@@ -1013,6 +1047,19 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_parse_csi_mode_report() {
+        assert_eq!(
+            parse_event(b"\x1B[?2027;2$y", false).unwrap(),
+            Some(InternalEvent::ModeReport(2027, 2))
+        );
+        assert_eq!(parse_event(b"\x1B[?2027;2$", false).unwrap(), None);
+        assert!(parse_event(b"\x1B[?2027$y", false).is_err());
+        // Malformed replies without the `$` are errors, not panics.
+        assert!(parse_event(b"\x1B[?1y", false).is_err());
+        assert!(parse_event(b"\x1B[?y", false).is_err());
+    }
+
     #[test]
     fn test_parse_csi() {
         assert_eq!(
@@ -1145,6 +1192,68 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_parse_csi_sgr_mouse_extra_buttons() {
+        assert_eq!(
+            parse_csi_sgr_mouse(b"\x1B[<128;20;10M").unwrap(),
+            Some(InternalEvent::Event(Event::Mouse(MouseEvent {
+                kind: MouseEventKind::Down(MouseButton::Back),
+                column: 19,
+                row: 9,
+                modifiers: KeyModifiers::empty(),
+            })))
+        );
+        assert_eq!(
+            parse_csi_sgr_mouse(b"\x1B[<129;20;10m").unwrap(),
+            Some(InternalEvent::Event(Event::Mouse(MouseEvent {
+                kind: MouseEventKind::Up(MouseButton::Forward),
+                column: 19,
+                row: 9,
+                modifiers: KeyModifiers::empty(),
+            })))
+        );
+        assert_eq!(
+            parse_csi_sgr_mouse(b"\x1B[<146;20;10M").unwrap(),
+            Some(InternalEvent::Event(Event::Mouse(MouseEvent {
+                kind: MouseEventKind::Down(MouseButton::Button10),
+                column: 19,
+                row: 9,
+                modifiers: KeyModifiers::CONTROL,
+            })))
+        );
+        assert_eq!(
+            parse_csi_sgr_mouse(b"\x1B[<163;20;10M").unwrap(),
+            Some(InternalEvent::Event(Event::Mouse(MouseEvent {
+                kind: MouseEventKind::Drag(MouseButton::Button11),
+                column: 19,
+                row: 9,
+                modifiers: KeyModifiers::empty(),
+            })))
+        );
+    }
+
+    #[test]
+    fn test_parse_csi_normal_mouse_extra_buttons() {
+        assert_eq!(
+            parse_csi_normal_mouse(b"\x1B[M\xA0\x60\x70").unwrap(),
+            Some(InternalEvent::Event(Event::Mouse(MouseEvent {
+                kind: MouseEventKind::Down(MouseButton::Back),
+                column: 63,
+                row: 79,
+                modifiers: KeyModifiers::empty(),
+            })))
+        );
+        assert_eq!(
+            parse_csi_normal_mouse(b"\x1B[M\xC1\x60\x70").unwrap(),
+            Some(InternalEvent::Event(Event::Mouse(MouseEvent {
+                kind: MouseEventKind::Drag(MouseButton::Forward),
+                column: 63,
+                row: 79,
+                modifiers: KeyModifiers::empty(),
+            })))
+        );
+    }
+
     #[test]
     fn test_utf8() {
         // https://www.php.net/manual/en/reference.pcre.pattern.modifiers.php#54805
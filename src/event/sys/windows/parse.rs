@@ -1,7 +1,8 @@
-use crossterm_winapi::{ControlKeyState, EventFlags, KeyEventRecord, ScreenBuffer};
+use crossterm_winapi::{ButtonState, ControlKeyState, EventFlags, KeyEventRecord, ScreenBuffer};
 use winapi::um::{
     wincon::{
-        CAPSLOCK_ON, LEFT_ALT_PRESSED, LEFT_CTRL_PRESSED, RIGHT_ALT_PRESSED, RIGHT_CTRL_PRESSED,
+        CAPSLOCK_ON, FROM_LEFT_3RD_BUTTON_PRESSED, FROM_LEFT_4TH_BUTTON_PRESSED, LEFT_ALT_PRESSED,
+        LEFT_CTRL_PRESSED, RIGHTMOST_BUTTON_PRESSED, RIGHT_ALT_PRESSED, RIGHT_CTRL_PRESSED,
         SHIFT_PRESSED,
     },
     winuser::{
@@ -21,6 +22,34 @@ pub struct MouseButtonsPressed {
     pub(crate) left: bool,
     pub(crate) right: bool,
     pub(crate) middle: bool,
+    pub(crate) back: bool,
+    pub(crate) forward: bool,
+}
+
+impl From<ButtonState> for MouseButtonsPressed {
+    fn from(button_state: ButtonState) -> Self {
+        MouseButtonsPressed {
+            left: button_state.left_button(),
+            right: is_rightmost_button_pressed(button_state),
+            middle: button_state.middle_button(),
+            back: is_back_button_pressed(button_state),
+            forward: is_forward_button_pressed(button_state),
+        }
+    }
+}
+
+// `ButtonState::right_button` also reports the 3rd and 4th buttons from the left (XBUTTON1 and
+// XBUTTON2), so we have to look at the raw state to tell them apart from the right button.
+fn is_rightmost_button_pressed(button_state: ButtonState) -> bool {
+    button_state.state() as u32 & RIGHTMOST_BUTTON_PRESSED != 0
+}
+
+fn is_back_button_pressed(button_state: ButtonState) -> bool {
+    button_state.state() as u32 & FROM_LEFT_3RD_BUTTON_PRESSED != 0
+}
+
+fn is_forward_button_pressed(button_state: ButtonState) -> bool {
+    button_state.state() as u32 & FROM_LEFT_4TH_BUTTON_PRESSED != 0
 }
 
 pub(crate) fn handle_mouse_event(
@@ -315,27 +344,40 @@ fn parse_mouse_event_record(
 
     let kind = match event.event_flags {
         EventFlags::PressOrRelease | EventFlags::DoubleClick => {
-            if button_state.left_button() && !buttons_pressed.left {
+            let pressed = MouseButtonsPressed::from(button_state);
+            if pressed.left && !buttons_pressed.left {
                 Some(MouseEventKind::Down(MouseButton::Left))
-            } else if !button_state.left_button() && buttons_pressed.left {
+            } else if !pressed.left && buttons_pressed.left {
                 Some(MouseEventKind::Up(MouseButton::Left))
-            } else if button_state.right_button() && !buttons_pressed.right {
+            } else if pressed.right && !buttons_pressed.right {
                 Some(MouseEventKind::Down(MouseButton::Right))
-            } else if !button_state.right_button() && buttons_pressed.right {
+            } else if !pressed.right && buttons_pressed.right {
                 Some(MouseEventKind::Up(MouseButton::Right))
-            } else if button_state.middle_button() && !buttons_pressed.middle {
+            } else if pressed.middle && !buttons_pressed.middle {
                 Some(MouseEventKind::Down(MouseButton::Middle))
-            } else if !button_state.middle_button() && buttons_pressed.middle {
+            } else if !pressed.middle && buttons_pressed.middle {
                 Some(MouseEventKind::Up(MouseButton::Middle))
+            } else if pressed.back && !buttons_pressed.back {
+                Some(MouseEventKind::Down(MouseButton::Back))
+            } else if !pressed.back && buttons_pressed.back {
+                Some(MouseEventKind::Up(MouseButton::Back))
+            } else if pressed.forward && !buttons_pressed.forward {
+                Some(MouseEventKind::Down(MouseButton::Forward))
+            } else if !pressed.forward && buttons_pressed.forward {
+                Some(MouseEventKind::Up(MouseButton::Forward))
             } else {
                 None
             }
         }
         EventFlags::MouseMoved => {
-            let button = if button_state.right_button() {
+            let button = if is_rightmost_button_pressed(button_state) {
                 MouseButton::Right
             } else if button_state.middle_button() {
                 MouseButton::Middle
+            } else if is_back_button_pressed(button_state) {
+                MouseButton::Back
+            } else if is_forward_button_pressed(button_state) {
+                MouseButton::Forward
             } else {
                 MouseButton::Left
             };
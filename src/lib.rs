@@ -236,6 +236,11 @@ pub mod cursor;
 /// A module to read events.
 #[cfg(feature = "events")]
 pub mod event;
+/// A module to read a line of text with a line editor.
+#[cfg(feature = "events")]
+pub mod line;
+/// A module to render a buffer of cells to the terminal.
+pub mod screen;
 /// A module to apply attributes and colors on your text.
 pub mod style;
 /// A module to work with the terminal.
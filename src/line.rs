@@ -0,0 +1,709 @@
+//! # Line
+//!
+//! The `line` module provides a small line editor to read a line of text from the user, like
+//! `readline`.
+//!
+//! The line is edited inline, at the position of the cursor, without switching to the alternate
+//! screen. Raw mode is enabled while reading if it's not enabled yet.
+//!
+//! The editor supports the usual Emacs style key bindings:
+//!
+//! * `Left`/`Ctrl+B` and `Right`/`Ctrl+F` move the cursor by a character, `Ctrl+Left`/`Alt+B`
+//!   and `Ctrl+Right`/`Alt+F` by a word, `Home`/`Ctrl+A` and `End`/`Ctrl+E` to the start and end
+//!   of the line.
+//! * `Backspace`/`Ctrl+H` and `Delete`/`Ctrl+D` delete a character.
+//! * `Ctrl+W`, `Alt+Backspace` and `Alt+D` kill a word, `Ctrl+U` and `Ctrl+K` kill to the start
+//!   and end of the line. `Ctrl+Y` yanks (inserts) the killed text again.
+//! * `Up`/`Ctrl+P` and `Down`/`Ctrl+N` go through the history, `Ctrl+R` searches it.
+//! * `Ctrl+L` clears the screen.
+//! * `Enter` accepts the line, `Ctrl+C` aborts reading with an error of kind
+//!   [`Interrupted`](std::io::ErrorKind::Interrupted) and `Ctrl+D` on an empty line with an error
+//!   of kind [`UnexpectedEof`](std::io::ErrorKind::UnexpectedEof).
+//!
+//! Pasted text ([`Event::Paste`](../event/enum.Event.html#variant.Paste)) is inserted at the
+//! cursor, bracketed paste has to be enabled with
+//! [`EnableBracketedPaste`](../event/struct.EnableBracketedPaste.html) for this.
+//!
+//! ## Examples
+//!
+//! ```no_run
+//! use std::io;
+//!
+//! use crossterm::{line::LineEditor, style::Stylize};
+//!
+//! fn main() -> io::Result<()> {
+//!     let mut editor = LineEditor::new();
+//!
+//!     loop {
+//!         let line = editor.read_line(&mut io::stdout(), "> ".green())?;
+//!         if line == "exit" {
+//!             break;
+//!         }
+//!         println!("{}", line);
+//!     }
+//!
+//!     Ok(())
+//! }
+//! ```
+
+use std::{fmt::Display, io};
+
+use unicode_segmentation::UnicodeSegmentation;
+
+use crate::{
+    cursor::{MoveDown, MoveTo, MoveToColumn, MoveUp},
+    event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers},
+    style::{width_mode, Print, StyledContent, WidthMode},
+    terminal::{self, Clear, ClearType},
+    QueueableCommand,
+};
+
+use self::buffer::LineBuffer;
+pub use self::history::History;
+
+mod buffer;
+mod history;
+
+/// Reads a line of text with a new [`LineEditor`], writing to the standard output.
+///
+/// See the [module documentation](index.html) for more information.
+pub fn read_line<D: Display>(prompt: StyledContent<D>) -> io::Result<String> {
+    LineEditor::new().read_line(&mut io::stdout(), prompt)
+}
+
+/// A line editor that keeps the history and the killed text between reads.
+///
+/// See the [module documentation](index.html) for more information.
+#[derive(Debug, Default, Clone)]
+pub struct LineEditor {
+    history: History,
+    kill_buffer: String,
+}
+
+impl LineEditor {
+    /// Creates a line editor with an empty history.
+    pub fn new() -> LineEditor {
+        LineEditor::default()
+    }
+
+    /// Returns the history of the entered lines.
+    pub fn history(&self) -> &History {
+        &self.history
+    }
+
+    /// Returns the history of the entered lines, e.g. to load it from a file.
+    pub fn history_mut(&mut self) -> &mut History {
+        &mut self.history
+    }
+
+    /// Reads a line of text, drawing the prompt and the line to the given writer.
+    ///
+    /// The entered line is added to the history.
+    pub fn read_line<W: io::Write, D: Display>(
+        &mut self,
+        writer: &mut W,
+        prompt: StyledContent<D>,
+    ) -> io::Result<String> {
+        let raw_mode_enabled = terminal::is_raw_mode_enabled()?;
+        if !raw_mode_enabled {
+            terminal::enable_raw_mode()?;
+        }
+
+        let result = self.read_line_raw(writer, prompt);
+
+        if !raw_mode_enabled {
+            terminal::disable_raw_mode()?;
+        }
+
+        let line = result?;
+        self.history.push(line.as_str());
+        Ok(line)
+    }
+
+    fn read_line_raw<W: io::Write, D: Display>(
+        &mut self,
+        writer: &mut W,
+        prompt: StyledContent<D>,
+    ) -> io::Result<String> {
+        let mut state = State::default();
+        let mut renderer = Renderer {
+            prompt_text: prompt.content().to_string(),
+            prompt,
+            columns: terminal::size().map_or(0, |(columns, _)| columns),
+            cursor_row: 0,
+        };
+        renderer.render(writer, &state)?;
+
+        loop {
+            let event = event::read()?;
+            if let Event::Resize(columns, _) = event {
+                renderer.resize(columns, &state);
+            }
+
+            match state.handle_event(&event, &self.history, &mut self.kill_buffer) {
+                Outcome::Ignore => continue,
+                Outcome::Redraw => {}
+                Outcome::ClearScreen => {
+                    writer.queue(Clear(ClearType::All))?.queue(MoveTo(0, 0))?;
+                    renderer.cursor_row = 0;
+                }
+                Outcome::Accept => {
+                    renderer.finish(writer, &state)?;
+                    return Ok(state.buffer.into_text());
+                }
+                Outcome::Interrupt => {
+                    renderer.finish(writer, &state)?;
+                    return Err(io::Error::new(io::ErrorKind::Interrupted, "Interrupted."));
+                }
+                Outcome::Eof => {
+                    renderer.finish(writer, &state)?;
+                    return Err(io::Error::new(
+                        io::ErrorKind::UnexpectedEof,
+                        "End of input.",
+                    ));
+                }
+            }
+
+            renderer.render(writer, &state)?;
+        }
+    }
+}
+
+/// What to do after an event was handled.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Outcome {
+    Ignore,
+    Redraw,
+    ClearScreen,
+    Accept,
+    Interrupt,
+    Eof,
+}
+
+/// A reverse incremental search through the history.
+#[derive(Debug, Default)]
+struct Search {
+    query: String,
+    // The index of the history line that matches the query.
+    index: Option<usize>,
+    // The line before the search started, restored if it's cancelled.
+    original: LineBuffer,
+}
+
+/// The state of a single `read_line` call.
+#[derive(Debug, Default)]
+struct State {
+    buffer: LineBuffer,
+    // The index of the history line that's edited, `None` if it's the new line.
+    history_index: Option<usize>,
+    // The new line while going through the history.
+    draft: String,
+    search: Option<Search>,
+    // Consecutive kills are added to the same kill buffer.
+    killing: bool,
+}
+
+impl State {
+    fn handle_event(
+        &mut self,
+        event: &Event,
+        history: &History,
+        kill_buffer: &mut String,
+    ) -> Outcome {
+        match event {
+            Event::Key(key) if key.kind != KeyEventKind::Release => {
+                let killing = self.killing;
+                self.killing = false;
+                if self.search.is_some() {
+                    self.handle_search_key(key, history, kill_buffer, killing)
+                } else {
+                    self.handle_key(key, history, kill_buffer, killing)
+                }
+            }
+            #[cfg(feature = "bracketed-paste")]
+            Event::Paste(text) => {
+                self.search = None;
+                self.killing = false;
+                self.buffer.insert_str(&sanitize(text));
+                Outcome::Redraw
+            }
+            Event::Resize(..) => Outcome::Redraw,
+            _ => Outcome::Ignore,
+        }
+    }
+
+    fn handle_key(
+        &mut self,
+        key: &KeyEvent,
+        history: &History,
+        kill_buffer: &mut String,
+        killing: bool,
+    ) -> Outcome {
+        let control = key.modifiers.contains(KeyModifiers::CONTROL);
+        let alt = key.modifiers.contains(KeyModifiers::ALT);
+
+        match key.code {
+            KeyCode::Enter => return Outcome::Accept,
+            KeyCode::Left if control => self.buffer.move_word_left(),
+            KeyCode::Right if control => self.buffer.move_word_right(),
+            KeyCode::Left => self.buffer.move_left(),
+            KeyCode::Right => self.buffer.move_right(),
+            KeyCode::Home => self.buffer.move_home(),
+            KeyCode::End => self.buffer.move_end(),
+            KeyCode::Up => self.history_previous(history),
+            KeyCode::Down => self.history_next(history),
+            KeyCode::Delete => self.buffer.delete_forward(),
+            KeyCode::Backspace if control || alt => {
+                let killed = self.buffer.kill_word_backward();
+                self.kill(killed, false, kill_buffer, killing);
+            }
+            KeyCode::Backspace => self.buffer.delete_backward(),
+            KeyCode::Char(c) if control => match c {
+                'c' => return Outcome::Interrupt,
+                'd' if self.buffer.text().is_empty() => return Outcome::Eof,
+                'l' => return Outcome::ClearScreen,
+                'a' => self.buffer.move_home(),
+                'e' => self.buffer.move_end(),
+                'b' => self.buffer.move_left(),
+                'f' => self.buffer.move_right(),
+                'h' => self.buffer.delete_backward(),
+                'd' => self.buffer.delete_forward(),
+                'p' => self.history_previous(history),
+                'n' => self.history_next(history),
+                'w' => {
+                    let killed = self.buffer.kill_big_word_backward();
+                    self.kill(killed, false, kill_buffer, killing);
+                }
+                'u' => {
+                    let killed = self.buffer.kill_to_start();
+                    self.kill(killed, false, kill_buffer, killing);
+                }
+                'k' => {
+                    let killed = self.buffer.kill_to_end();
+                    self.kill(killed, true, kill_buffer, killing);
+                }
+                'y' => self.buffer.insert_str(kill_buffer),
+                'r' => {
+                    self.search = Some(Search {
+                        original: self.buffer.clone(),
+                        ..Search::default()
+                    });
+                }
+                _ => return Outcome::Ignore,
+            },
+            KeyCode::Char(c) if alt => match c {
+                'b' => self.buffer.move_word_left(),
+                'f' => self.buffer.move_word_right(),
+                'd' => {
+                    let killed = self.buffer.kill_word_forward();
+                    self.kill(killed, true, kill_buffer, killing);
+                }
+                _ => return Outcome::Ignore,
+            },
+            KeyCode::Char(c) if !c.is_control() => {
+                let mut buffer = [0; 4];
+                self.buffer.insert_str(c.encode_utf8(&mut buffer));
+            }
+            _ => return Outcome::Ignore,
+        }
+
+        Outcome::Redraw
+    }
+
+    fn handle_search_key(
+        &mut self,
+        key: &KeyEvent,
+        history: &History,
+        kill_buffer: &mut String,
+        killing: bool,
+    ) -> Outcome {
+        let control = key.modifiers.contains(KeyModifiers::CONTROL);
+        let alt = key.modifiers.contains(KeyModifiers::ALT);
+        let search = self.search.as_mut().expect("search is active");
+
+        match key.code {
+            KeyCode::Char('r') if control => {
+                let start = search.index.unwrap_or(history.len());
+                self.search_history(history, start);
+            }
+            KeyCode::Char('g') if control => {
+                self.buffer = self.search.take().unwrap().original;
+            }
+            KeyCode::Esc => {
+                self.buffer = self.search.take().unwrap().original;
+            }
+            KeyCode::Backspace => {
+                search.query.pop();
+                self.search_history(history, history.len());
+            }
+            KeyCode::Char(c) if !control && !alt && !c.is_control() => {
+                search.query.push(c);
+                // The current match may still match the longer query.
+                let start = search.index.map_or(history.len(), |index| index + 1);
+                self.search_history(history, start);
+            }
+            _ => {
+                // Any other key accepts the match and is handled as usual.
+                let search = self.search.take().unwrap();
+                self.history_index = search.index;
+                if search.index.is_some() {
+                    self.draft = search.original.into_text();
+                }
+                return match self.handle_key(key, history, kill_buffer, killing) {
+                    Outcome::Ignore => Outcome::Redraw,
+                    outcome => outcome,
+                };
+            }
+        }
+
+        Outcome::Redraw
+    }
+
+    // Searches the history for the query of the active search, starting before the given index.
+    fn search_history(&mut self, history: &History, start: usize) {
+        let search = self.search.as_mut().expect("search is active");
+        if search.query.is_empty() {
+            search.index = None;
+            self.buffer = search.original.clone();
+            return;
+        }
+
+        let found = history
+            .iter()
+            .enumerate()
+            .take(start)
+            .rev()
+            .find_map(|(index, line)| {
+                line.rfind(&search.query)
+                    .map(|offset| (index, line, offset))
+            });
+
+        if let Some((index, line, offset)) = found {
+            search.index = Some(index);
+            self.buffer = LineBuffer::new(line.to_string());
+            self.buffer.set_cursor(offset);
+        }
+    }
+
+    fn kill(&mut self, killed: String, forward: bool, kill_buffer: &mut String, killing: bool) {
+        if !killing {
+            kill_buffer.clear();
+        }
+        if forward {
+            kill_buffer.push_str(&killed);
+        } else {
+            kill_buffer.insert_str(0, &killed);
+        }
+        self.killing = true;
+    }
+
+    fn history_previous(&mut self, history: &History) {
+        let index = match self.history_index {
+            Some(0) => return,
+            Some(index) => index - 1,
+            None if history.is_empty() => return,
+            None => {
+                self.draft = self.buffer.text().to_string();
+                history.len() - 1
+            }
+        };
+        self.history_index = Some(index);
+        self.buffer = LineBuffer::new(history.get(index).unwrap_or_default().to_string());
+    }
+
+    fn history_next(&mut self, history: &History) {
+        let index = match self.history_index {
+            Some(index) => index + 1,
+            None => return,
+        };
+        if index < history.len() {
+            self.history_index = Some(index);
+            self.buffer = LineBuffer::new(history.get(index).unwrap_or_default().to_string());
+        } else {
+            self.history_index = None;
+            self.buffer = LineBuffer::new(std::mem::take(&mut self.draft));
+        }
+    }
+}
+
+// Pasted text can contain line breaks and other control characters that would break the line.
+#[cfg(feature = "bracketed-paste")]
+fn sanitize(text: &str) -> String {
+    text.replace("\r\n", "\n")
+        .chars()
+        .filter_map(|c| match c {
+            '\n' | '\r' | '\t' => Some(' '),
+            c if c.is_control() => None,
+            c => Some(c),
+        })
+        .collect()
+}
+
+/// Draws the prompt and the line below the position the cursor was at when reading started.
+struct Renderer<D: Display> {
+    prompt: StyledContent<D>,
+    prompt_text: String,
+    columns: u16,
+    // The row of the cursor, relative to the row of the prompt.
+    cursor_row: u16,
+}
+
+impl<D: Display> Renderer<D> {
+    fn prompt(&self, state: &State) -> Option<String> {
+        state
+            .search
+            .as_ref()
+            .map(|search| format!("(reverse-i-search)`{}': ", search.query))
+    }
+
+    fn render(&mut self, writer: &mut impl io::Write, state: &State) -> io::Result<()> {
+        writer.queue(MoveToColumn(0))?;
+        if self.cursor_row > 0 {
+            writer.queue(MoveUp(self.cursor_row))?;
+        }
+        writer.queue(Clear(ClearType::FromCursorDown))?;
+
+        let search_prompt = self.prompt(state);
+        match &search_prompt {
+            Some(prompt) => writer.queue(Print(prompt))?,
+            None => writer.queue(Print(&self.prompt))?,
+        };
+        writer.queue(Print(state.buffer.text()))?;
+
+        let prompt_text = search_prompt.as_deref().unwrap_or(&self.prompt_text);
+        let (end, cursor) = self.layout(prompt_text, state);
+
+        // The terminal keeps the cursor in the last column after writing to it, move it to the
+        // next row ourselves to keep the layout predictable.
+        if end.1 == 0 && end.0 > 0 {
+            writer.queue(Print("\r\n"))?;
+        }
+        if end.0 > cursor.0 {
+            writer.queue(MoveUp(end.0 - cursor.0))?;
+        }
+        writer.queue(MoveToColumn(cursor.1))?;
+        self.cursor_row = cursor.0;
+
+        io::Write::flush(writer)
+    }
+
+    fn resize(&mut self, columns: u16, state: &State) {
+        // Assume the terminal reflowed the line to the new width.
+        self.columns = columns;
+        let prompt_text = self.prompt(state);
+        let prompt_text = prompt_text.as_deref().unwrap_or(&self.prompt_text);
+        self.cursor_row = self.layout(prompt_text, state).1 .0;
+    }
+
+    /// Moves the cursor below the line.
+    fn finish(&mut self, writer: &mut impl io::Write, state: &State) -> io::Result<()> {
+        let prompt_text = self.prompt(state);
+        let prompt_text = prompt_text.as_deref().unwrap_or(&self.prompt_text);
+        let (end, cursor) = self.layout(prompt_text, state);
+
+        if end.0 > cursor.0 {
+            writer.queue(MoveDown(end.0 - cursor.0))?;
+        }
+        if end.1 == 0 && end.0 > 0 {
+            writer.queue(MoveToColumn(0))?;
+        } else {
+            writer.queue(Print("\r\n"))?;
+        }
+        self.cursor_row = 0;
+
+        io::Write::flush(writer)
+    }
+
+    /// Returns the (row, column) of the end of the line and of the cursor.
+    fn layout(&self, prompt_text: &str, state: &State) -> ((u16, u16), (u16, u16)) {
+        let text = state.buffer.text();
+        // Terminals without a size (e.g. some pseudo terminals) report zero columns.
+        let columns = if self.columns == 0 { 80 } else { self.columns };
+        let mode = width_mode();
+        let prompt = advance((0, 0), prompt_text, columns, mode);
+        let cursor = advance(prompt, &text[..state.buffer.cursor()], columns, mode);
+        let end = advance(cursor, &text[state.buffer.cursor()..], columns, mode);
+        (wrap(end, columns), wrap(cursor, columns))
+    }
+}
+
+/// Advances the (row, column) position by the width of the text, wrapping grapheme clusters
+/// that don't fit into the row to the next one.
+fn advance(mut position: (u16, u16), text: &str, columns: u16, mode: WidthMode) -> (u16, u16) {
+    for grapheme in text.graphemes(true) {
+        let width = mode.grapheme_width(grapheme) as u16;
+        if width > 0 && position.1 + width > columns {
+            position = (position.0 + 1, 0);
+        }
+        position.1 += width;
+    }
+    position
+}
+
+/// Moves a position behind the last column to the start of the next row.
+fn wrap(position: (u16, u16), columns: u16) -> (u16, u16) {
+    if position.1 >= columns {
+        (position.0 + 1, 0)
+    } else {
+        position
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{advance, wrap, History, Outcome, State};
+    use crate::{
+        event::{Event, KeyCode, KeyEvent, KeyModifiers},
+        style::WidthMode,
+    };
+
+    fn type_keys(state: &mut State, history: &History, kill: &mut String, keys: &[KeyEvent]) {
+        for key in keys {
+            state.handle_event(&Event::Key(*key), history, kill);
+        }
+    }
+
+    fn chars(text: &str) -> Vec<KeyEvent> {
+        text.chars().map(|c| KeyCode::Char(c).into()).collect()
+    }
+
+    fn ctrl(c: char) -> KeyEvent {
+        KeyEvent::new(KeyCode::Char(c), KeyModifiers::CONTROL)
+    }
+
+    #[test]
+    fn test_edit_line() {
+        let (mut state, history, mut kill) = (State::default(), History::new(), String::new());
+
+        type_keys(&mut state, &history, &mut kill, &chars("hello world"));
+        type_keys(&mut state, &history, &mut kill, &[ctrl('a'), ctrl('f')]);
+        type_keys(&mut state, &history, &mut kill, &chars("-"));
+        assert_eq!(state.buffer.text(), "h-ello world");
+        assert_eq!(state.buffer.cursor(), 2);
+
+        type_keys(
+            &mut state,
+            &history,
+            &mut kill,
+            &[KeyCode::Backspace.into(), ctrl('e'), ctrl('w'), ctrl('w')],
+        );
+        assert_eq!(state.buffer.text(), "");
+        assert_eq!(kill, "hello world");
+
+        type_keys(
+            &mut state,
+            &history,
+            &mut kill,
+            &[ctrl('y'), ctrl('a'), ctrl('k')],
+        );
+        assert_eq!(kill, "hello world");
+        type_keys(&mut state, &history, &mut kill, &[ctrl('y'), ctrl('y')]);
+        assert_eq!(state.buffer.text(), "hello worldhello world");
+    }
+
+    #[test]
+    fn test_accept_and_abort() {
+        let (mut state, history, mut kill) = (State::default(), History::new(), String::new());
+
+        let enter = Event::Key(KeyCode::Enter.into());
+        assert_eq!(
+            state.handle_event(&enter, &history, &mut kill),
+            Outcome::Accept
+        );
+        let ctrl_d = Event::Key(ctrl('d'));
+        assert_eq!(
+            state.handle_event(&ctrl_d, &history, &mut kill),
+            Outcome::Eof
+        );
+        let ctrl_c = Event::Key(ctrl('c'));
+        assert_eq!(
+            state.handle_event(&ctrl_c, &history, &mut kill),
+            Outcome::Interrupt
+        );
+
+        type_keys(&mut state, &history, &mut kill, &chars("a"));
+        assert_eq!(
+            state.handle_event(&ctrl_d, &history, &mut kill),
+            Outcome::Redraw
+        );
+    }
+
+    #[test]
+    fn test_history() {
+        let mut history = History::new();
+        history.push("first");
+        history.push("second");
+        let (mut state, mut kill) = (State::default(), String::new());
+
+        type_keys(&mut state, &history, &mut kill, &chars("draft"));
+        type_keys(&mut state, &history, &mut kill, &[KeyCode::Up.into()]);
+        assert_eq!(state.buffer.text(), "second");
+        type_keys(&mut state, &history, &mut kill, &[ctrl('p'), ctrl('p')]);
+        assert_eq!(state.buffer.text(), "first");
+        type_keys(&mut state, &history, &mut kill, &[KeyCode::Down.into()]);
+        assert_eq!(state.buffer.text(), "second");
+        type_keys(&mut state, &history, &mut kill, &[KeyCode::Down.into()]);
+        assert_eq!(state.buffer.text(), "draft");
+    }
+
+    #[test]
+    fn test_history_search() {
+        let mut history = History::new();
+        history.push("cargo build");
+        history.push("cargo test");
+        history.push("git status");
+        let (mut state, mut kill) = (State::default(), String::new());
+
+        type_keys(&mut state, &history, &mut kill, &[ctrl('r')]);
+        type_keys(&mut state, &history, &mut kill, &chars("car"));
+        assert_eq!(state.buffer.text(), "cargo test");
+        type_keys(&mut state, &history, &mut kill, &[ctrl('r')]);
+        assert_eq!(state.buffer.text(), "cargo build");
+        type_keys(&mut state, &history, &mut kill, &chars("go b"));
+        assert_eq!(state.buffer.text(), "cargo build");
+
+        // Cancelling restores the line.
+        type_keys(&mut state, &history, &mut kill, &[ctrl('g')]);
+        assert_eq!(state.buffer.text(), "");
+
+        // Other keys accept the match.
+        type_keys(&mut state, &history, &mut kill, &[ctrl('r')]);
+        type_keys(&mut state, &history, &mut kill, &chars("stat"));
+        type_keys(&mut state, &history, &mut kill, &[ctrl('e')]);
+        type_keys(&mut state, &history, &mut kill, &chars(" -s"));
+        assert!(state.search.is_none());
+        assert_eq!(state.buffer.text(), "git status -s");
+    }
+
+    #[cfg(feature = "bracketed-paste")]
+    #[test]
+    fn test_paste() {
+        let (mut state, history, mut kill) = (State::default(), History::new(), String::new());
+
+        type_keys(&mut state, &history, &mut kill, &chars("ab"));
+        type_keys(&mut state, &history, &mut kill, &[KeyCode::Left.into()]);
+        state.handle_event(&Event::Paste("x\r\ny\x07".to_string()), &history, &mut kill);
+        assert_eq!(state.buffer.text(), "ax yb");
+    }
+
+    #[test]
+    fn test_layout_with_wide_chars() {
+        let mode = WidthMode::Characters;
+        assert_eq!(advance((0, 0), "ab字", 10, mode), (0, 4));
+        // The wide character doesn't fit into the last column.
+        assert_eq!(advance((0, 0), "abcd字", 5, mode), (1, 2));
+        assert_eq!(wrap(advance((0, 0), "abcde", 5, mode), 5), (1, 0));
+        assert_eq!(wrap(advance((0, 0), "abcd", 5, mode), 5), (0, 4));
+    }
+
+    #[test]
+    fn test_layout_with_grapheme_clusters() {
+        let family = "👨\u{200D}👩\u{200D}👧";
+        assert_eq!(advance((0, 0), family, 10, WidthMode::Characters), (0, 6));
+        assert_eq!(advance((0, 0), family, 10, WidthMode::Graphemes), (0, 2));
+        // The cluster is wrapped as a whole.
+        let text = format!("abc{}", family);
+        assert_eq!(advance((0, 0), &text, 5, WidthMode::Characters), (1, 6));
+        assert_eq!(advance((0, 0), &text, 4, WidthMode::Graphemes), (1, 2));
+    }
+}
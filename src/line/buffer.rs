@@ -0,0 +1,224 @@
+use unicode_segmentation::UnicodeSegmentation;
+
+/// The text of the edited line and the position of the cursor in it.
+///
+/// The cursor is a byte index that's always on a char boundary, moving it left or right steps
+/// over a whole grapheme cluster.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub(crate) struct LineBuffer {
+    text: String,
+    cursor: usize,
+}
+
+impl LineBuffer {
+    /// Creates a buffer with the cursor at the end of the text.
+    pub(crate) fn new(text: String) -> LineBuffer {
+        LineBuffer {
+            cursor: text.len(),
+            text,
+        }
+    }
+
+    pub(crate) fn text(&self) -> &str {
+        &self.text
+    }
+
+    pub(crate) fn into_text(self) -> String {
+        self.text
+    }
+
+    pub(crate) fn cursor(&self) -> usize {
+        self.cursor
+    }
+
+    pub(crate) fn set_cursor(&mut self, cursor: usize) {
+        debug_assert!(self.text.is_char_boundary(cursor));
+        self.cursor = cursor;
+    }
+
+    pub(crate) fn insert_str(&mut self, s: &str) {
+        self.text.insert_str(self.cursor, s);
+        self.cursor += s.len();
+    }
+
+    pub(crate) fn move_left(&mut self) {
+        self.cursor = self.prev_boundary();
+    }
+
+    pub(crate) fn move_right(&mut self) {
+        self.cursor = self.next_boundary();
+    }
+
+    pub(crate) fn move_home(&mut self) {
+        self.cursor = 0;
+    }
+
+    pub(crate) fn move_end(&mut self) {
+        self.cursor = self.text.len();
+    }
+
+    pub(crate) fn move_word_left(&mut self) {
+        self.cursor = self.word_start(is_word_char);
+    }
+
+    pub(crate) fn move_word_right(&mut self) {
+        self.cursor = self.word_end(is_word_char);
+    }
+
+    pub(crate) fn delete_backward(&mut self) {
+        let start = self.prev_boundary();
+        self.kill(start..self.cursor);
+    }
+
+    pub(crate) fn delete_forward(&mut self) {
+        let end = self.next_boundary();
+        self.kill(self.cursor..end);
+    }
+
+    pub(crate) fn kill_to_start(&mut self) -> String {
+        self.kill(0..self.cursor)
+    }
+
+    pub(crate) fn kill_to_end(&mut self) -> String {
+        self.kill(self.cursor..self.text.len())
+    }
+
+    /// Kills the word before the cursor, words are separated by whitespace.
+    pub(crate) fn kill_big_word_backward(&mut self) -> String {
+        let start = self.word_start(|c| !c.is_whitespace());
+        self.kill(start..self.cursor)
+    }
+
+    /// Kills the word before the cursor, words consist of alphanumeric characters.
+    pub(crate) fn kill_word_backward(&mut self) -> String {
+        let start = self.word_start(is_word_char);
+        self.kill(start..self.cursor)
+    }
+
+    pub(crate) fn kill_word_forward(&mut self) -> String {
+        let end = self.word_end(is_word_char);
+        self.kill(self.cursor..end)
+    }
+
+    fn kill(&mut self, range: std::ops::Range<usize>) -> String {
+        self.cursor = range.start;
+        self.text.drain(range).collect()
+    }
+
+    fn prev_boundary(&self) -> usize {
+        self.text[..self.cursor]
+            .grapheme_indices(true)
+            .next_back()
+            .map_or(0, |(index, _)| index)
+    }
+
+    fn next_boundary(&self) -> usize {
+        self.text[self.cursor..]
+            .graphemes(true)
+            .next()
+            .map_or(self.cursor, |grapheme| self.cursor + grapheme.len())
+    }
+
+    // The start of the word before the cursor, skipping the separators between the cursor and
+    // the word.
+    fn word_start(&self, is_word: impl Fn(char) -> bool) -> usize {
+        let mut chars = self.text[..self.cursor].char_indices().rev().peekable();
+        while chars.next_if(|(_, c)| !is_word(*c)).is_some() {}
+        let mut start = chars.peek().map_or(0, |(index, _)| *index);
+        while let Some((index, _)) = chars.next_if(|(_, c)| is_word(*c)) {
+            start = index;
+        }
+        start
+    }
+
+    // The end of the word after the cursor, skipping the separators between the cursor and the
+    // word.
+    fn word_end(&self, is_word: impl Fn(char) -> bool) -> usize {
+        let mut chars = self.text[self.cursor..].char_indices().peekable();
+        while chars.next_if(|(_, c)| !is_word(*c)).is_some() {}
+        while chars.next_if(|(_, c)| is_word(*c)).is_some() {}
+        chars
+            .peek()
+            .map_or(self.text.len(), |(index, _)| self.cursor + index)
+    }
+}
+
+fn is_word_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_'
+}
+
+#[cfg(test)]
+mod tests {
+    use super::LineBuffer;
+
+    fn buffer(text: &str, cursor: usize) -> LineBuffer {
+        let mut buffer = LineBuffer::new(text.to_string());
+        buffer.set_cursor(cursor);
+        buffer
+    }
+
+    #[test]
+    fn test_move_over_multi_byte_chars() {
+        let mut line = LineBuffer::new("aé字".to_string());
+        assert_eq!(line.cursor(), 6);
+
+        line.move_left();
+        assert_eq!(line.cursor(), 3);
+        line.move_left();
+        assert_eq!(line.cursor(), 1);
+        line.move_right();
+        assert_eq!(line.cursor(), 3);
+
+        line.delete_backward();
+        assert_eq!(line.text(), "a字");
+        assert_eq!(line.cursor(), 1);
+    }
+
+    #[test]
+    fn test_move_over_grapheme_clusters() {
+        let mut line = LineBuffer::new("ae\u{301}👍🏽".to_string());
+
+        line.move_left();
+        assert_eq!(line.cursor(), 4);
+        line.move_left();
+        assert_eq!(line.cursor(), 1);
+        line.move_right();
+        assert_eq!(line.cursor(), 4);
+
+        line.delete_forward();
+        assert_eq!(line.text(), "ae\u{301}");
+    }
+
+    #[test]
+    fn test_word_movement() {
+        let mut line = buffer("foo bar-baz  qux", 16);
+
+        line.move_word_left();
+        assert_eq!(line.cursor(), 13);
+        line.move_word_left();
+        assert_eq!(line.cursor(), 8);
+        line.move_word_left();
+        assert_eq!(line.cursor(), 4);
+        line.move_word_right();
+        assert_eq!(line.cursor(), 7);
+        line.move_word_right();
+        assert_eq!(line.cursor(), 11);
+        line.move_home();
+        line.move_word_left();
+        assert_eq!(line.cursor(), 0);
+    }
+
+    #[test]
+    fn test_kill() {
+        let mut line = buffer("foo bar-baz qux", 11);
+        assert_eq!(line.kill_word_backward(), "baz");
+        assert_eq!(line.kill_big_word_backward(), "bar-");
+        assert_eq!(line.text(), "foo  qux");
+
+        let mut line = buffer("foo bar baz", 3);
+        assert_eq!(line.kill_word_forward(), " bar");
+        assert_eq!(line.kill_to_end(), " baz");
+        assert_eq!(line.kill_to_start(), "foo");
+        assert_eq!(line.text(), "");
+    }
+}
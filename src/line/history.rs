@@ -0,0 +1,96 @@
+use std::collections::VecDeque;
+
+/// The lines entered in a [`LineEditor`](struct.LineEditor.html), oldest first.
+///
+/// Empty lines and lines equal to the previous one aren't added. When the history is full, the
+/// oldest line is removed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct History {
+    lines: VecDeque<String>,
+    max_len: usize,
+}
+
+impl Default for History {
+    fn default() -> Self {
+        History {
+            lines: VecDeque::new(),
+            max_len: 1000,
+        }
+    }
+}
+
+impl History {
+    /// Creates an empty history that holds up to 1000 lines.
+    pub fn new() -> History {
+        History::default()
+    }
+
+    /// Sets the maximum number of lines, removing the oldest lines if there are more.
+    pub fn set_max_len(&mut self, max_len: usize) {
+        self.max_len = max_len;
+        self.truncate();
+    }
+
+    /// Adds a line to the history.
+    pub fn push(&mut self, line: impl Into<String>) {
+        let line = line.into();
+        if line.is_empty() || self.lines.back() == Some(&line) {
+            return;
+        }
+        self.lines.push_back(line);
+        self.truncate();
+    }
+
+    /// Returns the line at the given index, `0` being the oldest line.
+    pub fn get(&self, index: usize) -> Option<&str> {
+        self.lines.get(index).map(String::as_str)
+    }
+
+    /// Returns an iterator over the lines, oldest first.
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item = &str> + ExactSizeIterator {
+        self.lines.iter().map(String::as_str)
+    }
+
+    /// Returns the number of lines.
+    pub fn len(&self) -> usize {
+        self.lines.len()
+    }
+
+    /// Returns `true` if the history has no lines.
+    pub fn is_empty(&self) -> bool {
+        self.lines.is_empty()
+    }
+
+    /// Removes all lines.
+    pub fn clear(&mut self) {
+        self.lines.clear();
+    }
+
+    fn truncate(&mut self) {
+        while self.lines.len() > self.max_len {
+            self.lines.pop_front();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::History;
+
+    #[test]
+    fn test_push() {
+        let mut history = History::new();
+        history.push("a");
+        history.push("");
+        history.push("a");
+        history.push("b");
+        history.push("a");
+
+        assert_eq!(history.iter().collect::<Vec<_>>(), ["a", "b", "a"]);
+
+        history.set_max_len(2);
+        assert_eq!(history.iter().collect::<Vec<_>>(), ["b", "a"]);
+        history.push("c");
+        assert_eq!(history.iter().collect::<Vec<_>>(), ["a", "c"]);
+    }
+}
@@ -0,0 +1,341 @@
+//! # Screen
+//!
+//! The `screen` module provides a [`Buffer`](struct.Buffer.html) of cells to draw on and a
+//! [`Renderer`](struct.Renderer.html) that draws it to the terminal, writing only the cells that
+//! changed since the previous frame.
+//!
+//! The renderer keeps a copy of the last rendered buffer (the front buffer) and compares it with
+//! the new one. It skips the unchanged cells, only emits the style attributes that differ
+//! between two cells and picks the shortest cursor movement to the next changed cell with
+//! [`plan_movement`](../cursor/fn.plan_movement.html). The frame is wrapped in
+//! [`BeginSynchronizedUpdate`](../terminal/struct.BeginSynchronizedUpdate.html) and
+//! [`EndSynchronizedUpdate`](../terminal/struct.EndSynchronizedUpdate.html), terminals that don't
+//! support synchronized updates ignore them.
+//!
+//! [`render_diff`](fn.render_diff.html) writes the difference between two buffers without the
+//! bookkeeping of the renderer.
+//!
+//! An [`InlineViewport`](struct.InlineViewport.html) renders buffers to a few rows below the
+//! cursor instead of the whole screen, without switching to the alternate screen.
+//!
+//! ## Examples
+//!
+//! ```no_run
+//! use std::io;
+//!
+//! use crossterm::{
+//!     screen::{Buffer, Renderer},
+//!     style::{ContentStyle, Stylize},
+//!     terminal,
+//! };
+//!
+//! fn main() -> io::Result<()> {
+//!     let (columns, rows) = terminal::size()?;
+//!     let mut buffer = Buffer::new(columns, rows);
+//!     let mut renderer = Renderer::new();
+//!
+//!     for frame in 0..100 {
+//!         buffer.clear();
+//!         buffer.set_string(0, 0, "Frame:", ContentStyle::new().bold());
+//!         buffer.set_string(7, 0, &frame.to_string(), ContentStyle::new().green());
+//!         // Only the frame number is written after the first frame.
+//!         renderer.render(&mut io::stdout(), &buffer)?;
+//!     }
+//!
+//!     Ok(())
+//! }
+//! ```
+
+use std::io;
+
+use crate::{
+    cursor::{plan_movement, MoveTo},
+    style::{
+        Color, ContentStyle, Print, ResetColor, SetAttributes, SetBackgroundColor,
+        SetForegroundColor, SetStyle, SetUnderlineColor,
+    },
+    terminal::{Clear, ClearType},
+    QueueableCommand, SynchronizedUpdate,
+};
+
+pub use self::buffer::{Buffer, Cell};
+pub use self::inline::InlineViewport;
+
+mod buffer;
+mod inline;
+
+/// Renders [`Buffer`]s to the terminal, writing only what changed since the previous one.
+///
+/// The buffer is drawn at the top left corner of the screen. The first frame, frames with a
+/// different size and the frame after [`invalidate`](Renderer::invalidate) clear the screen and
+/// redraw everything.
+///
+/// See the [module documentation](index.html) for more information.
+#[derive(Debug, Clone)]
+pub struct Renderer {
+    front: Buffer,
+    redraw: bool,
+}
+
+impl Renderer {
+    /// Creates a renderer that redraws the whole screen on the first frame.
+    pub fn new() -> Renderer {
+        Renderer {
+            front: Buffer::default(),
+            redraw: true,
+        }
+    }
+
+    /// Returns the last rendered buffer.
+    pub fn front(&self) -> &Buffer {
+        &self.front
+    }
+
+    /// Makes the next frame redraw the whole screen, e.g. after something else wrote to it.
+    pub fn invalidate(&mut self) {
+        self.redraw = true;
+    }
+
+    /// Renders a frame and flushes the writer.
+    pub fn render<W: io::Write>(&mut self, writer: &mut W, buffer: &Buffer) -> io::Result<()> {
+        let redraw = self.redraw
+            || self.front.width() != buffer.width()
+            || self.front.height() != buffer.height();
+        if redraw {
+            self.front = Buffer::new(buffer.width(), buffer.height());
+        } else if self.front == *buffer {
+            return Ok(());
+        }
+
+        writer.sync_update(|writer| {
+            if redraw {
+                writer.queue(Clear(ClearType::All))?;
+            }
+            render_diff(writer, &self.front, buffer)
+        })??;
+
+        self.front.clone_from(buffer);
+        self.redraw = false;
+        Ok(())
+    }
+}
+
+impl Default for Renderer {
+    fn default() -> Self {
+        Renderer::new()
+    }
+}
+
+/// Queues the commands that turn the screen showing `previous` into `next`.
+///
+/// The cells of `next` that aren't in `previous` are written too. The position of the cursor
+/// doesn't need to be known and the style is expected to be reset. The style is reset again at
+/// the end.
+pub fn render_diff<W: io::Write>(
+    writer: &mut W,
+    previous: &Buffer,
+    next: &Buffer,
+) -> io::Result<()> {
+    render_diff_at(writer, previous, next, 0)
+}
+
+/// Like [`render_diff`], with the buffers shown from the screen row `top` on.
+fn render_diff_at<W: io::Write>(
+    writer: &mut W,
+    previous: &Buffer,
+    next: &Buffer,
+    top: u16,
+) -> io::Result<()> {
+    let mut cursor = None;
+    let mut style = ContentStyle::default();
+
+    for row in 0..next.height() {
+        for column in 0..next.width() {
+            let cell = next.get(column, row).unwrap();
+            if cell.is_continuation() || previous.get(column, row) == Some(cell) {
+                continue;
+            }
+
+            match cursor {
+                Some(cursor) if cursor == (column, row) => {}
+                Some(cursor) => {
+                    // The cells between the cursor and the target are unchanged.
+                    let reprint = reprint(next, cursor, (column, row), &style);
+                    writer.queue(plan_movement(
+                        (cursor.0, top + cursor.1),
+                        (column, top + row),
+                        reprint.as_deref(),
+                    ))?;
+                }
+                None => {
+                    writer.queue(MoveTo(column, top + row))?;
+                }
+            }
+            queue_style_change(writer, &style, &cell.style())?;
+            style = cell.style();
+            writer.queue(Print(cell.symbol()))?;
+
+            let end = column + cell.width();
+            // The cursor position after writing to the last column differs between terminals.
+            cursor = if end < next.width() {
+                Some((end, row))
+            } else {
+                None
+            };
+        }
+    }
+
+    if style != ContentStyle::default() {
+        writer.queue(ResetColor)?;
+    }
+    Ok(())
+}
+
+/// Queues the commands to change the current style `from` to `to`.
+///
+/// Only the changed colors and the added attributes are set, unless an attribute has to be
+/// removed.
+fn queue_style_change<W: io::Write>(
+    writer: &mut W,
+    from: &ContentStyle,
+    to: &ContentStyle,
+) -> io::Result<()> {
+    if from == to {
+        return Ok(());
+    }
+
+    let removed = (from.attributes ^ to.attributes) & from.attributes;
+    if !removed.is_empty() {
+        writer.queue(ResetColor)?.queue(SetStyle(*to))?;
+        return Ok(());
+    }
+
+    if from.background_color != to.background_color {
+        writer.queue(SetBackgroundColor(
+            to.background_color.unwrap_or(Color::Reset),
+        ))?;
+    }
+    if from.foreground_color != to.foreground_color {
+        writer.queue(SetForegroundColor(
+            to.foreground_color.unwrap_or(Color::Reset),
+        ))?;
+    }
+    if from.underline_color != to.underline_color {
+        writer.queue(SetUnderlineColor(
+            to.underline_color.unwrap_or(Color::Reset),
+        ))?;
+    }
+    let added = (from.attributes ^ to.attributes) & to.attributes;
+    if !added.is_empty() {
+        writer.queue(SetAttributes(added))?;
+    }
+    Ok(())
+}
+
+/// Returns the text of the cells between the cursor and the target on the same row if they can
+/// be printed again to move the cursor, i.e. they're shown already and have the current style.
+fn reprint(
+    buffer: &Buffer,
+    (cursor_column, cursor_row): (u16, u16),
+    (column, row): (u16, u16),
+    style: &ContentStyle,
+) -> Option<String> {
+    if cursor_row != row || cursor_column >= column {
+        return None;
+    }
+    let mut text = String::new();
+    for cell_column in cursor_column..column {
+        let cell = buffer.get(cell_column, row)?;
+        if cell.style() != *style || (cell_column == cursor_column && cell.is_continuation()) {
+            return None;
+        }
+        text.push_str(cell.symbol());
+    }
+    Some(text)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::style::{with_color_mode, ColorMode, ContentStyle, Stylize};
+
+    use super::{render_diff, Buffer, Renderer};
+
+    fn diff(previous: &Buffer, next: &Buffer) -> String {
+        let mut output = Vec::new();
+        render_diff(&mut output, previous, next).unwrap();
+        String::from_utf8(output).unwrap()
+    }
+
+    #[test]
+    fn test_render_diff_skips_unchanged_cells() {
+        let mut previous = Buffer::new(20, 3);
+        previous.set_string(0, 0, "hello", ContentStyle::new());
+        let mut next = previous.clone();
+
+        assert_eq!(diff(&previous, &next), "");
+
+        next.set_string(0, 0, "help", ContentStyle::new());
+        next.set_string(10, 0, "x", ContentStyle::new());
+        next.set_string(11, 2, "y", ContentStyle::new());
+        assert_eq!(diff(&previous, &next), "\x1b[1;4Hp\x1b[6Cx\x1b[2By");
+    }
+
+    #[test]
+    fn test_render_diff_reprints_short_gaps() {
+        let mut previous = Buffer::new(10, 1);
+        previous.set_string(0, 0, "abcde", ContentStyle::new());
+        let mut next = previous.clone();
+        next.set_string(0, 0, "X", ContentStyle::new());
+        next.set_string(3, 0, "Y", ContentStyle::new());
+
+        assert_eq!(diff(&previous, &next), "\x1b[1;1HXbcY");
+    }
+
+    #[test]
+    fn test_render_diff_writes_wide_graphemes_once() {
+        let previous = Buffer::new(4, 1);
+        let mut next = previous.clone();
+        next.set_string(0, 0, "中a", ContentStyle::new());
+
+        assert_eq!(diff(&previous, &next), "\x1b[1;1H中a");
+    }
+
+    #[test]
+    fn test_render_diff_merges_style_changes() {
+        with_color_mode(ColorMode::TrueColor, || {
+            let previous = Buffer::new(4, 1);
+            let mut next = previous.clone();
+            next.set(0, 0, "a", ContentStyle::new().red().bold());
+            next.set(1, 0, "b", ContentStyle::new().blue().bold());
+            next.set(2, 0, "c", ContentStyle::new().blue());
+
+            assert_eq!(
+                diff(&previous, &next),
+                "\x1b[1;1H\x1b[38;5;9m\x1b[1ma\x1b[38;5;12mb\x1b[0m\x1b[38;5;12mc\x1b[0m"
+            );
+        });
+    }
+
+    #[test]
+    fn test_renderer_redraws_only_when_needed() {
+        let mut renderer = Renderer::new();
+        let mut buffer = Buffer::new(2, 1);
+        buffer.set_string(0, 0, "a", ContentStyle::new());
+
+        let mut output = Vec::new();
+        renderer.render(&mut output, &buffer).unwrap();
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            "\x1b[?2026h\x1b[2J\x1b[1;1Ha\x1b[?2026l"
+        );
+
+        let mut output = Vec::new();
+        renderer.render(&mut output, &buffer).unwrap();
+        assert!(output.is_empty());
+
+        renderer.invalidate();
+        let mut output = Vec::new();
+        renderer.render(&mut output, &buffer).unwrap();
+        assert!(!output.is_empty());
+    }
+}
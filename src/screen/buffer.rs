@@ -0,0 +1,273 @@
+use unicode_segmentation::UnicodeSegmentation;
+
+use crate::style::{self, ContentStyle};
+
+/// A cell of a [`Buffer`](struct.Buffer.html): a grapheme and its style.
+///
+/// A wide grapheme (e.g. `'中'`) covers two cells. The second one is a continuation cell with an
+/// empty symbol, it isn't drawn on its own.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Cell {
+    symbol: String,
+    style: ContentStyle,
+}
+
+impl Cell {
+    /// Returns the grapheme of the cell, an empty string for a continuation cell.
+    pub fn symbol(&self) -> &str {
+        &self.symbol
+    }
+
+    /// Returns the style of the cell.
+    pub fn style(&self) -> ContentStyle {
+        self.style
+    }
+
+    /// Returns the number of columns the grapheme covers, `0` for a continuation cell.
+    pub fn width(&self) -> u16 {
+        grapheme_width(&self.symbol)
+    }
+
+    pub(crate) fn is_continuation(&self) -> bool {
+        self.symbol.is_empty()
+    }
+
+    fn continuation(style: ContentStyle) -> Cell {
+        Cell {
+            symbol: String::new(),
+            style,
+        }
+    }
+
+    fn set(&mut self, symbol: &str, style: ContentStyle) {
+        self.symbol.clear();
+        self.symbol.push_str(symbol);
+        self.style = style;
+    }
+}
+
+impl Default for Cell {
+    /// Returns a blank cell: a space without style.
+    fn default() -> Self {
+        Cell {
+            symbol: " ".to_string(),
+            style: ContentStyle::default(),
+        }
+    }
+}
+
+/// A grid of [`Cell`](struct.Cell.html)s to draw on, rendered to the terminal with a
+/// [`Renderer`](struct.Renderer.html).
+///
+/// The columns and rows are 0-based, like the ones of [`MoveTo`](../cursor/struct.MoveTo.html).
+/// Graphemes written outside of the buffer are ignored.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Buffer {
+    width: u16,
+    height: u16,
+    cells: Vec<Cell>,
+}
+
+impl Buffer {
+    /// Creates a buffer of blank cells.
+    pub fn new(width: u16, height: u16) -> Buffer {
+        Buffer {
+            width,
+            height,
+            cells: vec![Cell::default(); usize::from(width) * usize::from(height)],
+        }
+    }
+
+    /// Returns the number of columns.
+    pub fn width(&self) -> u16 {
+        self.width
+    }
+
+    /// Returns the number of rows.
+    pub fn height(&self) -> u16 {
+        self.height
+    }
+
+    /// Returns the cell at the given position.
+    pub fn get(&self, column: u16, row: u16) -> Option<&Cell> {
+        self.index(column, row).map(|index| &self.cells[index])
+    }
+
+    /// Writes a grapheme to the given position, returning the number of columns it covers.
+    ///
+    /// A wide grapheme that doesn't fit into the row and a grapheme without width (e.g. a control
+    /// character) aren't written. A wide grapheme partly overwritten by another one is replaced
+    /// with blank cells.
+    pub fn set(&mut self, column: u16, row: u16, grapheme: &str, style: ContentStyle) -> u16 {
+        let width = grapheme_width(grapheme);
+        if width == 0 || column.saturating_add(width) > self.width {
+            return 0;
+        }
+        let index = match self.index(column, row) {
+            Some(index) => index,
+            None => return 0,
+        };
+
+        self.blank_overwritten(index, usize::from(width));
+        self.cells[index].set(grapheme, style);
+        if width == 2 {
+            self.cells[index + 1] = Cell::continuation(style);
+        }
+        width
+    }
+
+    /// Writes a string to the given position, returning the column after the last written
+    /// grapheme.
+    ///
+    /// The string is cut off at the end of the row.
+    pub fn set_string(&mut self, column: u16, row: u16, string: &str, style: ContentStyle) -> u16 {
+        let mut column = column;
+        for grapheme in string.graphemes(true) {
+            if column >= self.width {
+                break;
+            }
+            let width = grapheme_width(grapheme);
+            if width > 0 && self.set(column, row, grapheme, style) == 0 {
+                // A wide grapheme at the end of the row.
+                break;
+            }
+            column += width;
+        }
+        column
+    }
+
+    /// Sets the style of the given number of cells, keeping their graphemes.
+    pub fn set_style(&mut self, column: u16, row: u16, width: u16, style: ContentStyle) {
+        if let Some(start) = self.index(column, row) {
+            let end = start + usize::from(width.min(self.width - column));
+            for cell in &mut self.cells[start..end] {
+                cell.style = style;
+            }
+        }
+    }
+
+    /// Resets all cells to blank ones.
+    pub fn clear(&mut self) {
+        for cell in &mut self.cells {
+            *cell = Cell::default();
+        }
+    }
+
+    /// Changes the size of the buffer, keeping the cells that are still within it.
+    pub fn resize(&mut self, width: u16, height: u16) {
+        let mut resized = Buffer::new(width, height);
+        for row in 0..self.height.min(height) {
+            let mut column = 0;
+            while column < self.width.min(width) {
+                let cell = &self.cells[self.index(column, row).unwrap()];
+                let cell_width = cell.width().max(1);
+                if !cell.is_continuation() {
+                    resized.set(column, row, &cell.symbol, cell.style);
+                }
+                column += cell_width;
+            }
+        }
+        *self = resized;
+    }
+
+    fn index(&self, column: u16, row: u16) -> Option<usize> {
+        if column < self.width && row < self.height {
+            Some(usize::from(row) * usize::from(self.width) + usize::from(column))
+        } else {
+            None
+        }
+    }
+
+    /// Blanks the wide graphemes that are cut by writing `width` cells at `index`.
+    fn blank_overwritten(&mut self, index: usize, width: usize) {
+        let column = index % usize::from(self.width);
+        if self.cells[index].is_continuation() && column > 0 {
+            self.cells[index - 1] = Cell::default();
+        }
+        let last = index + width - 1;
+        if self.cells[last].width() == 2 {
+            self.cells[last + 1] = Cell::default();
+        }
+    }
+}
+
+/// Returns the number of columns a grapheme covers, at most the two columns of a cell.
+fn grapheme_width(grapheme: &str) -> u16 {
+    style::grapheme_width(grapheme).min(2) as u16
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::style::{Color, ContentStyle, Stylize};
+
+    use super::Buffer;
+
+    fn row(buffer: &Buffer, row: u16) -> String {
+        (0..buffer.width())
+            .map(|column| {
+                let symbol = buffer.get(column, row).unwrap().symbol();
+                if symbol.is_empty() {
+                    "_"
+                } else {
+                    symbol
+                }
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_set_string_handles_wide_graphemes() {
+        let mut buffer = Buffer::new(6, 1);
+
+        assert_eq!(
+            buffer.set_string(0, 0, "a中e\u{301}", ContentStyle::new()),
+            4
+        );
+        assert_eq!(row(&buffer, 0), "a中_e\u{301}  ");
+        assert_eq!(buffer.get(2, 0).unwrap().width(), 0);
+
+        // A wide grapheme at the end of the row doesn't fit.
+        assert_eq!(buffer.set_string(4, 0, "x中", ContentStyle::new()), 5);
+        assert_eq!(row(&buffer, 0), "a中_e\u{301}x ");
+    }
+
+    #[test]
+    fn test_set_blanks_cut_wide_graphemes() {
+        let mut buffer = Buffer::new(6, 1);
+        buffer.set_string(0, 0, "中文字", ContentStyle::new());
+
+        buffer.set(1, 0, "a", ContentStyle::new());
+        assert_eq!(row(&buffer, 0), " a文_字_");
+
+        buffer.set(3, 0, "国", ContentStyle::new());
+        assert_eq!(row(&buffer, 0), " a 国_ ");
+
+        buffer.set(3, 0, "b", ContentStyle::new());
+        assert_eq!(row(&buffer, 0), " a b  ");
+    }
+
+    #[test]
+    fn test_set_ignores_positions_outside() {
+        let mut buffer = Buffer::new(2, 1);
+
+        assert_eq!(buffer.set(2, 0, "a", ContentStyle::new()), 0);
+        assert_eq!(buffer.set(0, 1, "a", ContentStyle::new()), 0);
+        assert_eq!(buffer, Buffer::new(2, 1));
+    }
+
+    #[test]
+    fn test_resize_keeps_cells() {
+        let mut buffer = Buffer::new(4, 2);
+        buffer.set_string(0, 0, "ab中", ContentStyle::new());
+        buffer.set_style(0, 1, 10, ContentStyle::new().red());
+
+        buffer.resize(3, 3);
+
+        assert_eq!(row(&buffer, 0), "ab ");
+        assert_eq!(
+            buffer.get(2, 1).unwrap().style().foreground_color,
+            Some(Color::Red)
+        );
+        assert_eq!(row(&buffer, 2), "   ");
+    }
+}
@@ -0,0 +1,348 @@
+use std::{io, mem};
+
+#[cfg(feature = "events")]
+use crate::{cursor, terminal};
+use crate::{
+    cursor::{plan_movement, MoveTo},
+    style::{ContentStyle, Print, ResetColor},
+    terminal::{Clear, ClearType},
+    QueueableCommand, SynchronizedUpdate,
+};
+
+use super::{queue_style_change, render_diff_at, Buffer};
+
+/// Renders [`Buffer`](struct.Buffer.html)s to a region of rows below the cursor, without
+/// switching to the alternate screen.
+///
+/// The rows are reserved when the viewport is created, scrolling the terminal if the cursor is
+/// too close to its bottom. The frames are rendered like the ones of a
+/// [`Renderer`](struct.Renderer.html), writing only what changed. Lines printed with
+/// [`insert_before`](#method.insert_before) move the viewport down and stay in the scrollback,
+/// and so does the last frame after [`finish`](#method.finish).
+///
+/// The cursor is left at the top left corner of the viewport after each call, so that the
+/// viewport can be found again after the terminal was resized.
+#[derive(Debug, Clone)]
+pub struct InlineViewport {
+    height: u16,
+    screen: (u16, u16),
+    top: u16,
+    front: Buffer,
+}
+
+impl InlineViewport {
+    /// Reserves and clears `height` rows below the cursor, or below the current line if the
+    /// cursor isn't at its start.
+    ///
+    /// The viewport is at most as high as the screen.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use std::io;
+    ///
+    /// use crossterm::{screen::InlineViewport, style::ContentStyle};
+    ///
+    /// fn main() -> io::Result<()> {
+    ///     let mut stdout = io::stdout();
+    ///     let mut viewport = InlineViewport::new(&mut stdout, 1)?;
+    ///     let mut buffer = viewport.buffer();
+    ///
+    ///     for percent in 0..=100 {
+    ///         buffer.set_string(0, 0, &format!("Progress: {}%", percent), ContentStyle::new());
+    ///         viewport.render(&mut stdout, &buffer)?;
+    ///     }
+    ///     viewport.insert_before(&mut stdout, 1, |buffer| {
+    ///         buffer.set_string(0, 0, "Done", ContentStyle::new());
+    ///     })?;
+    ///
+    ///     viewport.finish(&mut stdout)
+    /// }
+    /// ```
+    #[cfg(feature = "events")]
+    pub fn new<W: io::Write>(writer: &mut W, height: u16) -> io::Result<InlineViewport> {
+        InlineViewport::with_position(writer, height, terminal::size()?, cursor::position()?)
+    }
+
+    /// Like [`new`](#method.new), with the size of the screen (columns, rows) and the position
+    /// of the cursor (column, row) already known.
+    pub fn with_position<W: io::Write>(
+        writer: &mut W,
+        height: u16,
+        screen: (u16, u16),
+        (column, row): (u16, u16),
+    ) -> io::Result<InlineViewport> {
+        let mut viewport = InlineViewport {
+            height,
+            screen,
+            top: row,
+            front: Buffer::default(),
+        };
+        if column > 0 {
+            writer.write_all(b"\r\n")?;
+            viewport.top = (row + 1).min(viewport.last_row());
+        }
+        viewport.reserve(writer)?;
+        // The rows below the cursor aren't necessarily empty.
+        writer.queue(Clear(ClearType::FromCursorDown))?;
+        viewport.front = viewport.buffer();
+        writer.flush()?;
+        Ok(viewport)
+    }
+
+    /// Returns the number of columns.
+    pub fn width(&self) -> u16 {
+        self.screen.0
+    }
+
+    /// Returns the number of rows.
+    pub fn height(&self) -> u16 {
+        self.height.min(self.screen.1)
+    }
+
+    /// Returns the screen row of the first row of the viewport.
+    pub fn top(&self) -> u16 {
+        self.top
+    }
+
+    /// Returns a blank buffer of the size of the viewport.
+    pub fn buffer(&self) -> Buffer {
+        Buffer::new(self.width(), self.height())
+    }
+
+    /// Renders a frame and flushes the writer.
+    ///
+    /// The buffer should have the size of the viewport, the cells outside of it aren't rendered.
+    pub fn render<W: io::Write>(&mut self, writer: &mut W, buffer: &Buffer) -> io::Result<()> {
+        let mut clipped;
+        let buffer = if buffer.width() == self.width() && buffer.height() == self.height() {
+            buffer
+        } else {
+            clipped = buffer.clone();
+            clipped.resize(self.width(), self.height());
+            &clipped
+        };
+        if self.front == *buffer {
+            return Ok(());
+        }
+
+        writer.sync_update(|writer| {
+            render_diff_at(writer, &self.front, buffer, self.top)?;
+            self.park_cursor(writer)
+        })??;
+
+        self.front.clone_from(buffer);
+        Ok(())
+    }
+
+    /// Prints `height` rows drawn by `draw` above the viewport, moving the viewport down.
+    ///
+    /// The rows are written like text, so that they stay in the scrollback when the terminal
+    /// scrolls. The viewport is redrawn below them.
+    pub fn insert_before<W, F>(&mut self, writer: &mut W, height: u16, draw: F) -> io::Result<()>
+    where
+        W: io::Write,
+        F: FnOnce(&mut Buffer),
+    {
+        let mut lines = Buffer::new(self.width(), height);
+        draw(&mut lines);
+
+        writer.sync_update(|writer| {
+            writer
+                .queue(MoveTo(0, self.top))?
+                .queue(Clear(ClearType::FromCursorDown))?;
+            for row in 0..lines.height() {
+                queue_line(writer, &lines, row)?;
+                writer.write_all(b"\r\n")?;
+            }
+            self.top = (self.top + height).min(self.last_row());
+            self.reserve(writer)?;
+            self.redraw(writer)
+        })?
+    }
+
+    /// Adapts the viewport to a new size of the screen (columns, rows), e.g. after an
+    /// [`Event::Resize`](../event/enum.Event.html#variant.Resize), and redraws it.
+    ///
+    /// The terminal may have moved the rows when it reflowed the lines, so the viewport is found
+    /// again with the position of the cursor.
+    #[cfg(feature = "events")]
+    pub fn resize<W: io::Write>(&mut self, writer: &mut W, screen: (u16, u16)) -> io::Result<()> {
+        self.resize_with_position(writer, screen, cursor::position()?)
+    }
+
+    /// Like [`resize`](#method.resize), with the position of the cursor (column, row) already
+    /// known.
+    pub fn resize_with_position<W: io::Write>(
+        &mut self,
+        writer: &mut W,
+        screen: (u16, u16),
+        (_, row): (u16, u16),
+    ) -> io::Result<()> {
+        self.screen = screen;
+        self.top = row.min(self.last_row());
+        self.front.resize(self.width(), self.height());
+
+        writer.sync_update(|writer| {
+            writer
+                .queue(MoveTo(0, self.top))?
+                .queue(Clear(ClearType::FromCursorDown))?;
+            self.reserve(writer)?;
+            self.redraw(writer)
+        })?
+    }
+
+    /// Leaves the last frame in the scrollback and moves the cursor to the start of the row below
+    /// it.
+    pub fn finish<W: io::Write>(self, writer: &mut W) -> io::Result<()> {
+        let last = self.top + self.height().saturating_sub(1);
+        writer
+            .queue(plan_movement((0, self.top), (0, last), None))?
+            .write_all(b"\r\n")?;
+        writer.flush()
+    }
+
+    fn last_row(&self) -> u16 {
+        self.screen.1.saturating_sub(1)
+    }
+
+    /// Scrolls the terminal until there are enough rows from the cursor at the start of the
+    /// `top` row on, and moves the cursor back to the top of the viewport.
+    fn reserve<W: io::Write>(&mut self, writer: &mut W) -> io::Result<()> {
+        let lines = self.height().saturating_sub(1);
+        for _ in 0..lines {
+            writer.write_all(b"\n")?;
+        }
+        let bottom = (self.top + lines).min(self.last_row());
+        self.top = bottom - lines;
+        writer.queue(plan_movement((0, bottom), (0, self.top), None))?;
+        Ok(())
+    }
+
+    /// Draws the front buffer to the cleared viewport.
+    fn redraw<W: io::Write>(&mut self, writer: &mut W) -> io::Result<()> {
+        let front = mem::take(&mut self.front);
+        render_diff_at(writer, &self.buffer(), &front, self.top)?;
+        self.front = front;
+        self.park_cursor(writer)
+    }
+
+    fn park_cursor<W: io::Write>(&self, writer: &mut W) -> io::Result<()> {
+        writer.queue(MoveTo(0, self.top))?;
+        Ok(())
+    }
+}
+
+/// Queues the cells of a row as text, leaving out the blank cells at its end.
+fn queue_line<W: io::Write>(writer: &mut W, buffer: &Buffer, row: u16) -> io::Result<()> {
+    let blank = super::Cell::default();
+    let end = (0..buffer.width())
+        .rev()
+        .find(|&column| buffer.get(column, row) != Some(&blank))
+        .map_or(0, |column| column + 1);
+
+    let mut style = ContentStyle::default();
+    for column in 0..end {
+        let cell = buffer.get(column, row).unwrap();
+        if cell.is_continuation() {
+            continue;
+        }
+        queue_style_change(writer, &style, &cell.style())?;
+        style = cell.style();
+        writer.queue(Print(cell.symbol()))?;
+    }
+    if style != ContentStyle::default() {
+        writer.queue(ResetColor)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::style::{with_color_mode, ColorMode, ContentStyle, Stylize};
+
+    use super::InlineViewport;
+
+    fn output(f: impl FnOnce(&mut Vec<u8>)) -> String {
+        let mut output = Vec::new();
+        f(&mut output);
+        String::from_utf8(output).unwrap()
+    }
+
+    fn reserve(height: u16, screen: (u16, u16), position: (u16, u16)) -> (String, InlineViewport) {
+        let mut output = Vec::new();
+        let viewport =
+            InlineViewport::with_position(&mut output, height, screen, position).unwrap();
+        (String::from_utf8(output).unwrap(), viewport)
+    }
+
+    #[test]
+    fn test_reserves_rows_below_the_cursor() {
+        let (written, viewport) = reserve(3, (80, 24), (0, 5));
+        assert_eq!(written, "\n\n\x1b[6H\x1b[J");
+        assert_eq!(viewport.top(), 5);
+
+        // The rows after a prompt at the bottom are made by scrolling.
+        let (written, viewport) = reserve(3, (80, 24), (10, 23));
+        assert_eq!(written, "\r\n\n\n\x1b[2A\x1b[J");
+        assert_eq!(viewport.top(), 21);
+
+        // The viewport isn't higher than the screen.
+        let (written, viewport) = reserve(10, (80, 4), (0, 3));
+        assert_eq!(written, "\n\n\n\x1b[H\x1b[J");
+        assert_eq!((viewport.top(), viewport.height()), (0, 4));
+    }
+
+    #[test]
+    fn test_render_writes_below_the_top() {
+        let mut viewport =
+            InlineViewport::with_position(&mut Vec::new(), 2, (10, 24), (0, 5)).unwrap();
+        let mut buffer = viewport.buffer();
+        buffer.set_string(0, 1, "ab", ContentStyle::new());
+
+        assert_eq!(
+            output(|w| viewport.render(w, &buffer).unwrap()),
+            "\x1b[?2026h\x1b[7;1Hab\x1b[6;1H\x1b[?2026l"
+        );
+        assert!(output(|w| viewport.render(w, &buffer).unwrap()).is_empty());
+    }
+
+    #[test]
+    fn test_insert_before_moves_the_viewport_down() {
+        with_color_mode(ColorMode::TrueColor, || {
+            let mut viewport =
+                InlineViewport::with_position(&mut Vec::new(), 2, (10, 5), (0, 2)).unwrap();
+            let mut buffer = viewport.buffer();
+            buffer.set_string(0, 0, "ui", ContentStyle::new());
+            viewport.render(&mut Vec::new(), &buffer).unwrap();
+
+            let written = output(|w| {
+                viewport
+                    .insert_before(w, 2, |lines| {
+                        lines.set_string(0, 0, "one", ContentStyle::new().red());
+                        lines.set_string(0, 1, "two", ContentStyle::new());
+                    })
+                    .unwrap()
+            });
+            assert_eq!(
+                written,
+                "\x1b[?2026h\x1b[3;1H\x1b[J\x1b[38;5;9mone\x1b[0m\r\ntwo\r\n\n\x1b[A\
+                 \x1b[4;1Hui\x1b[4;1H\x1b[?2026l"
+            );
+            assert_eq!(viewport.top(), 3);
+        });
+    }
+
+    #[test]
+    fn test_resize_finds_the_viewport_again() {
+        let mut viewport =
+            InlineViewport::with_position(&mut Vec::new(), 2, (10, 24), (0, 5)).unwrap();
+
+        let written = output(|w| viewport.resize_with_position(w, (20, 6), (0, 5)).unwrap());
+        assert_eq!(
+            written,
+            "\x1b[?2026h\x1b[6;1H\x1b[J\n\x1b[A\x1b[5;1H\x1b[?2026l"
+        );
+        assert_eq!((viewport.top(), viewport.width()), (4, 20));
+    }
+}
@@ -117,22 +117,30 @@ use std::{
 };
 
 use crate::command::execute_fmt;
+use crate::tty::IsTty;
 use crate::{csi, impl_display, Command};
 
 pub use self::{
     attributes::Attributes,
     content_style::ContentStyle,
     styled_content::StyledContent,
+    styled_writer::StyledWriter,
     stylize::Stylize,
-    types::{Attribute, Color, Colored, Colors},
+    types::{Attribute, Color, ColorMode, Colored, Colors, Hsl, Oklch},
+    width::{
+        grapheme_width, pad_to_width, set_width_mode, text_width, truncate_to_width, width_mode,
+        Alignment, WidthMode,
+    },
 };
 
 mod attributes;
 mod content_style;
 mod styled_content;
+mod styled_writer;
 mod stylize;
 mod sys;
 mod types;
+mod width;
 
 /// Creates a `StyledContent`.
 ///
@@ -184,14 +192,76 @@ pub fn available_color_count() -> u16 {
 ///
 /// # Notes
 ///
-/// crossterm supports NO_COLOR (https://no-color.org/) to disabled colored output.
+/// crossterm supports NO_COLOR (https://no-color.org/), `CLICOLOR=0`, `FORCE_COLOR=0` and
+/// `TERM=dumb` to disabled colored output, and `CLICOLOR_FORCE` and `FORCE_COLOR` to force it.
 ///
 /// This API allows applications to override that behavior and force colorized output
-/// even if NO_COLOR is set.
+/// even if NO_COLOR is set, also to streams that aren't a terminal (see
+/// [`color_mode_for`](fn.color_mode_for.html)).
 pub fn force_color_output(enabled: bool) {
     Colored::set_ansi_color_disabled(!enabled)
 }
 
+/// Returns the color mode the colors are downgraded to.
+///
+/// It's detected from the environment variables on the first call, unless it was set with
+/// [`set_color_mode`](fn.set_color_mode.html) before.
+pub fn color_mode() -> ColorMode {
+    ColorMode::current()
+}
+
+/// Sets the color mode the colors are downgraded to, overriding the detected one.
+///
+/// # Examples
+///
+/// ```no_run
+/// use crossterm::style::{set_color_mode, ColorMode};
+///
+/// // The Linux console only shows the 16 named colors.
+/// set_color_mode(ColorMode::Ansi16);
+/// ```
+pub fn set_color_mode(mode: ColorMode) {
+    ColorMode::set_current(mode)
+}
+
+/// Returns the color mode for the colors written to a stream, e.g. `io::stderr()`.
+///
+/// Colors aren't written to a stream that isn't a terminal, like a pipe or a file, unless they're
+/// forced with `CLICOLOR_FORCE`, `FORCE_COLOR` or
+/// [`force_color_output`](fn.force_color_output.html). The environment variables of
+/// [`Colored::ansi_color_disabled`](enum.Colored.html#method.ansi_color_disabled) disable them for
+/// all streams.
+///
+/// Use [`with_color_mode`](fn.with_color_mode.html) to write commands with the returned mode.
+pub fn color_mode_for<S: IsTty + ?Sized>(stream: &S) -> ColorMode {
+    Colored::color_mode_for(stream.is_tty())
+}
+
+/// Calls `f` with the colors of the commands written on this thread downgraded to the given
+/// color mode, instead of the global one.
+///
+/// # Examples
+///
+/// ```no_run
+/// use std::io;
+///
+/// use crossterm::{
+///     execute,
+///     style::{color_mode_for, with_color_mode, Print, SetForegroundColor, Color},
+/// };
+///
+/// fn main() -> io::Result<()> {
+///     // The colors are kept if stderr is a terminal, even if stdout is piped to a file.
+///     let mut stderr = io::stderr();
+///     with_color_mode(color_mode_for(&stderr), || {
+///         execute!(stderr, SetForegroundColor(Color::Red), Print("error"))
+///     })
+/// }
+/// ```
+pub fn with_color_mode<R>(mode: ColorMode, f: impl FnOnce() -> R) -> R {
+    ColorMode::with_scoped(mode, f)
+}
+
 /// A command that sets the the foreground color.
 ///
 /// See [`Color`](enum.Color.html) for more info.
@@ -2,7 +2,7 @@
 
 use std::fmt::{self, Display, Formatter};
 
-use super::{ContentStyle, PrintStyledContent};
+use super::{text_width, ContentStyle, PrintStyledContent};
 
 /// The style with the content to be styled.
 ///
@@ -45,6 +45,13 @@ impl<D: Display> StyledContent<D> {
         &self.style
     }
 
+    /// Returns the number of columns the content covers in the terminal.
+    ///
+    /// See [`text_width`](fn.text_width.html) for more information.
+    pub fn width(&self) -> usize {
+        text_width(&self.content.to_string())
+    }
+
     /// Returns a mutable reference to the style, so that it can be further
     /// manipulated
     #[inline]
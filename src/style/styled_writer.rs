@@ -0,0 +1,836 @@
+use std::{
+    io::{self, Write},
+    mem,
+};
+
+use unicode_segmentation::UnicodeSegmentation;
+
+use crate::{
+    csi,
+    style::{width_mode, Attribute, Color, Colored, ContentStyle, WidthMode},
+};
+
+/// The attributes the writer keeps track of, in the order they're set.
+const ATTRIBUTES: [Attribute; 17] = [
+    Attribute::Bold,
+    Attribute::Dim,
+    Attribute::Italic,
+    Attribute::Underlined,
+    Attribute::DoubleUnderlined,
+    Attribute::Undercurled,
+    Attribute::Underdotted,
+    Attribute::Underdashed,
+    Attribute::SlowBlink,
+    Attribute::RapidBlink,
+    Attribute::Reverse,
+    Attribute::Hidden,
+    Attribute::CrossedOut,
+    Attribute::Fraktur,
+    Attribute::Framed,
+    Attribute::Encircled,
+    Attribute::OverLined,
+];
+
+const UNDERLINES: [Attribute; 5] = [
+    Attribute::Underlined,
+    Attribute::DoubleUnderlined,
+    Attribute::Undercurled,
+    Attribute::Underdotted,
+    Attribute::Underdashed,
+];
+
+/// The SGR parameters turning off attributes and the attributes each of them turns off.
+const ATTRIBUTES_OFF: [(&str, &[Attribute]); 9] = [
+    ("22", &[Attribute::Bold, Attribute::Dim]),
+    ("23", &[Attribute::Italic, Attribute::Fraktur]),
+    ("24", &UNDERLINES),
+    ("25", &[Attribute::SlowBlink, Attribute::RapidBlink]),
+    ("27", &[Attribute::Reverse]),
+    ("28", &[Attribute::Hidden]),
+    ("29", &[Attribute::CrossedOut]),
+    ("54", &[Attribute::Framed, Attribute::Encircled]),
+    ("55", &[Attribute::OverLined]),
+];
+
+/// A writer that keeps track of the style and the cursor position of the terminal to leave out
+/// redundant escape sequences.
+///
+/// The commands queued to the writer are written to the inner writer with the following changes:
+///
+/// * The style set by [`SetForegroundColor`](struct.SetForegroundColor.html),
+///   [`SetAttribute`](struct.SetAttribute.html), [`ResetColor`](struct.ResetColor.html) and the
+///   other SGR sequences is only written before the next text, as the difference to the style of
+///   the terminal in a single sequence. Setting a color or an attribute that's already set writes
+///   nothing, and printing several [`PrintStyledContent`](struct.PrintStyledContent.html) with
+///   the same style sets it once.
+/// * Moving the cursor to its current position with [`MoveTo`](../cursor/struct.MoveTo.html),
+///   [`MoveToColumn`](../cursor/struct.MoveToColumn.html) or
+///   [`MoveToRow`](../cursor/struct.MoveToRow.html) writes nothing.
+///
+/// The style is written before flushing, so that the terminal always ends up with the queued
+/// style. The writer expects the terminal to start with the default style and an unknown cursor
+/// position. Call [`invalidate`](StyledWriter::invalidate) if something else writes to the
+/// terminal in between.
+///
+/// The cursor position is known after moving the cursor to an absolute position. It's kept
+/// across printed text if the size of the terminal is set with
+/// [`set_size`](StyledWriter::set_size), the text is measured per grapheme cluster with the
+/// [width mode](StyledWriter::set_width_mode).
+///
+/// SGR sequences with parameters the writer doesn't know (e.g. the fonts `10`-`19`) are
+/// written unchanged until the next reset.
+///
+/// # Examples
+///
+/// ```no_run
+/// use std::io::{self, Write};
+///
+/// use crossterm::{
+///     queue,
+///     style::{PrintStyledContent, SetForegroundColor, Color, StyledWriter, Stylize},
+/// };
+///
+/// fn main() -> io::Result<()> {
+///     let mut writer = StyledWriter::new(io::stdout());
+///
+///     // The red color is set once and reset once.
+///     queue!(
+///         writer,
+///         PrintStyledContent("Error".red()),
+///         PrintStyledContent(": ".red()),
+///         PrintStyledContent("file not found".red()),
+///     )?;
+///     writer.flush()
+/// }
+/// ```
+#[derive(Debug)]
+pub struct StyledWriter<W: Write> {
+    inner: W,
+    state: State,
+    /// The escape sequence being read.
+    sequence: Vec<u8>,
+    /// The incomplete UTF-8 character at the end of the last text.
+    utf8: Vec<u8>,
+    /// The last grapheme cluster of the last text, the next text may continue it.
+    last_grapheme: String,
+    /// The style of the queued commands.
+    style: ContentStyle,
+    /// The style of the terminal, `None` if it's not known.
+    written_style: Option<ContentStyle>,
+    /// Whether the terminal has attributes that aren't kept track of.
+    passthrough: bool,
+    cursor: Option<(u16, u16)>,
+    saved: Option<Saved>,
+    size: Option<(u16, u16)>,
+    width_mode: Option<WidthMode>,
+}
+
+/// The state saved with `ESC 7`.
+#[derive(Debug, Clone, Copy)]
+struct Saved {
+    cursor: Option<(u16, u16)>,
+    style: Option<ContentStyle>,
+    passthrough: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum State {
+    Ground,
+    Escape,
+    Intermediate,
+    Csi,
+    String,
+    StringEscape,
+}
+
+impl<W: Write> StyledWriter<W> {
+    /// Creates a writer writing to `inner`.
+    pub fn new(inner: W) -> StyledWriter<W> {
+        StyledWriter {
+            inner,
+            state: State::Ground,
+            sequence: Vec::new(),
+            utf8: Vec::new(),
+            last_grapheme: String::new(),
+            style: ContentStyle::default(),
+            written_style: Some(ContentStyle::default()),
+            passthrough: false,
+            cursor: None,
+            saved: None,
+            size: None,
+            width_mode: None,
+        }
+    }
+
+    /// Sets the size of the terminal, to keep track of the cursor position across printed text.
+    pub fn set_size(&mut self, columns: u16, rows: u16) {
+        self.size = Some((columns, rows));
+    }
+
+    /// Sets the width mode the printed text is measured with, instead of the global
+    /// [`width_mode`](fn.width_mode.html).
+    pub fn set_width_mode(&mut self, mode: WidthMode) {
+        self.width_mode = Some(mode);
+    }
+
+    /// Returns the style of the queued commands.
+    pub fn style(&self) -> ContentStyle {
+        self.style
+    }
+
+    /// Returns the cursor position (column, row) after the queued commands if it's known.
+    pub fn cursor_position(&self) -> Option<(u16, u16)> {
+        self.cursor
+    }
+
+    /// Forgets the style and the cursor position of the terminal, e.g. after something else
+    /// wrote to it.
+    ///
+    /// The next text resets the style first.
+    pub fn invalidate(&mut self) {
+        self.written_style = None;
+        self.passthrough = false;
+        self.cursor = None;
+        self.saved = None;
+    }
+
+    /// Returns a reference to the inner writer.
+    pub fn get_ref(&self) -> &W {
+        &self.inner
+    }
+
+    /// Returns a mutable reference to the inner writer.
+    ///
+    /// Writing to it directly should be followed by [`invalidate`](StyledWriter::invalidate).
+    pub fn get_mut(&mut self) -> &mut W {
+        &mut self.inner
+    }
+
+    /// Writes the queued style and an incomplete escape sequence and returns the inner writer.
+    pub fn into_inner(mut self) -> io::Result<W> {
+        self.write_style()?;
+        self.inner.write_all(&self.sequence)?;
+        Ok(self.inner)
+    }
+
+    /// Writes the difference between the style of the terminal and the queued style.
+    fn write_style(&mut self) -> io::Result<()> {
+        if self.passthrough || self.written_style == Some(self.style) {
+            return Ok(());
+        }
+        let params = sgr_difference(self.written_style.as_ref(), &self.style);
+        if !params.is_empty() {
+            write!(self.inner, csi!("{}m"), params)?;
+        }
+        self.written_style = Some(self.style);
+        Ok(())
+    }
+
+    fn write_text(&mut self, text: &[u8]) -> io::Result<()> {
+        self.write_style()?;
+        self.inner.write_all(text)?;
+
+        if self.cursor.is_none() {
+            self.utf8.clear();
+            self.last_grapheme.clear();
+            return Ok(());
+        }
+        let mut utf8 = mem::take(&mut self.utf8);
+        utf8.extend_from_slice(text);
+        let valid = match std::str::from_utf8(&utf8) {
+            Ok(valid) => valid,
+            Err(e) => {
+                let (valid, rest) = utf8.split_at(e.valid_up_to());
+                if e.error_len().is_some() {
+                    self.cursor = None;
+                } else {
+                    self.utf8 = rest.to_vec();
+                }
+                std::str::from_utf8(valid).unwrap()
+            }
+        };
+        let mode = self.width_mode.unwrap_or_else(width_mode);
+        let continued = self.last_grapheme.len();
+        let text = mem::take(&mut self.last_grapheme) + valid;
+        for (index, grapheme) in text.grapheme_indices(true) {
+            if index + grapheme.len() <= continued {
+                // Measured with the last text.
+                continue;
+            }
+            if index < continued {
+                // The text continues the last grapheme cluster, which changes its width in
+                // grapheme clustering mode, e.g. with an emoji presentation selector.
+                if mode == WidthMode::Graphemes {
+                    self.cursor = None;
+                }
+                self.advance_cursor(&grapheme[continued - index..], mode);
+            } else {
+                self.advance_cursor(grapheme, mode);
+            }
+        }
+        if let Some(grapheme) = text.graphemes(true).next_back() {
+            self.last_grapheme = grapheme.to_string();
+        }
+        Ok(())
+    }
+
+    fn advance_cursor(&mut self, grapheme: &str, mode: WidthMode) {
+        let (column, row) = match self.cursor {
+            Some(cursor) => cursor,
+            None => return,
+        };
+        self.cursor = match grapheme {
+            "\r" => Some((0, row)),
+            "\x08" => Some((column.saturating_sub(1), row)),
+            "\x07" => Some((column, row)),
+            // Line feeds depend on the output processing of the terminal.
+            grapheme if grapheme.starts_with(char::is_control) => None,
+            grapheme => match (mode.grapheme_width(grapheme) as u16, self.size) {
+                (0, _) => Some((column, row)),
+                // The cursor position after writing to the last column differs between
+                // terminals.
+                (width, Some((columns, _))) if column + width < columns => {
+                    Some((column + width, row))
+                }
+                _ => None,
+            },
+        };
+    }
+
+    fn write_sequence(&mut self, sequence: &[u8]) -> io::Result<()> {
+        self.utf8.clear();
+        match sequence.get(1) {
+            Some(b'[') => self.write_csi(sequence),
+            Some(b'7') => {
+                self.write_style()?;
+                self.saved = Some(Saved {
+                    cursor: self.cursor,
+                    style: self.written_style,
+                    passthrough: self.passthrough,
+                });
+                self.inner.write_all(sequence)
+            }
+            Some(b'8') => {
+                let saved = self.saved.unwrap_or(Saved {
+                    cursor: None,
+                    style: None,
+                    passthrough: false,
+                });
+                self.cursor = saved.cursor;
+                self.written_style = saved.style;
+                self.style = saved.style.unwrap_or_default();
+                self.passthrough = saved.passthrough;
+                self.inner.write_all(sequence)
+            }
+            Some(b'c') => {
+                self.style = ContentStyle::default();
+                self.written_style = Some(ContentStyle::default());
+                self.passthrough = false;
+                self.cursor = None;
+                self.inner.write_all(sequence)
+            }
+            // Operating system commands and other strings, keypad modes.
+            Some(b']' | b'P' | b'X' | b'^' | b'_' | b'=' | b'>') => self.inner.write_all(sequence),
+            _ => {
+                self.write_style()?;
+                self.cursor = None;
+                self.inner.write_all(sequence)
+            }
+        }
+    }
+
+    fn write_csi(&mut self, sequence: &[u8]) -> io::Result<()> {
+        let (&last, body) = sequence[2..].split_last().unwrap();
+        let (private, body) = match body.first() {
+            Some(b'?' | b'>' | b'<' | b'=') => (Some(body[0]), &body[1..]),
+            _ => (None, body),
+        };
+        let params = match std::str::from_utf8(body) {
+            Ok(params)
+                if params
+                    .bytes()
+                    .all(|b| b.is_ascii_digit() || b == b';' || b == b':') =>
+            {
+                params
+            }
+            // Intermediate bytes.
+            _ => {
+                self.write_style()?;
+                self.cursor = None;
+                return self.inner.write_all(sequence);
+            }
+        };
+
+        match (private, last) {
+            (None, b'm') => self.write_sgr(params, sequence),
+            (None, b'H' | b'f' | b'G' | b'd') => {
+                let mut values = params.split(';').map(parse_param);
+                let first = values.next().unwrap();
+                let target = match last {
+                    b'G' => self.cursor.map(|(_, row)| (first - 1, row)),
+                    b'd' => self.cursor.map(|(column, _)| (column, first - 1)),
+                    _ => Some((values.next().unwrap_or(1) - 1, first - 1)),
+                };
+                let target = target.map(|cursor| self.clamp(cursor));
+                if target.is_some() && target == self.cursor {
+                    return Ok(());
+                }
+                self.cursor = target;
+                self.inner.write_all(sequence)
+            }
+            (None, b'A' | b'B' | b'C' | b'D' | b'E' | b'F') => {
+                let n = parse_param(params.split(';').next().unwrap());
+                self.cursor = self.cursor.map(|(column, row)| {
+                    self.clamp(match last {
+                        b'A' => (column, row.saturating_sub(n)),
+                        b'B' => (column, row.saturating_add(n)),
+                        b'C' => (column.saturating_add(n), row),
+                        b'D' => (column.saturating_sub(n), row),
+                        b'E' => (0, row.saturating_add(n)),
+                        _ => (0, row.saturating_sub(n)),
+                    })
+                });
+                self.inner.write_all(sequence)
+            }
+            // Erasing and scrolling fill with the background color.
+            (None, b'J' | b'K' | b'X' | b'@' | b'P' | b'S' | b'T') => {
+                self.write_style()?;
+                self.inner.write_all(sequence)
+            }
+            (Some(b'?'), b'h' | b'l') => {
+                // The alternate screen, origin and column modes move the cursor.
+                if params
+                    .split(';')
+                    .any(|mode| matches!(mode, "3" | "6" | "47" | "69" | "1047" | "1048" | "1049"))
+                {
+                    self.write_style()?;
+                    self.cursor = None;
+                }
+                self.inner.write_all(sequence)
+            }
+            _ => {
+                self.write_style()?;
+                self.cursor = None;
+                self.inner.write_all(sequence)
+            }
+        }
+    }
+
+    fn write_sgr(&mut self, params: &str, sequence: &[u8]) -> io::Result<()> {
+        let mut style = self.style;
+        let modeled = apply_sgr(&mut style, params, !self.passthrough);
+        if !self.passthrough && modeled {
+            self.style = style;
+            return Ok(());
+        }
+
+        self.write_style()?;
+        self.inner.write_all(sequence)?;
+        self.style = style;
+        self.written_style = Some(style);
+        self.passthrough = !modeled;
+        Ok(())
+    }
+
+    fn clamp(&self, (column, row): (u16, u16)) -> (u16, u16) {
+        match self.size {
+            Some((columns, rows)) => (
+                column.min(columns.saturating_sub(1)),
+                row.min(rows.saturating_sub(1)),
+            ),
+            None => (column, row),
+        }
+    }
+}
+
+impl<W: Write> Write for StyledWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let mut text_start = None;
+        for (index, &byte) in buf.iter().enumerate() {
+            if self.state == State::Ground {
+                if byte == b'\x1B' {
+                    if let Some(start) = text_start.take() {
+                        self.write_text(&buf[start..index])?;
+                    }
+                    self.state = State::Escape;
+                    self.sequence.push(byte);
+                } else if text_start.is_none() {
+                    text_start = Some(index);
+                }
+                continue;
+            }
+
+            self.sequence.push(byte);
+            self.state = match (self.state, byte) {
+                (State::Escape, b'[') => State::Csi,
+                (State::Escape, b']' | b'P' | b'X' | b'^' | b'_') => State::String,
+                (State::Escape | State::Intermediate, 0x20..=0x2F) => State::Intermediate,
+                (State::Csi, 0x40..=0x7E) => State::Ground,
+                (State::Csi, _) => State::Csi,
+                (State::String, b'\x07') => State::Ground,
+                (State::String, b'\x1B') => State::StringEscape,
+                (State::String, _) => State::String,
+                _ => State::Ground,
+            };
+            if self.state == State::Ground {
+                let mut sequence = mem::take(&mut self.sequence);
+                let result = self.write_sequence(&sequence);
+                sequence.clear();
+                self.sequence = sequence;
+                result?;
+            }
+        }
+        if let Some(start) = text_start {
+            self.write_text(&buf[start..])?;
+        }
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.write_style()?;
+        self.inner.flush()
+    }
+}
+
+/// Parses a parameter of a cursor movement, where a missing or `0` parameter means `1`.
+fn parse_param(param: &str) -> u16 {
+    param.parse().unwrap_or(1).max(1)
+}
+
+/// Applies the SGR parameters to the style, returning whether the resulting style is known
+/// completely.
+fn apply_sgr(style: &mut ContentStyle, params: &str, mut modeled: bool) -> bool {
+    let mut params = params.split(';');
+    while let Some(param) = params.next() {
+        match param {
+            "" | "0" => {
+                *style = ContentStyle::default();
+                modeled = true;
+            }
+            "38" | "48" | "58" => {
+                let color = match parse_color(&mut params) {
+                    Some(color) => Some(color),
+                    // The number of parameters of the color isn't known.
+                    None => return false,
+                };
+                match param {
+                    "38" => style.foreground_color = color,
+                    "48" => style.background_color = color,
+                    _ => style.underline_color = color,
+                }
+            }
+            "39" => style.foreground_color = None,
+            "49" => style.background_color = None,
+            "59" => style.underline_color = None,
+            "4:0" => remove_attributes(style, &UNDERLINES),
+            _ => {
+                if let Some(colored) = parse_named_color(param) {
+                    match colored {
+                        Colored::ForegroundColor(color) => style.foreground_color = Some(color),
+                        Colored::BackgroundColor(color) => style.background_color = Some(color),
+                        Colored::UnderlineColor(color) => style.underline_color = Some(color),
+                    }
+                } else if let Some(attribute) = ATTRIBUTES.iter().find(|attribute| {
+                    attribute.sgr() == param
+                        || (param == "4:1" && **attribute == Attribute::Underlined)
+                }) {
+                    if UNDERLINES.contains(attribute) {
+                        remove_attributes(style, &UNDERLINES);
+                    }
+                    style.attributes.set(*attribute);
+                } else if let Some((_, attributes)) =
+                    ATTRIBUTES_OFF.iter().find(|(off, _)| *off == param)
+                {
+                    remove_attributes(style, attributes);
+                } else {
+                    modeled = false;
+                }
+            }
+        }
+    }
+    modeled
+}
+
+/// Parses the parameters of the 16 named colors, `30`-`37` and `90`-`97` for the foreground and
+/// `40`-`47` and `100`-`107` for the background.
+fn parse_named_color(param: &str) -> Option<Colored> {
+    let (colored, value): (fn(Color) -> Colored, u8) = match param.parse::<u8>().ok()? {
+        value @ 30..=37 => (Colored::ForegroundColor, value - 30),
+        value @ 90..=97 => (Colored::ForegroundColor, value - 90 + 8),
+        value @ 40..=47 => (Colored::BackgroundColor, value - 40),
+        value @ 100..=107 => (Colored::BackgroundColor, value - 100 + 8),
+        _ => return None,
+    };
+    Color::parse_ansi(&format!("5;{}", value)).map(colored)
+}
+
+fn parse_color<'a>(params: &mut impl Iterator<Item = &'a str>) -> Option<Color> {
+    let mut next = || params.next()?.parse::<u8>().ok();
+    match next()? {
+        5 => Color::parse_ansi(&format!("5;{}", next()?)),
+        2 => Some(Color::Rgb {
+            r: next()?,
+            g: next()?,
+            b: next()?,
+        }),
+        _ => None,
+    }
+}
+
+fn remove_attributes(style: &mut ContentStyle, attributes: &[Attribute]) {
+    for attribute in attributes {
+        style.attributes.unset(*attribute);
+    }
+}
+
+/// Returns the SGR parameters changing the style `from` (unknown if `None`) to `to`.
+fn sgr_difference(from: Option<&ContentStyle>, to: &ContentStyle) -> String {
+    let mut reset = vec!["0".to_string()];
+    push_sgr_difference(&mut reset, &ContentStyle::default(), to);
+    let reset = reset.join(";");
+
+    let from = match from {
+        Some(from) => from,
+        None => return reset,
+    };
+    let mut params = Vec::new();
+    push_sgr_difference(&mut params, from, to);
+    let params = params.join(";");
+
+    if params.len() <= reset.len() {
+        params
+    } else {
+        reset
+    }
+}
+
+fn push_sgr_difference(params: &mut Vec<String>, from: &ContentStyle, to: &ContentStyle) {
+    let mut attributes = from.attributes;
+    for (off, group) in ATTRIBUTES_OFF {
+        let removed = group
+            .iter()
+            .any(|attribute| from.attributes.has(*attribute) && !to.attributes.has(*attribute));
+        // Setting another underline replaces the current one.
+        let replaced = group == &UNDERLINES[..]
+            && UNDERLINES
+                .iter()
+                .any(|attribute| to.attributes.has(*attribute));
+        if removed && !replaced {
+            params.push(off.to_string());
+            for attribute in group {
+                attributes.unset(*attribute);
+            }
+        }
+    }
+
+    let colors = [
+        (
+            from.foreground_color,
+            to.foreground_color,
+            Colored::ForegroundColor as fn(Color) -> Colored,
+        ),
+        (
+            from.background_color,
+            to.background_color,
+            Colored::BackgroundColor,
+        ),
+        (
+            from.underline_color,
+            to.underline_color,
+            Colored::UnderlineColor,
+        ),
+    ];
+    for (old, new, colored) in colors {
+        if old != new {
+            let param = colored(new.unwrap_or(Color::Reset)).to_string();
+            if !param.is_empty() {
+                params.push(param);
+            }
+        }
+    }
+
+    for attribute in ATTRIBUTES {
+        if to.attributes.has(attribute) && !attributes.has(attribute) {
+            params.push(attribute.sgr());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Write;
+
+    use crate::{
+        cursor::{MoveTo, MoveToColumn},
+        style::{
+            with_color_mode, Attribute, Color, ColorMode, Print, PrintStyledContent, SetAttribute,
+            SetBackgroundColor, SetForegroundColor, Stylize, WidthMode,
+        },
+        terminal::{Clear, ClearType},
+        QueueableCommand,
+    };
+
+    use super::StyledWriter;
+
+    fn output(writer: StyledWriter<Vec<u8>>) -> String {
+        String::from_utf8(writer.into_inner().unwrap()).unwrap()
+    }
+
+    #[test]
+    fn test_elides_redundant_sgr_sequences() {
+        with_color_mode(ColorMode::TrueColor, || {
+            let mut writer = StyledWriter::new(Vec::new());
+            writer
+                .queue(SetForegroundColor(Color::Red))
+                .unwrap()
+                .queue(SetForegroundColor(Color::Red))
+                .unwrap()
+                .queue(SetAttribute(Attribute::Bold))
+                .unwrap()
+                .queue(Print("a"))
+                .unwrap()
+                .queue(SetAttribute(Attribute::Bold))
+                .unwrap()
+                .queue(Print("b"))
+                .unwrap();
+
+            assert_eq!(output(writer), "\x1b[38;5;9;1mab");
+        });
+    }
+
+    #[test]
+    fn test_merges_styled_content() {
+        with_color_mode(ColorMode::TrueColor, || {
+            let mut writer = StyledWriter::new(Vec::new());
+            for content in ["a", "b", "c"] {
+                writer
+                    .queue(PrintStyledContent(content.red().on_blue()))
+                    .unwrap();
+            }
+            writer.queue(PrintStyledContent("d".blue())).unwrap();
+            writer.flush().unwrap();
+
+            assert_eq!(
+                output(writer),
+                "\x1b[38;5;9;48;5;12mabc\x1b[0;38;5;12md\x1b[0m"
+            );
+        });
+    }
+
+    #[test]
+    fn test_turns_off_removed_attributes() {
+        let mut writer = StyledWriter::new(Vec::new());
+        write!(writer, "\x1b[1m\x1b[3m\x1b[4ma\x1b[23mb\x1b[4:3mc\x1b[22md").unwrap();
+
+        assert_eq!(output(writer), "\x1b[1;3;4ma\x1b[23mb\x1b[4:3mc\x1b[22md");
+    }
+
+    #[test]
+    fn test_writes_unknown_sgr_sequences_until_reset() {
+        let mut writer = StyledWriter::new(Vec::new());
+        write!(writer, "\x1b[11ma\x1b[1m\x1b[1mb\x1b[0m\x1b[1m\x1b[1mc").unwrap();
+
+        assert_eq!(output(writer), "\x1b[11ma\x1b[1m\x1b[1mb\x1b[0m\x1b[1mc");
+    }
+
+    #[test]
+    fn test_models_named_color_parameters() {
+        with_color_mode(ColorMode::Ansi16, || {
+            let mut writer = StyledWriter::new(Vec::new());
+            write!(writer, "\x1b[31;102ma\x1b[31mb").unwrap();
+            writer
+                .queue(SetForegroundColor(Color::DarkRed))
+                .unwrap()
+                .queue(SetBackgroundColor(Color::Green))
+                .unwrap()
+                .queue(Print("c"))
+                .unwrap()
+                .queue(SetForegroundColor(Color::Blue))
+                .unwrap()
+                .queue(Print("d"))
+                .unwrap();
+
+            assert_eq!(output(writer), "\x1b[31;102mabc\x1b[94md");
+        });
+    }
+
+    #[test]
+    fn test_writes_style_before_erasing() {
+        with_color_mode(ColorMode::TrueColor, || {
+            let mut writer = StyledWriter::new(Vec::new());
+            writer
+                .queue(SetBackgroundColor(Color::Blue))
+                .unwrap()
+                .queue(Clear(ClearType::All))
+                .unwrap();
+
+            assert_eq!(output(writer), "\x1b[48;5;12m\x1b[2J");
+        });
+    }
+
+    #[test]
+    fn test_elides_moves_to_the_cursor_position() {
+        let mut writer = StyledWriter::new(Vec::new());
+        writer.set_size(10, 5);
+        writer
+            .queue(MoveTo(2, 3))
+            .unwrap()
+            .queue(Print("a中"))
+            .unwrap()
+            .queue(MoveTo(5, 3))
+            .unwrap()
+            .queue(MoveToColumn(6))
+            .unwrap();
+        assert_eq!(writer.cursor_position(), Some((6, 3)));
+
+        writer.queue(Print("end\n")).unwrap();
+        assert_eq!(writer.cursor_position(), None);
+
+        assert_eq!(output(writer), "\x1b[4;3Ha中\x1b[7Gend\n");
+    }
+
+    #[test]
+    fn test_measures_grapheme_clusters_with_the_width_mode() {
+        let family = "👨\u{200D}👩\u{200D}👧";
+        for (mode, column) in [(WidthMode::Characters, 7), (WidthMode::Graphemes, 3)] {
+            let mut writer = StyledWriter::new(Vec::new());
+            writer.set_size(10, 5);
+            writer.set_width_mode(mode);
+            writer
+                .queue(MoveTo(0, 0))
+                .unwrap()
+                .queue(Print(format!("a{}", family)))
+                .unwrap();
+            assert_eq!(writer.cursor_position(), Some((column, 0)));
+        }
+    }
+
+    #[test]
+    fn test_grapheme_cluster_continued_by_the_next_text() {
+        let mut writer = StyledWriter::new(Vec::new());
+        writer.set_size(10, 5);
+        writer.set_width_mode(WidthMode::Characters);
+        write!(writer, "\x1b[1;1H❤").unwrap();
+        write!(writer, "\u{FE0F}").unwrap();
+        assert_eq!(writer.cursor_position(), Some((1, 0)));
+
+        // The presentation selector makes the heart wide.
+        writer.set_width_mode(WidthMode::Graphemes);
+        write!(writer, "\x1b[1;1H❤").unwrap();
+        assert_eq!(writer.cursor_position(), Some((1, 0)));
+        write!(writer, "\u{FE0F}").unwrap();
+        assert_eq!(writer.cursor_position(), None);
+    }
+
+    #[test]
+    fn test_handles_sequences_split_across_writes() {
+        let mut writer = StyledWriter::new(Vec::new());
+        writer.set_size(10, 5);
+        for byte in "\x1b[1;1H\x1b[1ma\x1b[1m\x1b]0;title\x07".bytes() {
+            writer.write_all(&[byte]).unwrap();
+        }
+
+        assert_eq!(writer.cursor_position(), Some((1, 0)));
+        assert_eq!(output(writer), "\x1b[1;1H\x1b[1ma\x1b]0;title\x07");
+    }
+}
@@ -1,6 +1,15 @@
-pub use self::{attribute::Attribute, color::Color, colored::Colored, colors::Colors};
+pub use self::{
+    attribute::Attribute,
+    color::Color,
+    color_mode::ColorMode,
+    color_model::{Hsl, Oklch},
+    colored::Colored,
+    colors::Colors,
+};
 
 mod attribute;
 mod color;
+mod color_mode;
+mod color_model;
 mod colored;
 mod colors;
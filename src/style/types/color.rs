@@ -8,6 +8,8 @@ use std::fmt;
 
 use crate::style::parse_next_u8;
 
+use super::color_model::parse_css;
+
 /// Represents a color.
 ///
 /// # Platform-specific Notes
@@ -169,6 +171,10 @@ impl TryFrom<&str> for Color {
     type Error = ();
 
     /// Try to create a `Color` from the string representation. This returns an error if the string does not match.
+    ///
+    /// Besides the names of the variants, the CSS notations `#rgb`, `#rrggbb`, `rgb(r, g, b)`,
+    /// `hsl(h, s%, l%)` and the CSS named colors are parsed into a `Color::Rgb`. The names of
+    /// the variants take precedence, e.g. `red` is `Color::Red` and not the CSS `red`.
     fn try_from(src: &str) -> Result<Self, Self::Error> {
         let src = src.to_lowercase();
 
@@ -190,7 +196,7 @@ impl TryFrom<&str> for Color {
             "dark_cyan" => Ok(Color::DarkCyan),
             "white" => Ok(Color::White),
             "grey" => Ok(Color::Grey),
-            _ => Err(()),
+            _ => parse_css(&src).ok_or(()),
         }
     }
 }
@@ -269,7 +275,7 @@ impl<'de> serde::de::Deserialize<'de> for Color {
             type Value = Color;
             fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
                 formatter.write_str(
-                    "`reset`, `black`, `blue`, `dark_blue`, `cyan`, `dark_cyan`, `green`, `dark_green`, `grey`, `dark_grey`, `magenta`, `dark_magenta`, `red`, `dark_red`, `white`, `yellow`, `dark_yellow`, `ansi_(value)`, or `rgb_(r,g,b)` or `#rgbhex`",
+                    "`reset`, `black`, `blue`, `dark_blue`, `cyan`, `dark_cyan`, `green`, `dark_green`, `grey`, `dark_grey`, `magenta`, `dark_magenta`, `red`, `dark_red`, `white`, `yellow`, `dark_yellow`, `ansi_(value)`, `rgb_(r,g,b)`, `#rgbhex`, `rgb(r, g, b)`, `hsl(h, s%, l%)` or a CSS color name",
                 )
             }
             fn visit_str<E>(self, value: &str) -> Result<Color, E>
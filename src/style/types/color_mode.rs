@@ -0,0 +1,432 @@
+use std::cell::Cell;
+use std::env;
+use std::sync::atomic::{AtomicU8, Ordering};
+
+use parking_lot::Once;
+
+use crate::style::Color;
+
+/// The colors the terminal can show, used to downgrade the colors crossterm writes.
+///
+/// [`Colored`](enum.Colored.html) writes the colors of the current mode, see
+/// [`color_mode`](fn.color_mode.html) and [`set_color_mode`](fn.set_color_mode.html).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ColorMode {
+    /// All colors, including [`Color::Rgb`](enum.Color.html#variant.Rgb).
+    TrueColor,
+    /// The 256 colors of [`Color::AnsiValue`](enum.Color.html#variant.AnsiValue).
+    Ansi256,
+    /// The 16 named colors, written with the SGR parameters `30`-`37` and `90`-`97` (`40`-`47`
+    /// and `100`-`107` for the background).
+    Ansi16,
+    /// No colors, they're all reset.
+    NoColor,
+}
+
+/// The 16 named colors in the order of their ANSI values.
+const NAMED: [Color; 16] = [
+    Color::Black,
+    Color::DarkRed,
+    Color::DarkGreen,
+    Color::DarkYellow,
+    Color::DarkBlue,
+    Color::DarkMagenta,
+    Color::DarkCyan,
+    Color::Grey,
+    Color::DarkGrey,
+    Color::Red,
+    Color::Green,
+    Color::Yellow,
+    Color::Blue,
+    Color::Magenta,
+    Color::Cyan,
+    Color::White,
+];
+
+/// The default xterm palette of the 16 named colors. Terminals use different ones, but they're
+/// close enough to pick the nearest color.
+const NAMED_RGB: [(u8, u8, u8); 16] = [
+    (0, 0, 0),
+    (205, 0, 0),
+    (0, 205, 0),
+    (205, 205, 0),
+    (0, 0, 238),
+    (205, 0, 205),
+    (0, 205, 205),
+    (229, 229, 229),
+    (127, 127, 127),
+    (255, 0, 0),
+    (0, 255, 0),
+    (255, 255, 0),
+    (92, 92, 255),
+    (255, 0, 255),
+    (0, 255, 255),
+    (255, 255, 255),
+];
+
+/// The levels of the channels of the 6x6x6 color cube (ANSI values 16 to 231).
+const CUBE_LEVELS: [u8; 6] = [0, 95, 135, 175, 215, 255];
+
+const UNDETECTED: u8 = u8::MAX;
+
+static COLOR_MODE: AtomicU8 = AtomicU8::new(UNDETECTED);
+static INITIALIZER: Once = Once::new();
+
+thread_local! {
+    // The color mode set with `with_color_mode`, used instead of the global one.
+    static SCOPED_COLOR_MODE: Cell<Option<ColorMode>> = const { Cell::new(None) };
+}
+
+/// Looks up an environment variable, `None` if it isn't set.
+pub(crate) type Env<'a> = &'a dyn Fn(&str) -> Option<String>;
+
+pub(crate) fn process_env(name: &str) -> Option<String> {
+    env::var(name).ok()
+}
+
+impl ColorMode {
+    /// Returns the color that is shown instead of `color` in this mode.
+    ///
+    /// [`Color::Rgb`](enum.Color.html#variant.Rgb) is mapped to the nearest color of the color
+    /// cube or the grayscale ramp of the 256 colors, and those are mapped to the nearest named
+    /// color. The distance of the colors is weighted like the human eye perceives them.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use crossterm::style::{Color, ColorMode};
+    ///
+    /// let orange = Color::Rgb { r: 255, g: 135, b: 0 };
+    /// assert_eq!(ColorMode::TrueColor.downgrade(orange), orange);
+    /// assert_eq!(ColorMode::Ansi256.downgrade(orange), Color::AnsiValue(208));
+    /// assert_eq!(ColorMode::Ansi16.downgrade(orange), Color::DarkYellow);
+    /// assert_eq!(ColorMode::NoColor.downgrade(orange), Color::Reset);
+    /// ```
+    pub fn downgrade(self, color: Color) -> Color {
+        match (self, color) {
+            (_, Color::Reset) | (ColorMode::TrueColor, _) => color,
+            (ColorMode::NoColor, _) => Color::Reset,
+            (ColorMode::Ansi256, Color::Rgb { r, g, b }) => {
+                Color::AnsiValue(nearest_ansi256(r, g, b))
+            }
+            (ColorMode::Ansi16, Color::Rgb { r, g, b }) => nearest_named(r, g, b),
+            (ColorMode::Ansi16, Color::AnsiValue(value)) => match NAMED.get(usize::from(value)) {
+                Some(named) => *named,
+                None => {
+                    let (r, g, b) = ansi256_rgb(value);
+                    nearest_named(r, g, b)
+                }
+            },
+            _ => color,
+        }
+    }
+
+    /// Detects the color mode of the terminal from the environment variables.
+    ///
+    /// `COLORTERM=truecolor` or `COLORTERM=24bit` select all colors, a `TERM` containing `256` and
+    /// the macOS Terminal select the 256 colors, and `TERM=linux` or a `TERM` containing `16color`
+    /// the 16 named colors. Other terminals are assumed to support all colors. `FORCE_COLOR=1`,
+    /// `2` and `3` select at least the 16, 256 or all colors.
+    fn detect(env: Env) -> ColorMode {
+        let detected = ColorMode::detect_terminal(env);
+        let forced = match env("FORCE_COLOR").as_deref() {
+            Some("1") | Some("true") | Some("") => ColorMode::Ansi16,
+            Some("2") => ColorMode::Ansi256,
+            Some("3") => ColorMode::TrueColor,
+            _ => return detected,
+        };
+        // The richer mode is the one with the lower value.
+        ColorMode::from_u8((detected as u8).min(forced as u8))
+    }
+
+    fn detect_terminal(env: Env) -> ColorMode {
+        let colorterm = env("COLORTERM").unwrap_or_default();
+        if colorterm.contains("truecolor") || colorterm.contains("24bit") {
+            return ColorMode::TrueColor;
+        }
+        if env("TERM_PROGRAM").is_some_and(|program| program == "Apple_Terminal") {
+            return ColorMode::Ansi256;
+        }
+        match env("TERM") {
+            Some(term) if term == "linux" || term.contains("16color") => ColorMode::Ansi16,
+            Some(term) if term.contains("256") => ColorMode::Ansi256,
+            _ => ColorMode::TrueColor,
+        }
+    }
+
+    fn detect_process() -> ColorMode {
+        #[cfg(windows)]
+        {
+            // Check if we're running in a pseudo TTY, which supports true color.
+            if crate::ansi_support::supports_ansi() {
+                return ColorMode::TrueColor;
+            }
+        }
+
+        ColorMode::detect(&process_env)
+    }
+
+    /// Returns the color mode, detecting it on the first call.
+    pub(crate) fn current() -> ColorMode {
+        INITIALIZER.call_once(|| {
+            // Don't overwrite a mode set before the first call.
+            let _ = COLOR_MODE.compare_exchange(
+                UNDETECTED,
+                ColorMode::detect_process() as u8,
+                Ordering::SeqCst,
+                Ordering::SeqCst,
+            );
+        });
+        ColorMode::from_u8(COLOR_MODE.load(Ordering::SeqCst))
+    }
+
+    pub(crate) fn set_current(mode: ColorMode) {
+        COLOR_MODE.store(mode as u8, Ordering::SeqCst);
+    }
+
+    /// Returns the color mode set with `with_color_mode` on this thread.
+    pub(crate) fn scoped() -> Option<ColorMode> {
+        SCOPED_COLOR_MODE.with(Cell::get)
+    }
+
+    pub(crate) fn with_scoped<R>(mode: ColorMode, f: impl FnOnce() -> R) -> R {
+        // Restores the previous mode, even if `f` panics.
+        struct Restore(Option<ColorMode>);
+
+        impl Drop for Restore {
+            fn drop(&mut self) {
+                SCOPED_COLOR_MODE.with(|scoped| scoped.set(self.0));
+            }
+        }
+
+        let _restore = Restore(SCOPED_COLOR_MODE.with(|scoped| scoped.replace(Some(mode))));
+        f()
+    }
+
+    fn from_u8(value: u8) -> ColorMode {
+        match value {
+            0 => ColorMode::TrueColor,
+            1 => ColorMode::Ansi256,
+            2 => ColorMode::Ansi16,
+            _ => ColorMode::NoColor,
+        }
+    }
+}
+
+/// Returns whether the environment forces colored output with a `CLICOLOR_FORCE` other than `0`
+/// or a `FORCE_COLOR` other than `0` and `false`.
+pub(crate) fn forced_by_env(env: Env) -> bool {
+    env("CLICOLOR_FORCE").is_some_and(|value| !value.is_empty() && value != "0")
+        || env("FORCE_COLOR").is_some_and(|value| value != "0" && value != "false")
+}
+
+/// Returns whether the environment disables colored output with `NO_COLOR`, `CLICOLOR=0`,
+/// `FORCE_COLOR=0` or `TERM=dumb`, unless it's forced.
+pub(crate) fn disabled_by_env(env: Env) -> bool {
+    if forced_by_env(env) {
+        return false;
+    }
+    env("NO_COLOR").is_some_and(|value| !value.is_empty())
+        || env("CLICOLOR").is_some_and(|value| value == "0")
+        || env("FORCE_COLOR").is_some_and(|value| value == "0" || value == "false")
+        || env("TERM").is_some_and(|value| value == "dumb")
+}
+
+/// Returns the ANSI value of a named color.
+pub(crate) fn named_ansi_value(color: Color) -> Option<u8> {
+    NAMED
+        .iter()
+        .position(|named| *named == color)
+        .map(|value| value as u8)
+}
+
+/// Returns the RGB value of one of the 256 colors.
+pub(crate) fn ansi256_rgb(value: u8) -> (u8, u8, u8) {
+    match value {
+        0..=15 => NAMED_RGB[usize::from(value)],
+        16..=231 => {
+            let index = value - 16;
+            (
+                CUBE_LEVELS[usize::from(index / 36)],
+                CUBE_LEVELS[usize::from(index / 6 % 6)],
+                CUBE_LEVELS[usize::from(index % 6)],
+            )
+        }
+        _ => {
+            let level = 8 + 10 * (value - 232);
+            (level, level, level)
+        }
+    }
+}
+
+/// Returns the nearest color of the color cube or the grayscale ramp (ANSI values 16 to 255).
+fn nearest_ansi256(r: u8, g: u8, b: u8) -> u8 {
+    let cube_index = |channel: u8| {
+        (0..CUBE_LEVELS.len())
+            .min_by_key(|&index| (i32::from(CUBE_LEVELS[index]) - i32::from(channel)).abs())
+            .unwrap() as u8
+    };
+    let cube = 16 + 36 * cube_index(r) + 6 * cube_index(g) + cube_index(b);
+
+    // The gray levels are 8, 18, ..., 238.
+    let average = (u32::from(r) + u32::from(g) + u32::from(b)) / 3;
+    let gray = 232 + (average.saturating_sub(3) / 10).min(23) as u8;
+
+    if distance((r, g, b), ansi256_rgb(gray)) < distance((r, g, b), ansi256_rgb(cube)) {
+        gray
+    } else {
+        cube
+    }
+}
+
+/// Returns the nearest named color.
+fn nearest_named(r: u8, g: u8, b: u8) -> Color {
+    let index = (0..NAMED_RGB.len())
+        .min_by_key(|&index| distance((r, g, b), NAMED_RGB[index]))
+        .unwrap();
+    NAMED[index]
+}
+
+/// Returns the squared "redmean" distance of two colors, a weighted euclidean distance that is
+/// close to how the human eye perceives their difference.
+fn distance((r1, g1, b1): (u8, u8, u8), (r2, g2, b2): (u8, u8, u8)) -> u32 {
+    let mean = (i32::from(r1) + i32::from(r2)) / 2;
+    let r = i32::from(r1) - i32::from(r2);
+    let g = i32::from(g1) - i32::from(g2);
+    let b = i32::from(b1) - i32::from(b2);
+    let weighted = (((512 + mean) * r * r) >> 8) + 4 * g * g + (((767 - mean) * b * b) >> 8);
+    weighted as u32
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::style::Color;
+
+    use std::collections::HashMap;
+
+    use super::{ansi256_rgb, disabled_by_env, forced_by_env, ColorMode};
+
+    fn env<'a>(vars: &'a [(&str, &str)]) -> impl Fn(&str) -> Option<String> + 'a {
+        let vars: HashMap<_, _> = vars.iter().copied().collect();
+        move |name| vars.get(name).map(|value| value.to_string())
+    }
+
+    #[test]
+    fn test_downgrade_to_ansi256() {
+        let mode = ColorMode::Ansi256;
+        assert_eq!(
+            mode.downgrade(Color::Rgb { r: 0, g: 0, b: 0 }),
+            Color::AnsiValue(16)
+        );
+        assert_eq!(
+            mode.downgrade(Color::Rgb {
+                r: 255,
+                g: 255,
+                b: 255
+            }),
+            Color::AnsiValue(231)
+        );
+        assert_eq!(
+            mode.downgrade(Color::Rgb {
+                r: 100,
+                g: 100,
+                b: 100
+            }),
+            Color::AnsiValue(241)
+        );
+        assert_eq!(
+            mode.downgrade(Color::Rgb {
+                r: 0,
+                g: 95,
+                b: 215
+            }),
+            Color::AnsiValue(26)
+        );
+        assert_eq!(mode.downgrade(Color::Red), Color::Red);
+        assert_eq!(mode.downgrade(Color::AnsiValue(100)), Color::AnsiValue(100));
+
+        // Every color of the cube and the ramp is mapped to itself.
+        for value in 16..=255 {
+            let (r, g, b) = ansi256_rgb(value);
+            assert_eq!(
+                mode.downgrade(Color::Rgb { r, g, b }),
+                Color::AnsiValue(value)
+            );
+        }
+    }
+
+    #[test]
+    fn test_downgrade_to_ansi16() {
+        let mode = ColorMode::Ansi16;
+        assert_eq!(
+            mode.downgrade(Color::Rgb {
+                r: 250,
+                g: 10,
+                b: 10
+            }),
+            Color::Red
+        );
+        assert_eq!(
+            mode.downgrade(Color::Rgb {
+                r: 30,
+                g: 30,
+                b: 30
+            }),
+            Color::Black
+        );
+        assert_eq!(
+            mode.downgrade(Color::Rgb {
+                r: 140,
+                g: 140,
+                b: 140
+            }),
+            Color::DarkGrey
+        );
+        assert_eq!(mode.downgrade(Color::AnsiValue(9)), Color::Red);
+        assert_eq!(mode.downgrade(Color::AnsiValue(21)), Color::DarkBlue);
+        assert_eq!(mode.downgrade(Color::AnsiValue(255)), Color::Grey);
+        assert_eq!(mode.downgrade(Color::DarkCyan), Color::DarkCyan);
+        assert_eq!(mode.downgrade(Color::Reset), Color::Reset);
+    }
+
+    #[test]
+    fn test_env_disables_and_forces_colors() {
+        assert!(!disabled_by_env(&env(&[])));
+        assert!(!disabled_by_env(&env(&[("NO_COLOR", "")])));
+        assert!(disabled_by_env(&env(&[("NO_COLOR", "1")])));
+        assert!(disabled_by_env(&env(&[("CLICOLOR", "0")])));
+        assert!(!disabled_by_env(&env(&[("CLICOLOR", "1")])));
+        assert!(disabled_by_env(&env(&[("FORCE_COLOR", "0")])));
+        assert!(disabled_by_env(&env(&[("TERM", "dumb")])));
+
+        assert!(!forced_by_env(&env(&[("CLICOLOR_FORCE", "0")])));
+        assert!(forced_by_env(&env(&[("CLICOLOR_FORCE", "1")])));
+        assert!(!disabled_by_env(&env(&[
+            ("CLICOLOR_FORCE", "1"),
+            ("NO_COLOR", "1")
+        ])));
+        assert!(!disabled_by_env(&env(&[
+            ("FORCE_COLOR", "1"),
+            ("TERM", "dumb")
+        ])));
+    }
+
+    #[test]
+    fn test_force_color_selects_the_minimum_mode() {
+        let detect = |vars: &[(&str, &str)]| ColorMode::detect(&env(vars));
+        assert_eq!(detect(&[("TERM", "linux")]), ColorMode::Ansi16);
+        assert_eq!(detect(&[("TERM", "xterm-256color")]), ColorMode::Ansi256);
+        assert_eq!(
+            detect(&[("TERM", "linux"), ("FORCE_COLOR", "2")]),
+            ColorMode::Ansi256
+        );
+        assert_eq!(
+            detect(&[("TERM", "linux"), ("FORCE_COLOR", "3")]),
+            ColorMode::TrueColor
+        );
+        assert_eq!(
+            detect(&[("TERM", "xterm-256color"), ("FORCE_COLOR", "1")]),
+            ColorMode::Ansi256
+        );
+    }
+}
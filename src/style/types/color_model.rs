@@ -0,0 +1,690 @@
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::style::Color;
+
+use super::color_mode::{ansi256_rgb, named_ansi_value};
+
+/// A color in the [HSL](https://en.wikipedia.org/wiki/HSL_and_HSV) model.
+///
+/// It converts from and to [`Color`](enum.Color.html) through its RGB value.
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Hsl {
+    /// The hue in degrees, from `0.0` to `360.0`.
+    pub hue: f64,
+    /// The saturation, from `0.0` to `1.0`.
+    pub saturation: f64,
+    /// The lightness, from `0.0` to `1.0`.
+    pub lightness: f64,
+}
+
+impl Hsl {
+    /// Creates a new HSL color.
+    pub fn new(hue: f64, saturation: f64, lightness: f64) -> Hsl {
+        Hsl {
+            hue,
+            saturation,
+            lightness,
+        }
+    }
+}
+
+/// A color in the [OKLCH](https://bottosson.github.io/posts/oklab/) model, the polar form of the
+/// perceptually uniform Oklab.
+///
+/// Changing the lightness of an OKLCH color keeps the hue as perceived by the eye, which makes it
+/// the model of choice to derive shades of a color.
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Oklch {
+    /// The perceived lightness, from `0.0` to `1.0`.
+    pub lightness: f64,
+    /// The chroma, from `0.0` to about `0.37` for the colors of the sRGB gamut.
+    pub chroma: f64,
+    /// The hue in degrees, from `0.0` to `360.0`.
+    pub hue: f64,
+}
+
+impl Oklch {
+    /// Creates a new OKLCH color.
+    pub fn new(lightness: f64, chroma: f64, hue: f64) -> Oklch {
+        Oklch {
+            lightness,
+            chroma,
+            hue,
+        }
+    }
+}
+
+impl From<Hsl> for Color {
+    /// Creates a `Color::Rgb` from an HSL color.
+    fn from(hsl: Hsl) -> Self {
+        let saturation = hsl.saturation.clamp(0.0, 1.0);
+        let lightness = hsl.lightness.clamp(0.0, 1.0);
+
+        let chroma = (1.0 - (2.0 * lightness - 1.0).abs()) * saturation;
+        let sector = hsl.hue.rem_euclid(360.0) / 60.0;
+        let x = chroma * (1.0 - (sector % 2.0 - 1.0).abs());
+        let (r, g, b) = match sector as u8 {
+            0 => (chroma, x, 0.0),
+            1 => (x, chroma, 0.0),
+            2 => (0.0, chroma, x),
+            3 => (0.0, x, chroma),
+            4 => (x, 0.0, chroma),
+            _ => (chroma, 0.0, x),
+        };
+        let m = lightness - chroma / 2.0;
+        from_unit(r + m, g + m, b + m)
+    }
+}
+
+impl From<Oklch> for Color {
+    /// Creates a `Color::Rgb` from an OKLCH color. The colors outside of the sRGB gamut are
+    /// mapped into it by lowering their chroma, keeping their lightness and hue.
+    fn from(oklch: Oklch) -> Self {
+        let lightness = oklch.lightness.clamp(0.0, 1.0);
+        let (sin, cos) = oklch.hue.to_radians().sin_cos();
+        let oklab = |chroma: f64| (lightness, chroma * cos, chroma * sin);
+
+        let mut chroma = oklch.chroma.max(0.0);
+        if !in_gamut(oklab(chroma)) {
+            // Bisects between a chroma in the gamut and one out of it.
+            let (mut low, mut high) = (0.0, chroma);
+            for _ in 0..24 {
+                let middle = (low + high) / 2.0;
+                if in_gamut(oklab(middle)) {
+                    low = middle;
+                } else {
+                    high = middle;
+                }
+            }
+            chroma = low;
+        }
+        let (lightness, a, b) = oklab(chroma);
+        from_oklab(lightness, a, b)
+    }
+}
+
+impl Color {
+    /// Returns the RGB value of the color, or `None` for `Color::Reset`.
+    ///
+    /// The named colors and the 16 first ANSI values have the RGB values of the default xterm
+    /// palette. The actual colors depend on the palette of the terminal.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use crossterm::style::Color;
+    ///
+    /// assert_eq!(Color::DarkRed.to_rgb(), Some((205, 0, 0)));
+    /// assert_eq!(Color::AnsiValue(196).to_rgb(), Some((255, 0, 0)));
+    /// assert_eq!(Color::Reset.to_rgb(), None);
+    /// ```
+    pub fn to_rgb(self) -> Option<(u8, u8, u8)> {
+        match self {
+            Color::Reset => None,
+            Color::Rgb { r, g, b } => Some((r, g, b)),
+            Color::AnsiValue(value) => Some(ansi256_rgb(value)),
+            named => named_ansi_value(named).map(ansi256_rgb),
+        }
+    }
+
+    /// Returns the color in the HSL model, or `None` for `Color::Reset`.
+    pub fn to_hsl(self) -> Option<Hsl> {
+        let (r, g, b) = to_unit(self.to_rgb()?);
+        let max = r.max(g).max(b);
+        let min = r.min(g).min(b);
+        let delta = max - min;
+        let lightness = (max + min) / 2.0;
+
+        if delta == 0.0 {
+            return Some(Hsl::new(0.0, 0.0, lightness));
+        }
+        let saturation = delta / (1.0 - (2.0 * lightness - 1.0).abs());
+        let sector = if max == r {
+            ((g - b) / delta).rem_euclid(6.0)
+        } else if max == g {
+            (b - r) / delta + 2.0
+        } else {
+            (r - g) / delta + 4.0
+        };
+        Some(Hsl::new(sector * 60.0, saturation, lightness))
+    }
+
+    /// Returns the color in the OKLCH model, or `None` for `Color::Reset`.
+    pub fn to_oklch(self) -> Option<Oklch> {
+        let (lightness, a, b) = to_oklab(self.to_rgb()?);
+        let chroma = a.hypot(b);
+        let hue = b.atan2(a).to_degrees().rem_euclid(360.0);
+        Some(Oklch::new(lightness, chroma, hue))
+    }
+
+    /// Returns the color with its perceived (OKLCH) lightness raised by `amount`, from `0.0` to
+    /// `1.0`. `Color::Reset` is returned unchanged.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use crossterm::style::Color;
+    ///
+    /// let background = Color::Rgb { r: 30, g: 30, b: 46 };
+    /// let highlight = background.lighten(0.1);
+    ///
+    /// assert_eq!(Color::Black.lighten(1.0), Color::Rgb { r: 255, g: 255, b: 255 });
+    /// ```
+    pub fn lighten(self, amount: f64) -> Color {
+        match self.to_oklch() {
+            Some(oklch) => Color::from(Oklch {
+                lightness: (oklch.lightness + amount).clamp(0.0, 1.0),
+                ..oklch
+            }),
+            None => self,
+        }
+    }
+
+    /// Returns the color with its perceived (OKLCH) lightness lowered by `amount`, from `0.0` to
+    /// `1.0`. `Color::Reset` is returned unchanged.
+    pub fn darken(self, amount: f64) -> Color {
+        self.lighten(-amount)
+    }
+
+    /// Blends two colors in the Oklab model. A `weight` of `0.0` returns this color and a weight
+    /// of `1.0` returns `other`.
+    ///
+    /// `Color::Reset` can't be blended, if one of the colors is reset the color the weight is
+    /// closer to is returned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use crossterm::style::Color;
+    ///
+    /// let red = Color::Rgb { r: 255, g: 0, b: 0 };
+    /// let blue = Color::Rgb { r: 0, g: 0, b: 255 };
+    ///
+    /// assert_eq!(red.mix(blue, 0.0), red);
+    /// assert_eq!(red.mix(blue, 1.0), blue);
+    /// ```
+    pub fn mix(self, other: Color, weight: f64) -> Color {
+        let weight = weight.clamp(0.0, 1.0);
+        match (self.to_rgb(), other.to_rgb()) {
+            (Some(from), Some(to)) => {
+                let (l1, a1, b1) = to_oklab(from);
+                let (l2, a2, b2) = to_oklab(to);
+                let blend = |from: f64, to: f64| from + (to - from) * weight;
+                from_oklab(blend(l1, l2), blend(a1, a2), blend(b1, b2))
+            }
+            _ if weight < 0.5 => self,
+            _ => other,
+        }
+    }
+
+    /// Returns the relative luminance of the color as defined by
+    /// [WCAG](https://www.w3.org/TR/WCAG21/#dfn-relative-luminance), from `0.0` for black to
+    /// `1.0` for white, or `None` for `Color::Reset`.
+    pub fn relative_luminance(self) -> Option<f64> {
+        let (r, g, b) = to_unit(self.to_rgb()?);
+        Some(0.2126 * to_linear(r) + 0.7152 * to_linear(g) + 0.0722 * to_linear(b))
+    }
+
+    /// Returns the [WCAG](https://www.w3.org/TR/WCAG21/#dfn-contrast-ratio) contrast ratio of two
+    /// colors, from `1.0` to `21.0`, or `None` if one of them is `Color::Reset`.
+    ///
+    /// Text should have a contrast ratio of at least `4.5` with its background.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use crossterm::style::Color;
+    ///
+    /// let black = Color::Rgb { r: 0, g: 0, b: 0 };
+    /// let white = Color::Rgb { r: 255, g: 255, b: 255 };
+    ///
+    /// assert_eq!(black.contrast_ratio(white).map(f64::round), Some(21.0));
+    /// ```
+    pub fn contrast_ratio(self, other: Color) -> Option<f64> {
+        let first = self.relative_luminance()?;
+        let second = other.relative_luminance()?;
+        Some((first.max(second) + 0.05) / (first.min(second) + 0.05))
+    }
+}
+
+/// Parses the CSS notations of a color: `#rgb`, `#rrggbb`, `rgb(r, g, b)`, `hsl(h, s%, l%)` and
+/// the named colors. The string is expected to be lowercase.
+pub(crate) fn parse_css(src: &str) -> Option<Color> {
+    if let Some(hex) = src.strip_prefix('#') {
+        return parse_hex(hex);
+    }
+    if let Some([r, g, b]) = parse_function(src, "rgb") {
+        return Some(Color::Rgb {
+            r: parse_channel(r)?,
+            g: parse_channel(g)?,
+            b: parse_channel(b)?,
+        });
+    }
+    if let Some([hue, saturation, lightness]) = parse_function(src, "hsl") {
+        let hue = hue.strip_suffix("deg").unwrap_or(hue);
+        return Some(Color::from(Hsl::new(
+            parse_number(hue)?,
+            parse_percentage(saturation)?,
+            parse_percentage(lightness)?,
+        )));
+    }
+    CSS_COLORS
+        .binary_search_by(|(name, _)| (*name).cmp(src))
+        .ok()
+        .map(|index| from_hex(CSS_COLORS[index].1))
+}
+
+fn parse_hex(hex: &str) -> Option<Color> {
+    if !hex.bytes().all(|byte| byte.is_ascii_hexdigit()) {
+        return None;
+    }
+    let value = u32::from_str_radix(hex, 16).ok()?;
+    match hex.len() {
+        3 => {
+            let digit = |shift: u32| ((value >> shift) & 0xf) as u8 * 17;
+            Some(Color::Rgb {
+                r: digit(8),
+                g: digit(4),
+                b: digit(0),
+            })
+        }
+        6 => Some(from_hex(value)),
+        _ => None,
+    }
+}
+
+/// Returns the three arguments of `name(a, b, c)`, which may also be separated by spaces.
+fn parse_function<'a>(src: &'a str, name: &str) -> Option<[&'a str; 3]> {
+    let arguments = src
+        .strip_prefix(name)?
+        .trim_start()
+        .strip_prefix('(')?
+        .strip_suffix(')')?;
+    let mut arguments = arguments
+        .split(|c: char| c == ',' || c.is_whitespace())
+        .filter(|argument| !argument.is_empty());
+    let parsed = [arguments.next()?, arguments.next()?, arguments.next()?];
+    match arguments.next() {
+        Some(_) => None,
+        None => Some(parsed),
+    }
+}
+
+fn parse_number(src: &str) -> Option<f64> {
+    src.parse::<f64>().ok().filter(|number| number.is_finite())
+}
+
+/// Parses a channel from `0` to `255` or from `0%` to `100%`. Like in CSS, the values out of
+/// range are clamped.
+fn parse_channel(src: &str) -> Option<u8> {
+    let channel = match src.strip_suffix('%') {
+        Some(percentage) => parse_number(percentage)? / 100.0,
+        None => parse_number(src)? / 255.0,
+    };
+    Some(to_u8(channel))
+}
+
+/// Parses a percentage into a value from `0.0` to `1.0`, the `%` sign is optional.
+fn parse_percentage(src: &str) -> Option<f64> {
+    let percentage = parse_number(src.strip_suffix('%').unwrap_or(src))?;
+    Some((percentage / 100.0).clamp(0.0, 1.0))
+}
+
+fn from_hex(value: u32) -> Color {
+    Color::Rgb {
+        r: (value >> 16) as u8,
+        g: (value >> 8) as u8,
+        b: value as u8,
+    }
+}
+
+fn to_unit((r, g, b): (u8, u8, u8)) -> (f64, f64, f64) {
+    (
+        f64::from(r) / 255.0,
+        f64::from(g) / 255.0,
+        f64::from(b) / 255.0,
+    )
+}
+
+fn to_u8(unit: f64) -> u8 {
+    (unit.clamp(0.0, 1.0) * 255.0).round() as u8
+}
+
+fn from_unit(r: f64, g: f64, b: f64) -> Color {
+    Color::Rgb {
+        r: to_u8(r),
+        g: to_u8(g),
+        b: to_u8(b),
+    }
+}
+
+/// Removes the gamma of an sRGB channel.
+fn to_linear(channel: f64) -> f64 {
+    if channel <= 0.04045 {
+        channel / 12.92
+    } else {
+        ((channel + 0.055) / 1.055).powf(2.4)
+    }
+}
+
+/// Applies the gamma of sRGB to a linear channel.
+fn to_gamma(channel: f64) -> f64 {
+    if channel <= 0.0031308 {
+        channel * 12.92
+    } else {
+        1.055 * channel.powf(1.0 / 2.4) - 0.055
+    }
+}
+
+/// Converts an sRGB color to Oklab, see <https://bottosson.github.io/posts/oklab/>.
+fn to_oklab(rgb: (u8, u8, u8)) -> (f64, f64, f64) {
+    let (r, g, b) = to_unit(rgb);
+    let (r, g, b) = (to_linear(r), to_linear(g), to_linear(b));
+
+    let l = (0.4122214708 * r + 0.5363325363 * g + 0.0514459929 * b).cbrt();
+    let m = (0.2119034982 * r + 0.6806995451 * g + 0.1073969566 * b).cbrt();
+    let s = (0.0883024619 * r + 0.2817188376 * g + 0.6299787005 * b).cbrt();
+
+    (
+        0.2104542553 * l + 0.7936177850 * m - 0.0040720468 * s,
+        1.9779984951 * l - 2.4285922050 * m + 0.4505937099 * s,
+        0.0259040371 * l + 0.7827717662 * m - 0.8086757660 * s,
+    )
+}
+
+/// Converts an Oklab color to linear sRGB, the channels are out of `0.0..=1.0` if the color is
+/// out of the gamut.
+fn to_linear_rgb((lightness, a, b): (f64, f64, f64)) -> (f64, f64, f64) {
+    let l = (lightness + 0.3963377774 * a + 0.2158037573 * b).powi(3);
+    let m = (lightness - 0.1055613458 * a - 0.0638541728 * b).powi(3);
+    let s = (lightness - 0.0894841775 * a - 1.2914855480 * b).powi(3);
+
+    (
+        4.0767416621 * l - 3.3077115913 * m + 0.2309699292 * s,
+        -1.2684380046 * l + 2.6097574011 * m - 0.3413193965 * s,
+        -0.0041960863 * l - 0.7034186147 * m + 1.7076147010 * s,
+    )
+}
+
+fn in_gamut(oklab: (f64, f64, f64)) -> bool {
+    let (r, g, b) = to_linear_rgb(oklab);
+    let range = -1e-6..=1.0 + 1e-6;
+    range.contains(&r) && range.contains(&g) && range.contains(&b)
+}
+
+/// Converts an Oklab color to sRGB, clipping the channels out of the gamut.
+fn from_oklab(lightness: f64, a: f64, b: f64) -> Color {
+    let (r, g, b) = to_linear_rgb((lightness, a, b));
+    from_unit(to_gamma(r), to_gamma(g), to_gamma(b))
+}
+
+/// The CSS named colors, derived from the X11 colors, sorted by name.
+const CSS_COLORS: [(&str, u32); 148] = [
+    ("aliceblue", 0xf0f8ff),
+    ("antiquewhite", 0xfaebd7),
+    ("aqua", 0x00ffff),
+    ("aquamarine", 0x7fffd4),
+    ("azure", 0xf0ffff),
+    ("beige", 0xf5f5dc),
+    ("bisque", 0xffe4c4),
+    ("black", 0x000000),
+    ("blanchedalmond", 0xffebcd),
+    ("blue", 0x0000ff),
+    ("blueviolet", 0x8a2be2),
+    ("brown", 0xa52a2a),
+    ("burlywood", 0xdeb887),
+    ("cadetblue", 0x5f9ea0),
+    ("chartreuse", 0x7fff00),
+    ("chocolate", 0xd2691e),
+    ("coral", 0xff7f50),
+    ("cornflowerblue", 0x6495ed),
+    ("cornsilk", 0xfff8dc),
+    ("crimson", 0xdc143c),
+    ("cyan", 0x00ffff),
+    ("darkblue", 0x00008b),
+    ("darkcyan", 0x008b8b),
+    ("darkgoldenrod", 0xb8860b),
+    ("darkgray", 0xa9a9a9),
+    ("darkgreen", 0x006400),
+    ("darkgrey", 0xa9a9a9),
+    ("darkkhaki", 0xbdb76b),
+    ("darkmagenta", 0x8b008b),
+    ("darkolivegreen", 0x556b2f),
+    ("darkorange", 0xff8c00),
+    ("darkorchid", 0x9932cc),
+    ("darkred", 0x8b0000),
+    ("darksalmon", 0xe9967a),
+    ("darkseagreen", 0x8fbc8f),
+    ("darkslateblue", 0x483d8b),
+    ("darkslategray", 0x2f4f4f),
+    ("darkslategrey", 0x2f4f4f),
+    ("darkturquoise", 0x00ced1),
+    ("darkviolet", 0x9400d3),
+    ("deeppink", 0xff1493),
+    ("deepskyblue", 0x00bfff),
+    ("dimgray", 0x696969),
+    ("dimgrey", 0x696969),
+    ("dodgerblue", 0x1e90ff),
+    ("firebrick", 0xb22222),
+    ("floralwhite", 0xfffaf0),
+    ("forestgreen", 0x228b22),
+    ("fuchsia", 0xff00ff),
+    ("gainsboro", 0xdcdcdc),
+    ("ghostwhite", 0xf8f8ff),
+    ("gold", 0xffd700),
+    ("goldenrod", 0xdaa520),
+    ("gray", 0x808080),
+    ("green", 0x008000),
+    ("greenyellow", 0xadff2f),
+    ("grey", 0x808080),
+    ("honeydew", 0xf0fff0),
+    ("hotpink", 0xff69b4),
+    ("indianred", 0xcd5c5c),
+    ("indigo", 0x4b0082),
+    ("ivory", 0xfffff0),
+    ("khaki", 0xf0e68c),
+    ("lavender", 0xe6e6fa),
+    ("lavenderblush", 0xfff0f5),
+    ("lawngreen", 0x7cfc00),
+    ("lemonchiffon", 0xfffacd),
+    ("lightblue", 0xadd8e6),
+    ("lightcoral", 0xf08080),
+    ("lightcyan", 0xe0ffff),
+    ("lightgoldenrodyellow", 0xfafad2),
+    ("lightgray", 0xd3d3d3),
+    ("lightgreen", 0x90ee90),
+    ("lightgrey", 0xd3d3d3),
+    ("lightpink", 0xffb6c1),
+    ("lightsalmon", 0xffa07a),
+    ("lightseagreen", 0x20b2aa),
+    ("lightskyblue", 0x87cefa),
+    ("lightslategray", 0x778899),
+    ("lightslategrey", 0x778899),
+    ("lightsteelblue", 0xb0c4de),
+    ("lightyellow", 0xffffe0),
+    ("lime", 0x00ff00),
+    ("limegreen", 0x32cd32),
+    ("linen", 0xfaf0e6),
+    ("magenta", 0xff00ff),
+    ("maroon", 0x800000),
+    ("mediumaquamarine", 0x66cdaa),
+    ("mediumblue", 0x0000cd),
+    ("mediumorchid", 0xba55d3),
+    ("mediumpurple", 0x9370db),
+    ("mediumseagreen", 0x3cb371),
+    ("mediumslateblue", 0x7b68ee),
+    ("mediumspringgreen", 0x00fa9a),
+    ("mediumturquoise", 0x48d1cc),
+    ("mediumvioletred", 0xc71585),
+    ("midnightblue", 0x191970),
+    ("mintcream", 0xf5fffa),
+    ("mistyrose", 0xffe4e1),
+    ("moccasin", 0xffe4b5),
+    ("navajowhite", 0xffdead),
+    ("navy", 0x000080),
+    ("oldlace", 0xfdf5e6),
+    ("olive", 0x808000),
+    ("olivedrab", 0x6b8e23),
+    ("orange", 0xffa500),
+    ("orangered", 0xff4500),
+    ("orchid", 0xda70d6),
+    ("palegoldenrod", 0xeee8aa),
+    ("palegreen", 0x98fb98),
+    ("paleturquoise", 0xafeeee),
+    ("palevioletred", 0xdb7093),
+    ("papayawhip", 0xffefd5),
+    ("peachpuff", 0xffdab9),
+    ("peru", 0xcd853f),
+    ("pink", 0xffc0cb),
+    ("plum", 0xdda0dd),
+    ("powderblue", 0xb0e0e6),
+    ("purple", 0x800080),
+    ("rebeccapurple", 0x663399),
+    ("red", 0xff0000),
+    ("rosybrown", 0xbc8f8f),
+    ("royalblue", 0x4169e1),
+    ("saddlebrown", 0x8b4513),
+    ("salmon", 0xfa8072),
+    ("sandybrown", 0xf4a460),
+    ("seagreen", 0x2e8b57),
+    ("seashell", 0xfff5ee),
+    ("sienna", 0xa0522d),
+    ("silver", 0xc0c0c0),
+    ("skyblue", 0x87ceeb),
+    ("slateblue", 0x6a5acd),
+    ("slategray", 0x708090),
+    ("slategrey", 0x708090),
+    ("snow", 0xfffafa),
+    ("springgreen", 0x00ff7f),
+    ("steelblue", 0x4682b4),
+    ("tan", 0xd2b48c),
+    ("teal", 0x008080),
+    ("thistle", 0xd8bfd8),
+    ("tomato", 0xff6347),
+    ("turquoise", 0x40e0d0),
+    ("violet", 0xee82ee),
+    ("wheat", 0xf5deb3),
+    ("white", 0xffffff),
+    ("whitesmoke", 0xf5f5f5),
+    ("yellow", 0xffff00),
+    ("yellowgreen", 0x9acd32),
+];
+
+#[cfg(test)]
+mod tests {
+    use crate::style::Color;
+
+    use super::{parse_css, Hsl, Oklch, CSS_COLORS};
+
+    fn rgb(r: u8, g: u8, b: u8) -> Color {
+        Color::Rgb { r, g, b }
+    }
+
+    #[test]
+    fn test_parse_css_notations() {
+        assert_eq!(parse_css("#ff8000"), Some(rgb(255, 128, 0)));
+        assert_eq!(parse_css("#f80"), Some(rgb(255, 136, 0)));
+        assert_eq!(parse_css("rgb(255, 128, 0)"), Some(rgb(255, 128, 0)));
+        assert_eq!(parse_css("rgb(100% 50% 0%)"), Some(rgb(255, 128, 0)));
+        assert_eq!(parse_css("rgb(300,-5,0)"), Some(rgb(255, 0, 0)));
+        assert_eq!(parse_css("hsl(30, 100%, 50%)"), Some(rgb(255, 128, 0)));
+        assert_eq!(parse_css("hsl(390deg 100% 50%)"), Some(rgb(255, 128, 0)));
+        assert_eq!(parse_css("rebeccapurple"), Some(rgb(102, 51, 153)));
+
+        assert_eq!(parse_css("#ff80"), None);
+        assert_eq!(parse_css("#+ff"), None);
+        assert_eq!(parse_css("rgb(1, 2)"), None);
+        assert_eq!(parse_css("rgb(1, 2, 3, 4)"), None);
+        assert_eq!(parse_css("rgb(1, 2, nan)"), None);
+        assert_eq!(parse_css("hsl(30, 100%, 50%"), None);
+        assert_eq!(parse_css("unknown"), None);
+    }
+
+    #[test]
+    fn test_css_colors_are_sorted() {
+        assert!(CSS_COLORS.windows(2).all(|pair| pair[0].0 < pair[1].0));
+    }
+
+    #[test]
+    fn test_ansi_names_take_precedence() {
+        assert_eq!("red".parse(), Ok(Color::Red));
+        assert_eq!("gray".parse(), Ok(rgb(128, 128, 128)));
+        assert_eq!("#FFF".parse(), Ok(rgb(255, 255, 255)));
+        assert_eq!("Navy".parse(), Ok(rgb(0, 0, 128)));
+    }
+
+    #[test]
+    fn test_to_rgb() {
+        assert_eq!(Color::Red.to_rgb(), Some((255, 0, 0)));
+        assert_eq!(Color::Grey.to_rgb(), Some((229, 229, 229)));
+        assert_eq!(Color::AnsiValue(9).to_rgb(), Some((255, 0, 0)));
+        assert_eq!(Color::AnsiValue(110).to_rgb(), Some((135, 175, 215)));
+        assert_eq!(Color::AnsiValue(244).to_rgb(), Some((128, 128, 128)));
+        assert_eq!(Color::Reset.to_rgb(), None);
+    }
+
+    #[test]
+    fn test_models_round_trip() {
+        for color in [
+            rgb(0, 0, 0),
+            rgb(255, 255, 255),
+            rgb(255, 128, 0),
+            rgb(30, 30, 46),
+            rgb(12, 200, 99),
+            rgb(102, 51, 153),
+        ] {
+            assert_eq!(Color::from(color.to_hsl().unwrap()), color);
+            assert_eq!(Color::from(color.to_oklch().unwrap()), color);
+        }
+
+        let hsl = rgb(255, 0, 0).to_hsl().unwrap();
+        assert_eq!(hsl, Hsl::new(0.0, 1.0, 0.5));
+
+        let white = rgb(255, 255, 255).to_oklch().unwrap();
+        assert!((white.lightness - 1.0).abs() < 1e-4 && white.chroma < 1e-4);
+        assert_eq!(Color::from(Oklch::new(2.0, 0.0, 0.0)), rgb(255, 255, 255));
+
+        // Out of the gamut, the chroma is lowered.
+        let mapped = Color::from(Oklch::new(0.6, 0.4, 29.0)).to_oklch().unwrap();
+        assert!((mapped.lightness - 0.6).abs() < 0.01 && (mapped.hue - 29.0).abs() < 2.0);
+        assert!(mapped.chroma < 0.4);
+        assert_eq!(Color::Reset.to_oklch(), None);
+    }
+
+    #[test]
+    fn test_lighten_and_darken() {
+        let gray = rgb(128, 128, 128);
+        let lighter = gray.lighten(0.1).to_rgb().unwrap();
+        let darker = gray.darken(0.1).to_rgb().unwrap();
+        assert!(lighter.0 > 128 && lighter.0 == lighter.1 && lighter.1 == lighter.2);
+        assert!(darker.0 < 128 && darker.0 == darker.1 && darker.1 == darker.2);
+
+        assert_eq!(Color::Blue.darken(1.0), rgb(0, 0, 0));
+        assert_eq!(Color::Reset.lighten(0.5), Color::Reset);
+    }
+
+    #[test]
+    fn test_mix() {
+        let (black, white) = (rgb(0, 0, 0), rgb(255, 255, 255));
+        assert_eq!(black.mix(white, 0.0), black);
+        assert_eq!(black.mix(white, 1.0), white);
+        assert_eq!(black.mix(white, 0.5), rgb(99, 99, 99));
+        assert_eq!(Color::Black.mix(Color::White, 2.0), white);
+
+        assert_eq!(Color::Reset.mix(white, 0.2), Color::Reset);
+        assert_eq!(Color::Reset.mix(white, 0.8), white);
+    }
+
+    #[test]
+    fn test_contrast_ratio() {
+        let ratio = |a: Color, b: Color| (a.contrast_ratio(b).unwrap() * 100.0).round() / 100.0;
+        assert_eq!(ratio(rgb(0, 0, 0), rgb(255, 255, 255)), 21.0);
+        assert_eq!(ratio(rgb(255, 255, 255), rgb(0, 0, 0)), 21.0);
+        assert_eq!(ratio(rgb(118, 118, 118), rgb(255, 255, 255)), 4.54);
+        assert_eq!(ratio(Color::Red, Color::Red), 1.0);
+        assert_eq!(Color::Reset.contrast_ratio(Color::Black), None);
+    }
+}
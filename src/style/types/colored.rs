@@ -7,6 +7,8 @@ use serde::{Deserialize, Serialize};
 
 use crate::style::{parse_next_u8, Color};
 
+use super::color_mode::{disabled_by_env, forced_by_env, named_ansi_value, process_env, ColorMode};
+
 /// Represents a foreground or background color.
 ///
 /// This can be converted to a [Colors](struct.Colors.html) by calling `into()` and applied
@@ -24,6 +26,8 @@ pub enum Colored {
 }
 
 static ANSI_COLOR_DISABLED: AtomicBool = AtomicBool::new(false);
+// Whether colors were enabled or disabled with `set_ansi_color_disabled`.
+static ANSI_COLOR_FORCED: AtomicBool = AtomicBool::new(false);
 static INITIALIZER: Once = Once::new();
 
 impl Colored {
@@ -70,12 +74,11 @@ impl Colored {
         Some(output)
     }
 
-    /// Checks whether ansi color sequences are disabled by setting of NO_COLOR
-    /// in environment as per https://no-color.org/
+    /// Checks whether ansi color sequences are disabled by the environment: by setting of
+    /// NO_COLOR (https://no-color.org/), `CLICOLOR=0`, `FORCE_COLOR=0` or `TERM=dumb`, unless
+    /// `CLICOLOR_FORCE` or `FORCE_COLOR` force them.
     pub fn ansi_color_disabled() -> bool {
-        !std::env::var("NO_COLOR")
-            .unwrap_or("".to_string())
-            .is_empty()
+        disabled_by_env(&process_env)
     }
 
     pub fn ansi_color_disabled_memoized() -> bool {
@@ -90,35 +93,71 @@ impl Colored {
         // Force the one-time initializer to run.
         _ = Self::ansi_color_disabled_memoized();
         ANSI_COLOR_DISABLED.store(val, Ordering::SeqCst);
+        ANSI_COLOR_FORCED.store(true, Ordering::SeqCst);
+    }
+
+    /// Returns whether ansi color sequences are written to a stream, with the color mode of the
+    /// terminal.
+    ///
+    /// They aren't written if the stream isn't a terminal, unless they're forced by the
+    /// environment or [`set_ansi_color_disabled`](#method.set_ansi_color_disabled).
+    pub(crate) fn color_mode_for(is_tty: bool) -> ColorMode {
+        if Self::ansi_color_disabled_memoized() {
+            ColorMode::NoColor
+        } else if is_tty || ANSI_COLOR_FORCED.load(Ordering::SeqCst) || forced_by_env(&process_env)
+        {
+            ColorMode::current()
+        } else {
+            ColorMode::NoColor
+        }
     }
 }
 
 impl fmt::Display for Colored {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
-        let color;
-
+        if let Some(mode) = ColorMode::scoped() {
+            return self.fmt_in(mode, f);
+        }
         if Self::ansi_color_disabled_memoized() {
             return Ok(());
         }
+        self.fmt_in(ColorMode::current(), f)
+    }
+}
+
+impl Colored {
+    /// Writes the color downgraded to the given color mode.
+    fn fmt_in(&self, mode: ColorMode, f: &mut Formatter<'_>) -> fmt::Result {
+        let color;
 
         match *self {
             Colored::ForegroundColor(new_color) => {
+                let new_color = mode.downgrade(new_color);
                 if new_color == Color::Reset {
                     return f.write_str("39");
+                } else if let (ColorMode::Ansi16, Some(value)) = (mode, named_ansi_value(new_color))
+                {
+                    return write_ansi16(f, 30, value);
                 } else {
                     f.write_str("38;")?;
                     color = new_color;
                 }
             }
             Colored::BackgroundColor(new_color) => {
+                let new_color = mode.downgrade(new_color);
                 if new_color == Color::Reset {
                     return f.write_str("49");
+                } else if let (ColorMode::Ansi16, Some(value)) = (mode, named_ansi_value(new_color))
+                {
+                    return write_ansi16(f, 40, value);
                 } else {
                     f.write_str("48;")?;
                     color = new_color;
                 }
             }
             Colored::UnderlineColor(new_color) => {
+                // There are no SGR parameters for the 16 colors of the underline.
+                let new_color = mode.downgrade(new_color);
                 if new_color == Color::Reset {
                     return f.write_str("59");
                 } else {
@@ -152,15 +191,36 @@ impl fmt::Display for Colored {
     }
 }
 
+/// Writes a named color with the parameters of the 8 normal (`base`) and 8 bright (`base + 60`)
+/// colors.
+fn write_ansi16(f: &mut Formatter<'_>, base: u8, value: u8) -> fmt::Result {
+    if value < 8 {
+        write!(f, "{}", base + value)
+    } else {
+        write!(f, "{}", base + 60 + value - 8)
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use crate::style::{Color, Colored};
+    use std::fmt;
+
+    use crate::style::{
+        with_color_mode, Color, ColorMode,
+        Colored::{self, BackgroundColor, ForegroundColor, UnderlineColor},
+    };
+
+    use super::{forced_by_env, process_env};
 
     fn check_format_color(colored: Colored, expected: &str) {
         Colored::set_ansi_color_disabled(true);
         assert_eq!(colored.to_string(), "");
         Colored::set_ansi_color_disabled(false);
-        assert_eq!(colored.to_string(), expected);
+        // The color mode of the terminal running the tests would downgrade the colors.
+        assert_eq!(
+            with_color_mode(ColorMode::TrueColor, || colored.to_string()),
+            expected
+        );
     }
 
     #[test]
@@ -199,6 +259,57 @@ mod tests {
         check_format_color(colored, "38;5;255");
     }
 
+    #[test]
+    fn test_format_downgraded_colors() {
+        struct InMode(ColorMode, Colored);
+
+        impl fmt::Display for InMode {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                self.1.fmt_in(self.0, f)
+            }
+        }
+
+        let rgb = Color::Rgb {
+            r: 0,
+            g: 95,
+            b: 215,
+        };
+        let format = |mode, colored| InMode(mode, colored).to_string();
+
+        assert_eq!(
+            format(ColorMode::TrueColor, ForegroundColor(rgb)),
+            "38;2;0;95;215"
+        );
+        assert_eq!(format(ColorMode::Ansi256, ForegroundColor(rgb)), "38;5;26");
+        assert_eq!(format(ColorMode::Ansi16, ForegroundColor(rgb)), "94");
+        assert_eq!(
+            format(ColorMode::Ansi16, BackgroundColor(Color::Red)),
+            "101"
+        );
+        assert_eq!(
+            format(ColorMode::Ansi16, UnderlineColor(Color::Red)),
+            "58;5;9"
+        );
+        assert_eq!(format(ColorMode::NoColor, BackgroundColor(rgb)), "49");
+    }
+
+    #[test]
+    fn test_format_with_color_mode() {
+        let colored = ForegroundColor(Color::Rgb {
+            r: 0,
+            g: 95,
+            b: 215,
+        });
+        assert_eq!(
+            with_color_mode(ColorMode::Ansi256, || colored.to_string()),
+            "38;5;26"
+        );
+        assert_eq!(
+            with_color_mode(ColorMode::NoColor, || colored.to_string()),
+            "39"
+        );
+    }
+
     #[test]
     fn test_parse_ansi_fg() {
         test_parse_ansi(Colored::ForegroundColor)
@@ -215,7 +326,8 @@ mod tests {
         macro_rules! test {
             ($color:expr) => {
                 let colored = bg_or_fg($color);
-                assert_eq!(Colored::parse_ansi(&format!("{}", colored)), Some(colored));
+                let formatted = with_color_mode(ColorMode::TrueColor, || colored.to_string());
+                assert_eq!(Colored::parse_ansi(&formatted), Some(colored));
             };
         }
 
@@ -308,13 +420,18 @@ mod tests {
 
     #[test]
     fn test_no_color() {
+        // The other variables of the environment running the tests can disable or force colors.
+        std::env::remove_var("NO_COLOR");
+        let disabled = Colored::ansi_color_disabled();
+        let forced = forced_by_env(&process_env);
+
         std::env::set_var("NO_COLOR", "1");
-        assert!(Colored::ansi_color_disabled());
+        assert_eq!(Colored::ansi_color_disabled(), !forced);
         std::env::set_var("NO_COLOR", "XXX");
-        assert!(Colored::ansi_color_disabled());
+        assert_eq!(Colored::ansi_color_disabled(), !forced);
         std::env::set_var("NO_COLOR", "");
-        assert!(!Colored::ansi_color_disabled());
+        assert_eq!(Colored::ansi_color_disabled(), disabled);
         std::env::remove_var("NO_COLOR");
-        assert!(!Colored::ansi_color_disabled());
+        assert_eq!(Colored::ansi_color_disabled(), disabled);
     }
 }
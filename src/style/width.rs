@@ -0,0 +1,219 @@
+//! This module contains the display width of text.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use unicode_segmentation::UnicodeSegmentation;
+use unicode_width::UnicodeWidthChar;
+
+static GRAPHEME_CLUSTERING: AtomicBool = AtomicBool::new(false);
+
+/// How the width of a grapheme cluster is computed.
+///
+/// The width of a character follows the East Asian Width property
+/// ([UAX #11](https://www.unicode.org/reports/tr11/)): wide and fullwidth characters cover two
+/// columns, combining marks and other zero width characters none, and the rest one.
+///
+/// The mode used by [`text_width`](fn.text_width.html) and the other functions is set with
+/// [`set_width_mode`](fn.set_width_mode.html).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum WidthMode {
+    /// A grapheme cluster covers the sum of the widths of its characters, like in most
+    /// terminals. An emoji ZWJ sequence like `"👨‍👩‍👧"` covers six columns.
+    #[default]
+    Characters,
+    /// A grapheme cluster covers the width of its first character, two columns with an emoji
+    /// presentation selector (`U+FE0F`) or one with a text presentation selector (`U+FE0E`).
+    /// This is how terminals with grapheme clustering (mode 2027) enabled compute the width.
+    Graphemes,
+}
+
+impl WidthMode {
+    /// Returns the number of columns the text covers.
+    pub fn text_width(self, text: &str) -> usize {
+        match self {
+            WidthMode::Characters => text.chars().map(char_width).sum(),
+            WidthMode::Graphemes => text
+                .graphemes(true)
+                .map(|grapheme| self.grapheme_width(grapheme))
+                .sum(),
+        }
+    }
+
+    /// Returns the number of columns a grapheme cluster covers.
+    pub fn grapheme_width(self, grapheme: &str) -> usize {
+        match self {
+            WidthMode::Characters => self.text_width(grapheme),
+            WidthMode::Graphemes => {
+                let mut chars = grapheme.chars();
+                let mut width = chars.next().map_or(0, char_width);
+                for c in chars {
+                    match c {
+                        '\u{FE0F}' => width = 2,
+                        '\u{FE0E}' => width = width.min(1),
+                        // A pair of regional indicators is a flag.
+                        '\u{1F1E6}'..='\u{1F1FF}' => width = 2,
+                        _ => {}
+                    }
+                }
+                width
+            }
+        }
+    }
+}
+
+fn char_width(c: char) -> usize {
+    c.width().unwrap_or(0)
+}
+
+/// Returns the width mode used by [`text_width`](fn.text_width.html) and the other functions.
+pub fn width_mode() -> WidthMode {
+    if GRAPHEME_CLUSTERING.load(Ordering::Relaxed) {
+        WidthMode::Graphemes
+    } else {
+        WidthMode::Characters
+    }
+}
+
+/// Sets the width mode used by [`text_width`](fn.text_width.html) and the other functions.
+///
+/// [`WidthMode::Graphemes`](enum.WidthMode.html#variant.Graphemes) should only be set if the
+/// terminal supports grapheme clustering and it's enabled.
+pub fn set_width_mode(mode: WidthMode) {
+    GRAPHEME_CLUSTERING.store(mode == WidthMode::Graphemes, Ordering::Relaxed);
+}
+
+/// Returns the number of columns the text covers in the terminal.
+///
+/// # Examples
+///
+/// ```
+/// use crossterm::style::text_width;
+///
+/// assert_eq!(text_width("abc"), 3);
+/// assert_eq!(text_width("中文"), 4);
+/// assert_eq!(text_width("e\u{301}"), 1);
+/// ```
+pub fn text_width(text: &str) -> usize {
+    width_mode().text_width(text)
+}
+
+/// Returns the number of columns a grapheme cluster covers in the terminal.
+pub fn grapheme_width(grapheme: &str) -> usize {
+    width_mode().grapheme_width(grapheme)
+}
+
+/// Returns the longest start of the text that covers at most `width` columns, without splitting
+/// a grapheme cluster.
+///
+/// # Examples
+///
+/// ```
+/// use crossterm::style::truncate_to_width;
+///
+/// assert_eq!(truncate_to_width("abc", 2), "ab");
+/// assert_eq!(truncate_to_width("a中b", 2), "a");
+/// ```
+pub fn truncate_to_width(text: &str, width: usize) -> &str {
+    truncate_in(width_mode(), text, width)
+}
+
+fn truncate_in(mode: WidthMode, text: &str, width: usize) -> &str {
+    let mut used = 0;
+    for (index, grapheme) in text.grapheme_indices(true) {
+        used += mode.grapheme_width(grapheme);
+        if used > width {
+            return &text[..index];
+        }
+    }
+    text
+}
+
+/// The alignment of text padded with [`pad_to_width`](fn.pad_to_width.html).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Alignment {
+    /// Pads the text at the end.
+    Left,
+    /// Pads the text on both sides, with the odd space at the end.
+    Center,
+    /// Pads the text at the start.
+    Right,
+}
+
+/// Pads the text with spaces to cover `width` columns, truncating it with
+/// [`truncate_to_width`](fn.truncate_to_width.html) if it's wider.
+///
+/// # Examples
+///
+/// ```
+/// use crossterm::style::{pad_to_width, Alignment};
+///
+/// assert_eq!(pad_to_width("中", 4, Alignment::Left), "中  ");
+/// assert_eq!(pad_to_width("ab", 5, Alignment::Center), " ab  ");
+/// assert_eq!(pad_to_width("abc", 2, Alignment::Right), "ab");
+/// ```
+pub fn pad_to_width(text: &str, width: usize, alignment: Alignment) -> String {
+    pad_in(width_mode(), text, width, alignment)
+}
+
+fn pad_in(mode: WidthMode, text: &str, width: usize, alignment: Alignment) -> String {
+    let text = truncate_in(mode, text, width);
+    // A wide grapheme cluster that doesn't fit leaves one column.
+    let padding = width - mode.text_width(text);
+    let start = match alignment {
+        Alignment::Left => 0,
+        Alignment::Center => padding / 2,
+        Alignment::Right => padding,
+    };
+    format!(
+        "{:start$}{}{:end$}",
+        "",
+        text,
+        "",
+        start = start,
+        end = padding - start
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{pad_in, truncate_in, Alignment, WidthMode};
+
+    const FAMILY: &str = "👨\u{200D}👩\u{200D}👧";
+
+    #[test]
+    fn test_text_width() {
+        for mode in [WidthMode::Characters, WidthMode::Graphemes] {
+            assert_eq!(mode.text_width(""), 0);
+            assert_eq!(mode.text_width("abc"), 3);
+            assert_eq!(mode.text_width("中文"), 4);
+            assert_eq!(mode.text_width("ｆｕｌｌ"), 8);
+            assert_eq!(mode.text_width("e\u{301}"), 1);
+            assert_eq!(mode.text_width("🇩🇪"), 2);
+            assert_eq!(mode.text_width("\x1b"), 0);
+        }
+
+        assert_eq!(WidthMode::Characters.text_width(FAMILY), 6);
+        assert_eq!(WidthMode::Graphemes.text_width(FAMILY), 2);
+        assert_eq!(WidthMode::Characters.text_width("❤\u{FE0F}"), 1);
+        assert_eq!(WidthMode::Graphemes.text_width("❤\u{FE0F}"), 2);
+    }
+
+    #[test]
+    fn test_truncate_does_not_split_graphemes() {
+        let mode = WidthMode::Characters;
+        assert_eq!(truncate_in(mode, "abc", 5), "abc");
+        assert_eq!(truncate_in(mode, "a中b", 2), "a");
+        assert_eq!(truncate_in(mode, "e\u{301}x", 1), "e\u{301}");
+        assert_eq!(truncate_in(mode, FAMILY, 5), "");
+        assert_eq!(truncate_in(WidthMode::Graphemes, FAMILY, 5), FAMILY);
+    }
+
+    #[test]
+    fn test_pad() {
+        let mode = WidthMode::Characters;
+        assert_eq!(pad_in(mode, "ab", 4, Alignment::Left), "ab  ");
+        assert_eq!(pad_in(mode, "ab", 4, Alignment::Right), "  ab");
+        assert_eq!(pad_in(mode, "中", 5, Alignment::Center), " 中  ");
+        assert_eq!(pad_in(mode, "a中", 2, Alignment::Left), "a ");
+    }
+}
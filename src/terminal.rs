@@ -60,6 +60,9 @@
 //!
 //! Raw mode can be enabled/disabled with the [enable_raw_mode](terminal::enable_raw_mode) and [disable_raw_mode](terminal::disable_raw_mode) functions.
 //!
+//! Some of these modes can be kept with [enable_raw_mode_with](terminal::enable_raw_mode_with), e.g. to
+//! use the cbreak mode where CTRL+C still sends `SIGINT` and `println!` still works.
+//!
 //! ## Examples
 //!
 //! ```no_run
@@ -96,10 +99,15 @@ use winapi::um::wincon::ENABLE_WRAP_AT_EOL_OUTPUT;
 use crate::Command;
 use crate::{csi, impl_display};
 
+pub use self::guard::TerminalGuard;
+use self::modes::Modes;
+
+mod guard;
+pub(crate) mod modes;
 pub(crate) mod sys;
 
 #[cfg(feature = "events")]
-pub use sys::supports_keyboard_enhancement;
+pub use sys::{query_grapheme_clustering, supports_keyboard_enhancement};
 
 /// Tells whether the raw mode is enabled.
 ///
@@ -123,6 +131,82 @@ pub fn enable_raw_mode() -> io::Result<()> {
     sys::enable_raw_mode()
 }
 
+/// Enables raw mode, keeping the modes selected in the given options.
+///
+/// If raw mode is enabled already, the options are applied to it. Raw mode is disabled with
+/// [`disable_raw_mode`](fn.disable_raw_mode.html) like with the default options.
+///
+/// Please have a look at the [raw mode](./index.html#raw-mode) section.
+///
+/// # Examples
+///
+/// ```no_run
+/// use std::io;
+///
+/// use crossterm::terminal::{enable_raw_mode_with, RawModeOptions};
+///
+/// fn main() -> io::Result<()> {
+///     // No echo and no line buffering, but CTRL+C still sends SIGINT and `\n` starts a new line.
+///     enable_raw_mode_with(RawModeOptions::cbreak())?;
+///     Ok(())
+/// }
+/// ```
+pub fn enable_raw_mode_with(options: RawModeOptions) -> io::Result<()> {
+    sys::enable_raw_mode_with(options)
+}
+
+/// Options for [`enable_raw_mode_with`](fn.enable_raw_mode_with.html).
+///
+/// The modes selected to be kept stay as they were before raw mode was enabled. The default
+/// options don't keep any mode, like [`enable_raw_mode`](fn.enable_raw_mode.html).
+///
+/// # Platform-specific Notes
+///
+/// Only [`keep_signals`](#structfield.keep_signals) is used on Windows, where it keeps the
+/// processing of CTRL+C by the system.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct RawModeOptions {
+    /// Keeps `ISIG`, the terminal sends signals like `SIGINT` for CTRL+C instead of the keys.
+    pub keep_signals: bool,
+    /// Keeps `OPOST`, the output is processed, e.g. `\n` is written as `\r\n`.
+    pub keep_output_processing: bool,
+    /// Keeps `IXON`, CTRL+S and CTRL+Q stop and start the output instead of being read as keys.
+    pub keep_flow_control: bool,
+    /// Keeps `ICRNL`, `\r` is read as `\n`, i.e. Enter is read as CTRL+J.
+    pub keep_carriage_return_translation: bool,
+    /// `VMIN`, the minimum number of bytes a read from the terminal waits for.
+    pub min_bytes: u8,
+    /// `VTIME`, the time in tenths of a second a read from the terminal waits for input.
+    pub timeout: u8,
+}
+
+impl Default for RawModeOptions {
+    fn default() -> Self {
+        RawModeOptions {
+            keep_signals: false,
+            keep_output_processing: false,
+            keep_flow_control: false,
+            keep_carriage_return_translation: false,
+            min_bytes: 1,
+            timeout: 0,
+        }
+    }
+}
+
+impl RawModeOptions {
+    /// Options for the cbreak mode: no echo and no line buffering, but signals, output
+    /// processing, flow control and carriage return translation are kept.
+    pub fn cbreak() -> RawModeOptions {
+        RawModeOptions {
+            keep_signals: true,
+            keep_output_processing: true,
+            keep_flow_control: true,
+            keep_carriage_return_translation: true,
+            ..RawModeOptions::default()
+        }
+    }
+}
+
 /// Disables raw mode.
 ///
 /// Please have a look at the [raw mode](./index.html#raw-mode) section.
@@ -130,6 +214,55 @@ pub fn disable_raw_mode() -> io::Result<()> {
     sys::disable_raw_mode()
 }
 
+/// Discards the input typed but not read yet.
+///
+/// Both the input buffered by the terminal driver and the key, mouse and paste events already
+/// read by crossterm but not returned by [`read`](../event/fn.read.html) yet are discarded, e.g.
+/// to ignore the keys pressed during a long operation before asking for a confirmation. Other
+/// events, like resize events, are kept.
+///
+/// The events queued by an [`EventStream`](../event/struct.EventStream.html) are discarded as
+/// well, but the events already delivered to a
+/// [`Subscription`](../event/struct.Subscription.html) are kept.
+///
+/// This waits for a concurrent call to [`read`](../event/fn.read.html) or
+/// [`poll`](../event/fn.poll.html) to return.
+pub fn flush_input() -> io::Result<()> {
+    sys::flush_input()?;
+    #[cfg(feature = "events")]
+    crate::event::discard_input();
+    Ok(())
+}
+
+/// Waits until all output written to the terminal has been transmitted.
+///
+/// This doesn't flush the buffers of writers like [`Stdout`](std::io::Stdout), flush them
+/// first.
+///
+/// # Platform-specific Notes
+///
+/// This does nothing on Windows, where the console output is not queued.
+pub fn drain_output() -> io::Result<()> {
+    sys::drain_output()
+}
+
+/// Suspends the process like `Ctrl+Z` does in a shell (UNIX only).
+///
+/// In raw mode `Ctrl+Z` is read as a key event instead of stopping the process, call this
+/// function to handle it. The raw mode and the modes enabled with crossterm's commands (alternate
+/// screen, hidden cursor, mouse capture, focus change, bracketed paste and keyboard enhancement
+/// flags) are disabled before the process is stopped and enabled again when it continues. The
+/// function returns after the process continued.
+///
+/// After [`enable_suspend_handling`](../event/fn.enable_suspend_handling.html), a `SIGTSTP`
+/// signal is handled the same way when events are read. After the process continued, an
+/// [`Event::Resumed`](../event/enum.Event.html#variant.Resumed) is read, the screen should be
+/// redrawn then.
+#[cfg(all(unix, feature = "events"))]
+pub fn suspend() -> io::Result<()> {
+    sys::suspend()
+}
+
 /// Returns the terminal size `(columns, rows)`.
 ///
 /// The top left cell is represented `(1, 1)`.
@@ -192,6 +325,98 @@ impl Command for EnableLineWrap {
     }
 }
 
+/// A command that enables grapheme clustering (mode 2027).
+///
+/// A terminal with grapheme clustering enabled treats a grapheme cluster, like an emoji ZWJ
+/// sequence, as a single character covering one or two columns. Terminals that don't support it
+/// ignore this command.
+///
+/// # Notes
+///
+/// * Commands must be executed/queued for execution otherwise they do nothing.
+/// * Use [`query_grapheme_clustering`](fn.query_grapheme_clustering.html) to check whether it's
+///   enabled and [`set_width_mode`](../style/fn.set_width_mode.html) to compute the width of text
+///   the same way.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EnableGraphemeClustering;
+
+impl Command for EnableGraphemeClustering {
+    fn write_ansi(&self, f: &mut impl fmt::Write) -> fmt::Result {
+        f.write_str(csi!("?2027h"))
+    }
+
+    #[cfg(windows)]
+    fn execute_winapi(&self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+/// A command that disables grapheme clustering (mode 2027).
+///
+/// # Notes
+///
+/// * Commands must be executed/queued for execution otherwise they do nothing.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DisableGraphemeClustering;
+
+impl Command for DisableGraphemeClustering {
+    fn write_ansi(&self, f: &mut impl fmt::Write) -> fmt::Result {
+        f.write_str(csi!("?2027l"))
+    }
+
+    #[cfg(windows)]
+    fn execute_winapi(&self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+/// The setting of a terminal mode, as reported by the terminal.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ModeSetting {
+    /// The terminal doesn't recognize the mode or doesn't reply to the query.
+    NotRecognized,
+    /// The mode is enabled and can be disabled.
+    Set,
+    /// The mode is disabled and can be enabled.
+    Reset,
+    /// The mode is always enabled.
+    PermanentlySet,
+    /// The mode is always disabled.
+    PermanentlyReset,
+}
+
+impl ModeSetting {
+    /// Returns whether the mode is enabled.
+    pub fn is_set(self) -> bool {
+        matches!(self, ModeSetting::Set | ModeSetting::PermanentlySet)
+    }
+
+    /// Returns the setting for a `Ps` parameter of a DECRPM report.
+    #[cfg(unix)]
+    #[cfg(feature = "events")]
+    pub(crate) fn from_report(setting: u8) -> ModeSetting {
+        match setting {
+            1 => ModeSetting::Set,
+            2 => ModeSetting::Reset,
+            3 => ModeSetting::PermanentlySet,
+            4 => ModeSetting::PermanentlyReset,
+            _ => ModeSetting::NotRecognized,
+        }
+    }
+}
+
+/// Checks whether crossterm computes the width of text like the terminal does.
+///
+/// The terminal is queried with [`query_grapheme_clustering`](fn.query_grapheme_clustering.html)
+/// and compared with the [`width_mode`](../style/fn.width_mode.html): grapheme clustering has to
+/// be enabled exactly if the width mode is
+/// [`WidthMode::Graphemes`](../style/enum.WidthMode.html#variant.Graphemes).
+#[cfg(feature = "events")]
+pub fn width_mode_matches_terminal() -> io::Result<bool> {
+    let graphemes = crate::style::width_mode() == crate::style::WidthMode::Graphemes;
+    Ok(query_grapheme_clustering()?.is_set() == graphemes)
+}
+
 /// A command that switches to alternate screen.
 ///
 /// # Notes
@@ -222,6 +447,10 @@ impl Command for EnterAlternateScreen {
         f.write_str(csi!("?1049h"))
     }
 
+    fn record_mode(&self) {
+        modes::set(Modes::ALTERNATE_SCREEN, true);
+    }
+
     #[cfg(windows)]
     fn execute_winapi(&self) -> io::Result<()> {
         let alternate_screen = ScreenBuffer::create()?;
@@ -260,6 +489,10 @@ impl Command for LeaveAlternateScreen {
         f.write_str(csi!("?1049l"))
     }
 
+    fn record_mode(&self) {
+        modes::set(Modes::ALTERNATE_SCREEN, false);
+    }
+
     #[cfg(windows)]
     fn execute_winapi(&self) -> io::Result<()> {
         let screen_buffer = ScreenBuffer::from(Handle::current_out_handle()?);
@@ -330,6 +563,310 @@ impl Command for ScrollDown {
     }
 }
 
+/// A command that restricts scrolling to the rows from `top` to `bottom`, both 0-based and
+/// inclusive (DECSTBM).
+///
+/// Line feeds at the bottom row, [`ScrollUp`](struct.ScrollUp.html),
+/// [`ScrollDown`](struct.ScrollDown.html), [`InsertLines`](struct.InsertLines.html) and
+/// [`DeleteLines`](struct.DeleteLines.html) only move the rows of the region, e.g. to scroll a pane
+/// without repainting it. The cursor is moved to the top left corner of the screen.
+///
+/// # Notes
+///
+/// * Commands must be executed/queued for execution otherwise they do nothing.
+/// * Use [`ResetScrollRegion`](struct.ResetScrollRegion.html) to scroll the whole screen again.
+/// * With WinAPI, the region is only used by `InsertLines` and `DeleteLines`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SetScrollRegion(pub u16, pub u16);
+
+impl Command for SetScrollRegion {
+    fn write_ansi(&self, f: &mut impl fmt::Write) -> fmt::Result {
+        write!(f, csi!("{};{}r"), self.0 + 1, self.1 + 1)
+    }
+
+    fn record_mode(&self) {
+        modes::set_scroll_region(Some((self.0, self.1)));
+    }
+
+    #[cfg(windows)]
+    fn execute_winapi(&self) -> io::Result<()> {
+        sys::set_scroll_region(Some((self.0, self.1)))
+    }
+}
+
+/// A command that makes the whole screen scroll again after a
+/// [`SetScrollRegion`](struct.SetScrollRegion.html).
+///
+/// The cursor is moved to the top left corner of the screen.
+///
+/// # Notes
+///
+/// Commands must be executed/queued for execution otherwise they do nothing.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ResetScrollRegion;
+
+impl Command for ResetScrollRegion {
+    fn write_ansi(&self, f: &mut impl fmt::Write) -> fmt::Result {
+        f.write_str(csi!("r"))
+    }
+
+    fn record_mode(&self) {
+        modes::set_scroll_region(None);
+    }
+
+    #[cfg(windows)]
+    fn execute_winapi(&self) -> io::Result<()> {
+        sys::set_scroll_region(None)
+    }
+}
+
+/// A command that allows setting left and right margins with
+/// [`SetLeftRightMargins`](struct.SetLeftRightMargins.html) (mode 69).
+///
+/// # Notes
+///
+/// * Commands must be executed/queued for execution otherwise they do nothing.
+/// * While this mode is enabled, `ESC [ s` sets the margins instead of saving the cursor position.
+///   [`SavePosition`](../cursor/struct.SavePosition.html) isn't affected, it uses `ESC 7`.
+/// * Margins aren't supported by WinAPI.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EnableLeftRightMargins;
+
+impl Command for EnableLeftRightMargins {
+    fn write_ansi(&self, f: &mut impl fmt::Write) -> fmt::Result {
+        f.write_str(csi!("?69h"))
+    }
+
+    fn record_mode(&self) {
+        modes::set(Modes::LEFT_RIGHT_MARGINS, true);
+    }
+
+    #[cfg(windows)]
+    fn execute_winapi(&self) -> io::Result<()> {
+        Err(margins_unsupported())
+    }
+}
+
+/// A command that disables left and right margins again, resetting them to the whole width of the
+/// screen.
+///
+/// # Notes
+///
+/// Commands must be executed/queued for execution otherwise they do nothing.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DisableLeftRightMargins;
+
+impl Command for DisableLeftRightMargins {
+    fn write_ansi(&self, f: &mut impl fmt::Write) -> fmt::Result {
+        f.write_str(csi!("?69l"))
+    }
+
+    fn record_mode(&self) {
+        modes::set(Modes::LEFT_RIGHT_MARGINS, false);
+    }
+
+    #[cfg(windows)]
+    fn execute_winapi(&self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+/// A command that restricts scrolling, inserting and deleting to the columns from `left` to
+/// `right`, both 0-based and inclusive (DECSLRM).
+///
+/// Together with [`SetScrollRegion`](struct.SetScrollRegion.html) this scrolls a rectangular
+/// pane. The cursor is moved to the top left corner of the screen.
+///
+/// # Notes
+///
+/// * Commands must be executed/queued for execution otherwise they do nothing.
+/// * It only has an effect after [`EnableLeftRightMargins`](struct.EnableLeftRightMargins.html).
+/// * Margins aren't supported by WinAPI.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SetLeftRightMargins(pub u16, pub u16);
+
+impl Command for SetLeftRightMargins {
+    fn write_ansi(&self, f: &mut impl fmt::Write) -> fmt::Result {
+        write!(f, csi!("{};{}s"), self.0 + 1, self.1 + 1)
+    }
+
+    fn record_mode(&self) {
+        modes::set_left_right_margins((self.0, self.1));
+    }
+
+    #[cfg(windows)]
+    fn execute_winapi(&self) -> io::Result<()> {
+        Err(margins_unsupported())
+    }
+}
+
+#[cfg(windows)]
+fn margins_unsupported() -> io::Error {
+    io::Error::new(
+        io::ErrorKind::Unsupported,
+        "Left and right margins are not implemented for the Windows API.",
+    )
+}
+
+/// A command that inserts a given number of blank rows at the cursor row (IL).
+///
+/// The rows below move down within the scroll region, the ones moved past its bottom are lost.
+///
+/// # Notes
+///
+/// Commands must be executed/queued for execution otherwise they do nothing.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct InsertLines(pub u16);
+
+impl Command for InsertLines {
+    fn write_ansi(&self, f: &mut impl fmt::Write) -> fmt::Result {
+        if self.0 != 0 {
+            write!(f, csi!("{}L"), self.0)?;
+        }
+        Ok(())
+    }
+
+    #[cfg(windows)]
+    fn execute_winapi(&self) -> io::Result<()> {
+        sys::insert_lines(self.0)
+    }
+}
+
+/// A command that deletes a given number of rows starting at the cursor row (DL).
+///
+/// The rows below move up within the scroll region and blank rows are added at its bottom.
+///
+/// # Notes
+///
+/// Commands must be executed/queued for execution otherwise they do nothing.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DeleteLines(pub u16);
+
+impl Command for DeleteLines {
+    fn write_ansi(&self, f: &mut impl fmt::Write) -> fmt::Result {
+        if self.0 != 0 {
+            write!(f, csi!("{}M"), self.0)?;
+        }
+        Ok(())
+    }
+
+    #[cfg(windows)]
+    fn execute_winapi(&self) -> io::Result<()> {
+        sys::delete_lines(self.0)
+    }
+}
+
+/// A command that inserts a given number of blank cells at the cursor position (ICH).
+///
+/// The cells right of the cursor move to the right, the ones moved past the end of the row are
+/// lost.
+///
+/// # Notes
+///
+/// Commands must be executed/queued for execution otherwise they do nothing.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct InsertChars(pub u16);
+
+impl Command for InsertChars {
+    fn write_ansi(&self, f: &mut impl fmt::Write) -> fmt::Result {
+        if self.0 != 0 {
+            write!(f, csi!("{}@"), self.0)?;
+        }
+        Ok(())
+    }
+
+    #[cfg(windows)]
+    fn execute_winapi(&self) -> io::Result<()> {
+        sys::insert_chars(self.0)
+    }
+}
+
+/// A command that deletes a given number of cells starting at the cursor position (DCH).
+///
+/// The cells right of them move to the left and blank cells are added at the end of the row.
+///
+/// # Notes
+///
+/// Commands must be executed/queued for execution otherwise they do nothing.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DeleteChars(pub u16);
+
+impl Command for DeleteChars {
+    fn write_ansi(&self, f: &mut impl fmt::Write) -> fmt::Result {
+        if self.0 != 0 {
+            write!(f, csi!("{}P"), self.0)?;
+        }
+        Ok(())
+    }
+
+    #[cfg(windows)]
+    fn execute_winapi(&self) -> io::Result<()> {
+        sys::delete_chars(self.0)
+    }
+}
+
+/// A command that blanks a given number of cells starting at the cursor position (ECH), without
+/// moving the other cells or the cursor.
+///
+/// # Notes
+///
+/// Commands must be executed/queued for execution otherwise they do nothing.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EraseChars(pub u16);
+
+impl Command for EraseChars {
+    fn write_ansi(&self, f: &mut impl fmt::Write) -> fmt::Result {
+        if self.0 != 0 {
+            write!(f, csi!("{}X"), self.0)?;
+        }
+        Ok(())
+    }
+
+    #[cfg(windows)]
+    fn execute_winapi(&self) -> io::Result<()> {
+        sys::erase_chars(self.0)
+    }
+}
+
+/// A command that prints a character a given number of times.
+///
+/// The character is printed once and repeated with REP, unless printing it again is shorter.
+///
+/// # Notes
+///
+/// * Commands must be executed/queued for execution otherwise they do nothing.
+/// * The character should cover a single column, terminals disagree about repeating wide and
+///   combining characters.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RepeatChar(pub char, pub u16);
+
+impl Command for RepeatChar {
+    fn write_ansi(&self, f: &mut impl fmt::Write) -> fmt::Result {
+        let RepeatChar(c, count) = *self;
+        if count == 0 {
+            return Ok(());
+        }
+        f.write_char(c)?;
+
+        let repeats = count - 1;
+        // `ESC [ n b` has 3 bytes plus the digits.
+        let rep_len = 3 + repeats.to_string().len();
+        if c.len_utf8() * usize::from(repeats) <= rep_len {
+            for _ in 0..repeats {
+                f.write_char(c)?;
+            }
+            Ok(())
+        } else {
+            write!(f, csi!("{}b"), repeats)
+        }
+    }
+
+    #[cfg(windows)]
+    fn execute_winapi(&self) -> io::Result<()> {
+        sys::repeat_char(self.0, self.1)
+    }
+}
+
 /// A command that clears the terminal screen buffer.
 ///
 /// See the [`ClearType`](enum.ClearType.html) enum.
@@ -506,6 +1043,15 @@ impl_display!(for ScrollUp);
 impl_display!(for ScrollDown);
 impl_display!(for SetSize);
 impl_display!(for Clear);
+impl_display!(for SetScrollRegion);
+impl_display!(for ResetScrollRegion);
+impl_display!(for SetLeftRightMargins);
+impl_display!(for InsertLines);
+impl_display!(for DeleteLines);
+impl_display!(for InsertChars);
+impl_display!(for DeleteChars);
+impl_display!(for EraseChars);
+impl_display!(for RepeatChar);
 
 #[cfg(test)]
 mod tests {
@@ -537,6 +1083,31 @@ mod tests {
         assert_eq!((width, height), size().unwrap());
     }
 
+    #[test]
+    fn test_scroll_region_ansi() {
+        assert_eq!(SetScrollRegion(2, 10).to_string(), "\x1b[3;11r");
+        assert_eq!(ResetScrollRegion.to_string(), "\x1b[r");
+        assert_eq!(SetLeftRightMargins(0, 39).to_string(), "\x1b[1;40s");
+    }
+
+    #[test]
+    fn test_insert_delete_ansi() {
+        assert_eq!(InsertLines(3).to_string(), "\x1b[3L");
+        assert_eq!(DeleteLines(1).to_string(), "\x1b[1M");
+        assert_eq!(InsertChars(2).to_string(), "\x1b[2@");
+        assert_eq!(DeleteChars(4).to_string(), "\x1b[4P");
+        assert_eq!(EraseChars(5).to_string(), "\x1b[5X");
+        assert_eq!(DeleteLines(0).to_string(), "");
+    }
+
+    #[test]
+    fn test_repeat_char_ansi() {
+        assert_eq!(RepeatChar('-', 0).to_string(), "");
+        assert_eq!(RepeatChar('-', 5).to_string(), "-----");
+        assert_eq!(RepeatChar('-', 6).to_string(), "-\x1b[5b");
+        assert_eq!(RepeatChar('─', 3).to_string(), "─\x1b[2b");
+    }
+
     #[test]
     fn test_raw_mode() {
         // check we start from normal mode (may fail on some test harnesses)
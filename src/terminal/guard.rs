@@ -0,0 +1,363 @@
+use std::{
+    io, panic,
+    sync::{Arc, Once, Weak},
+};
+
+use parking_lot::Mutex;
+
+#[cfg(all(feature = "events", feature = "bracketed-paste"))]
+use crate::event::DisableBracketedPaste;
+#[cfg(feature = "events")]
+use crate::event::{DisableFocusChange, DisableMouseCapture, PopKeyboardEnhancementFlags};
+use crate::{
+    cursor::{RestorePosition, SavePosition, Show},
+    terminal::{
+        self,
+        modes::{self, Modes, RecordModes, TerminalModes},
+        DisableLeftRightMargins, LeaveAlternateScreen, ResetScrollRegion,
+    },
+    QueueableCommand,
+};
+
+/// A guard that restores the terminal when it's dropped or when the program panics.
+///
+/// The guard records the terminal modes changed by the commands queued through it (e.g. with
+/// [`execute!`](../macro.execute.html)) and the raw mode enabled with
+/// [`TerminalGuard::enable_raw_mode`], modes changed with other writers are left alone. When
+/// the guard is dropped, [restored](#method.restore) or the program panics, the recorded changes
+/// are undone in reverse order with these commands:
+///
+/// - [`LeaveAlternateScreen`](struct.LeaveAlternateScreen.html) for
+///   [`EnterAlternateScreen`](struct.EnterAlternateScreen.html)
+/// - [`Show`](../cursor/struct.Show.html) for [`Hide`](../cursor/struct.Hide.html)
+/// - [`ResetScrollRegion`](struct.ResetScrollRegion.html) for
+///   [`SetScrollRegion`](struct.SetScrollRegion.html), keeping the cursor position
+/// - [`DisableLeftRightMargins`](struct.DisableLeftRightMargins.html) for
+///   [`EnableLeftRightMargins`](struct.EnableLeftRightMargins.html)
+/// - [`DisableMouseCapture`](../event/struct.DisableMouseCapture.html),
+///   [`DisableFocusChange`](../event/struct.DisableFocusChange.html),
+///   [`DisableBracketedPaste`](../event/struct.DisableBracketedPaste.html) and
+///   [`PopKeyboardEnhancementFlags`](../event/struct.PopKeyboardEnhancementFlags.html) for the
+///   commands enabling them
+/// - [`disable_raw_mode`](fn.disable_raw_mode.html) for the raw mode
+///
+/// Modes disabled through the guard before are not disabled again.
+///
+/// Creating the first guard installs a panic hook that restores the terminal of all guards,
+/// newest first, and then calls the previous panic hook, so the panic message is printed to
+/// the restored terminal.
+///
+/// # Examples
+///
+/// ```no_run
+/// use std::io;
+///
+/// use crossterm::{
+///     cursor::Hide,
+///     execute,
+///     terminal::{EnterAlternateScreen, TerminalGuard},
+/// };
+///
+/// fn main() -> io::Result<()> {
+///     let mut guard = TerminalGuard::new();
+///     guard.enable_raw_mode()?;
+///     execute!(guard, EnterAlternateScreen, Hide)?;
+///
+///     // Draw and handle events, the terminal is restored on return and on panic.
+///
+///     Ok(())
+/// }
+/// ```
+pub struct TerminalGuard<W: io::Write + Send + 'static = io::Stdout> {
+    inner: Arc<Mutex<Inner<W>>>,
+}
+
+struct Inner<W> {
+    writer: W,
+    changes: Vec<Change>,
+}
+
+/// A change to undo when restoring the terminal.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Change {
+    RawMode,
+    Mode(Modes),
+    KeyboardEnhancementFlags,
+}
+
+impl TerminalGuard<io::Stdout> {
+    /// Creates a guard writing to the standard output.
+    pub fn new() -> TerminalGuard<io::Stdout> {
+        TerminalGuard::with_writer(io::stdout())
+    }
+}
+
+impl Default for TerminalGuard<io::Stdout> {
+    fn default() -> Self {
+        TerminalGuard::new()
+    }
+}
+
+impl<W: io::Write + Send + 'static> TerminalGuard<W> {
+    /// Creates a guard writing to the given writer.
+    pub fn with_writer(writer: W) -> TerminalGuard<W> {
+        let inner = Arc::new(Mutex::new(Inner {
+            writer,
+            changes: Vec::new(),
+        }));
+        register(Arc::downgrade(&inner) as Weak<dyn Restore>);
+        TerminalGuard { inner }
+    }
+
+    /// Enables raw mode, it's disabled again when the terminal is restored.
+    ///
+    /// Nothing is recorded if raw mode is enabled already.
+    pub fn enable_raw_mode(&mut self) -> io::Result<()> {
+        let mut inner = self.inner.lock();
+        if terminal::is_raw_mode_enabled()? {
+            return Ok(());
+        }
+        terminal::enable_raw_mode()?;
+        inner.changes.push(Change::RawMode);
+        Ok(())
+    }
+
+    /// Undoes the recorded changes in reverse order.
+    ///
+    /// The guard can be used afterwards, only the changes made after this call are undone when
+    /// it's dropped.
+    pub fn restore(&mut self) -> io::Result<()> {
+        self.inner.lock().restore()
+    }
+}
+
+impl<W: io::Write + Send + 'static> io::Write for TerminalGuard<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        modes::set_recorder(Arc::downgrade(&self.inner) as Weak<dyn RecordModes>);
+        self.inner.lock().writer.write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        // Commands not supported by the console flush the writer instead of writing to it.
+        modes::set_recorder(Arc::downgrade(&self.inner) as Weak<dyn RecordModes>);
+        self.inner.lock().writer.flush()
+    }
+}
+
+impl<W: io::Write + Send + 'static> Drop for TerminalGuard<W> {
+    fn drop(&mut self) {
+        let _ = self.inner.lock().restore();
+    }
+}
+
+impl<W: io::Write> RecordModes for Mutex<Inner<W>> {
+    fn record(&self, before: &TerminalModes, after: &TerminalModes) {
+        self.lock().record(before, after);
+    }
+}
+
+impl<W: io::Write> Inner<W> {
+    /// Records the changes of the modes made by a command queued through the guard.
+    fn record(&mut self, before: &TerminalModes, after: &TerminalModes) {
+        let (modes_before, modes_after) = (before.modes(), after.modes());
+        for mode in (modes_after - modes_before).iter() {
+            self.changes.push(Change::Mode(mode));
+        }
+        for mode in (modes_before - modes_after).iter() {
+            self.remove(Change::Mode(mode));
+        }
+
+        let (flags_before, flags_after) = (
+            before.keyboard_enhancement_flags().len(),
+            after.keyboard_enhancement_flags().len(),
+        );
+        for _ in flags_before..flags_after {
+            self.changes.push(Change::KeyboardEnhancementFlags);
+        }
+        for _ in flags_after..flags_before {
+            self.remove(Change::KeyboardEnhancementFlags);
+        }
+    }
+
+    fn remove(&mut self, change: Change) {
+        if let Some(index) = self.changes.iter().rposition(|c| *c == change) {
+            self.changes.remove(index);
+        }
+    }
+
+    /// Undoes all changes, returning the first error after trying to undo the others.
+    fn restore(&mut self) -> io::Result<()> {
+        let mut result = Ok(());
+        while let Some(change) = self.changes.pop() {
+            let undone = match change {
+                Change::RawMode => self
+                    .writer
+                    .flush()
+                    .and_then(|_| terminal::disable_raw_mode()),
+                Change::Mode(mode) => self.undo_mode(mode),
+                #[cfg(feature = "events")]
+                Change::KeyboardEnhancementFlags => {
+                    self.writer.queue(PopKeyboardEnhancementFlags).map(|_| ())
+                }
+                #[cfg(not(feature = "events"))]
+                Change::KeyboardEnhancementFlags => Ok(()),
+            };
+            if result.is_ok() {
+                result = undone;
+            }
+        }
+        let flushed = self.writer.flush();
+        result.and(flushed)
+    }
+
+    fn undo_mode(&mut self, mode: Modes) -> io::Result<()> {
+        if mode == Modes::ALTERNATE_SCREEN {
+            self.writer.queue(LeaveAlternateScreen)?;
+        } else if mode == Modes::HIDDEN_CURSOR {
+            self.writer.queue(Show)?;
+        } else if mode == Modes::SCROLL_REGION {
+            // Resetting the region moves the cursor to the top left corner.
+            self.writer
+                .queue(SavePosition)?
+                .queue(ResetScrollRegion)?
+                .queue(RestorePosition)?;
+        } else if mode == Modes::LEFT_RIGHT_MARGINS {
+            self.writer.queue(DisableLeftRightMargins)?;
+        }
+        #[cfg(feature = "events")]
+        if mode == Modes::MOUSE_CAPTURE {
+            self.writer.queue(DisableMouseCapture)?;
+        } else if mode == Modes::FOCUS_CHANGE {
+            self.writer.queue(DisableFocusChange)?;
+        }
+        #[cfg(all(feature = "events", feature = "bracketed-paste"))]
+        if mode == Modes::BRACKETED_PASTE {
+            self.writer.queue(DisableBracketedPaste)?;
+        }
+        Ok(())
+    }
+}
+
+/// The guards restored by the panic hook.
+static GUARDS: Mutex<Vec<Weak<dyn Restore>>> = parking_lot::const_mutex(Vec::new());
+
+trait Restore: Send + Sync {
+    fn restore_on_panic(&self);
+}
+
+impl<W: io::Write + Send> Restore for Mutex<Inner<W>> {
+    fn restore_on_panic(&self) {
+        // The guard is locked if the panic happened while writing to it.
+        if let Some(mut inner) = self.try_lock() {
+            let _ = inner.restore();
+        }
+    }
+}
+
+fn register(guard: Weak<dyn Restore>) {
+    static INSTALL_HOOK: Once = Once::new();
+    INSTALL_HOOK.call_once(|| {
+        let previous = panic::take_hook();
+        panic::set_hook(Box::new(move |info| {
+            restore_all();
+            previous(info);
+        }));
+    });
+
+    let mut guards = GUARDS.lock();
+    guards.retain(|guard| guard.strong_count() > 0);
+    guards.push(guard);
+}
+
+fn restore_all() {
+    let guards: Vec<_> = match GUARDS.try_lock() {
+        Some(guards) => guards.iter().rev().filter_map(Weak::upgrade).collect(),
+        None => return,
+    };
+    for guard in guards {
+        guard.restore_on_panic();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        io::{self, Write},
+        sync::Arc,
+    };
+
+    use parking_lot::Mutex;
+
+    use crate::{
+        cursor::{Hide, Show},
+        execute, queue,
+        terminal::{EnterAlternateScreen, LeaveAlternateScreen},
+        Command,
+    };
+
+    use super::TerminalGuard;
+
+    #[derive(Clone, Default)]
+    struct SharedWriter(Arc<Mutex<Vec<u8>>>);
+
+    impl io::Write for SharedWriter {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.0.lock().write(buf)
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    impl SharedWriter {
+        fn take(&self) -> String {
+            String::from_utf8(std::mem::take(&mut *self.0.lock())).unwrap()
+        }
+    }
+
+    #[test]
+    fn test_restore_in_reverse_order() {
+        let writer = SharedWriter::default();
+        let mut guard = TerminalGuard::with_writer(writer.clone());
+
+        execute!(guard, EnterAlternateScreen, Hide).unwrap();
+        writer.take();
+        guard.restore().unwrap();
+        assert_eq!(writer.take(), "\x1B[?25h\x1B[?1049l");
+
+        // Nothing left to restore.
+        guard.restore().unwrap();
+        assert_eq!(writer.take(), "");
+
+        // Modes disabled through the guard aren't disabled again.
+        execute!(guard, Hide, EnterAlternateScreen, Show).unwrap();
+        writer.take();
+        drop(guard);
+        assert_eq!(writer.take(), "\x1B[?1049l");
+
+        // Queued commands are recorded without a flush, formatted ones aren't recorded.
+        let mut guard = TerminalGuard::with_writer(writer.clone());
+        EnterAlternateScreen.write_ansi(&mut String::new()).unwrap();
+        queue!(guard, Hide).unwrap();
+        guard.restore().unwrap();
+        assert_eq!(writer.take(), "\x1B[?25l\x1B[?25h");
+    }
+
+    #[test]
+    fn test_restore_only_changes_queued_through_the_guard() {
+        let writer = SharedWriter::default();
+        let mut guard = TerminalGuard::with_writer(writer.clone());
+        let mut other = SharedWriter::default();
+
+        queue!(guard, Hide).unwrap();
+        execute!(other, EnterAlternateScreen).unwrap();
+        write!(guard, "text").unwrap();
+        guard.flush().unwrap();
+        assert_eq!(writer.take(), "\x1B[?25ltext");
+
+        drop(guard);
+        assert_eq!(writer.take(), "\x1B[?25h");
+        execute!(other, LeaveAlternateScreen).unwrap();
+    }
+}
@@ -0,0 +1,320 @@
+//! Keeps track of the terminal modes enabled with crossterm's commands.
+//!
+//! The commands record themselves when they are queued or executed, not when they are only
+//! formatted, so that the modes can be disabled when the process is suspended and enabled again
+//! when it continues.
+//!
+//! A writer can also record the changes of the commands queued through it, like the
+//! `TerminalGuard`, by calling [`set_recorder`] while the commands are written to it.
+
+use std::{
+    cell::{Cell, RefCell},
+    fmt, io,
+    sync::Weak,
+};
+
+use bitflags::bitflags;
+use parking_lot::Mutex;
+
+#[cfg(all(feature = "events", feature = "bracketed-paste"))]
+use crate::event::{DisableBracketedPaste, EnableBracketedPaste};
+#[cfg(feature = "events")]
+use crate::event::{
+    DisableFocusChange, DisableMouseCapture, EnableFocusChange, EnableMouseCapture,
+    KeyboardEnhancementFlags, PopKeyboardEnhancementFlags, PushKeyboardEnhancementFlags,
+};
+use crate::{
+    cursor::{Hide, RestorePosition, SavePosition, Show},
+    terminal::{
+        DisableLeftRightMargins, EnableLeftRightMargins, EnterAlternateScreen,
+        LeaveAlternateScreen, ResetScrollRegion, SetLeftRightMargins, SetScrollRegion,
+    },
+    Command,
+};
+
+bitflags! {
+    /// Terminal modes that can be enabled with crossterm's commands.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub(crate) struct Modes: u8 {
+        const ALTERNATE_SCREEN = 0b0000_0001;
+        const HIDDEN_CURSOR = 0b0000_0010;
+        const MOUSE_CAPTURE = 0b0000_0100;
+        const FOCUS_CHANGE = 0b0000_1000;
+        const BRACKETED_PASTE = 0b0001_0000;
+        const SCROLL_REGION = 0b0010_0000;
+        const LEFT_RIGHT_MARGINS = 0b0100_0000;
+    }
+}
+
+/// The terminal modes enabled by the commands written so far.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct TerminalModes {
+    modes: Modes,
+    // The bits of the pushed keyboard enhancement flags, the last pushed flags last.
+    keyboard_enhancement_flags: Vec<u8>,
+    // The rows (top, bottom) of the scroll region, set together with `Modes::SCROLL_REGION`.
+    scroll_region: Option<(u16, u16)>,
+    // The columns (left, right) of the margins set while `Modes::LEFT_RIGHT_MARGINS` is enabled.
+    left_right_margins: Option<(u16, u16)>,
+}
+
+static TERMINAL_MODES: Mutex<TerminalModes> = parking_lot::const_mutex(TerminalModes {
+    modes: Modes::empty(),
+    keyboard_enhancement_flags: Vec::new(),
+    scroll_region: None,
+    left_right_margins: None,
+});
+
+/// Records the changes of the modes made by the commands queued through a writer.
+pub(crate) trait RecordModes {
+    /// Called with the modes before and after a command queued through the writer.
+    fn record(&self, before: &TerminalModes, after: &TerminalModes);
+}
+
+thread_local! {
+    // The recorder of the writer the command queued on this thread was written to.
+    static RECORDER: RefCell<Option<Weak<dyn RecordModes>>> = const { RefCell::new(None) };
+    // Set while the mode of a command queued through a recorder is recorded.
+    static RECORDING: Cell<bool> = const { Cell::new(false) };
+    // The modes before and after the changes made while recording.
+    static RECORDED: RefCell<Option<(TerminalModes, TerminalModes)>> = const { RefCell::new(None) };
+}
+
+/// Passes the changes of the command being queued on this thread to `recorder`, called by the
+/// writer the command is written to.
+pub(crate) fn set_recorder(recorder: Weak<dyn RecordModes>) {
+    RECORDER.with(|current| *current.borrow_mut() = Some(recorder));
+}
+
+/// Writes a command with `write` and records its mode with `record_mode`, the changes are
+/// passed to the recorder of the writer if it set one.
+pub(crate) fn queue_command(
+    write: impl FnOnce() -> io::Result<()>,
+    record_mode: impl FnOnce(),
+) -> io::Result<()> {
+    // Left by a write that didn't queue a command.
+    RECORDER.with(|current| current.borrow_mut().take());
+    write()?;
+    let recorder = match RECORDER.with(|current| current.borrow_mut().take()) {
+        Some(recorder) => recorder,
+        None => {
+            record_mode();
+            return Ok(());
+        }
+    };
+
+    RECORDING.with(|recording| recording.set(true));
+    record_mode();
+    RECORDING.with(|recording| recording.set(false));
+    if let (Some(recorder), Some((before, after))) = (
+        recorder.upgrade(),
+        RECORDED.with(|recorded| recorded.borrow_mut().take()),
+    ) {
+        recorder.record(&before, &after);
+    }
+    Ok(())
+}
+
+/// Changes the modes, the changes are kept if they're recorded on this thread.
+fn update(f: impl FnOnce(&mut TerminalModes)) {
+    let mut terminal_modes = TERMINAL_MODES.lock();
+    if !RECORDING.with(Cell::get) {
+        f(&mut terminal_modes);
+        return;
+    }
+
+    let before = terminal_modes.clone();
+    f(&mut terminal_modes);
+    let after = terminal_modes.clone();
+    RECORDED.with(|recorded| match &mut *recorded.borrow_mut() {
+        Some((_, recorded_after)) => *recorded_after = after,
+        recorded => *recorded = Some((before, after)),
+    });
+}
+
+pub(crate) fn set(modes: Modes, enabled: bool) {
+    update(|terminal_modes| {
+        terminal_modes.modes.set(modes, enabled);
+        if modes.contains(Modes::LEFT_RIGHT_MARGINS) && !enabled {
+            // Disabling the mode resets the margins.
+            terminal_modes.left_right_margins = None;
+        }
+    });
+}
+
+pub(crate) fn set_scroll_region(region: Option<(u16, u16)>) {
+    update(|terminal_modes| {
+        terminal_modes
+            .modes
+            .set(Modes::SCROLL_REGION, region.is_some());
+        terminal_modes.scroll_region = region;
+    });
+}
+
+pub(crate) fn set_left_right_margins(margins: (u16, u16)) {
+    update(|terminal_modes| {
+        // The margins can't be set while the mode is disabled.
+        if terminal_modes.modes.contains(Modes::LEFT_RIGHT_MARGINS) {
+            terminal_modes.left_right_margins = Some(margins);
+        }
+    });
+}
+
+#[cfg(feature = "events")]
+pub(crate) fn push_keyboard_enhancement_flags(bits: u8) {
+    update(|terminal_modes| terminal_modes.keyboard_enhancement_flags.push(bits));
+}
+
+#[cfg(feature = "events")]
+pub(crate) fn pop_keyboard_enhancement_flags() {
+    update(|terminal_modes| {
+        terminal_modes.keyboard_enhancement_flags.pop();
+    });
+}
+
+#[cfg_attr(not(all(unix, feature = "events")), allow(dead_code))]
+pub(crate) fn current() -> TerminalModes {
+    TERMINAL_MODES.lock().clone()
+}
+
+#[cfg_attr(not(all(unix, feature = "events")), allow(dead_code))]
+impl TerminalModes {
+    pub(crate) fn modes(&self) -> Modes {
+        self.modes
+    }
+
+    pub(crate) fn keyboard_enhancement_flags(&self) -> &[u8] {
+        &self.keyboard_enhancement_flags
+    }
+
+    /// Writes the commands that disable the modes, in reverse order of enabling them.
+    pub(crate) fn write_disable(&self, f: &mut impl fmt::Write) -> fmt::Result {
+        #[cfg(feature = "events")]
+        {
+            // The flags are kept per screen, pop them before leaving the alternate screen.
+            for _ in &self.keyboard_enhancement_flags {
+                PopKeyboardEnhancementFlags.write_ansi(f)?;
+            }
+            #[cfg(feature = "bracketed-paste")]
+            if self.modes.contains(Modes::BRACKETED_PASTE) {
+                DisableBracketedPaste.write_ansi(f)?;
+            }
+            if self.modes.contains(Modes::FOCUS_CHANGE) {
+                DisableFocusChange.write_ansi(f)?;
+            }
+            if self.modes.contains(Modes::MOUSE_CAPTURE) {
+                DisableMouseCapture.write_ansi(f)?;
+            }
+        }
+        if self.modes.contains(Modes::LEFT_RIGHT_MARGINS) {
+            DisableLeftRightMargins.write_ansi(f)?;
+        }
+        if self.modes.contains(Modes::SCROLL_REGION) {
+            // Resetting the region moves the cursor to the top left corner.
+            SavePosition.write_ansi(f)?;
+            ResetScrollRegion.write_ansi(f)?;
+            RestorePosition.write_ansi(f)?;
+        }
+        if self.modes.contains(Modes::HIDDEN_CURSOR) {
+            Show.write_ansi(f)?;
+        }
+        if self.modes.contains(Modes::ALTERNATE_SCREEN) {
+            LeaveAlternateScreen.write_ansi(f)?;
+        }
+        Ok(())
+    }
+
+    /// Writes the commands that enable the modes again.
+    pub(crate) fn write_enable(&self, f: &mut impl fmt::Write) -> fmt::Result {
+        if self.modes.contains(Modes::ALTERNATE_SCREEN) {
+            EnterAlternateScreen.write_ansi(f)?;
+        }
+        if self.modes.contains(Modes::HIDDEN_CURSOR) {
+            Hide.write_ansi(f)?;
+        }
+        if self
+            .modes
+            .intersects(Modes::SCROLL_REGION | Modes::LEFT_RIGHT_MARGINS)
+        {
+            // Setting the region and the margins moves the cursor to the top left corner.
+            SavePosition.write_ansi(f)?;
+            if let Some((top, bottom)) = self.scroll_region {
+                SetScrollRegion(top, bottom).write_ansi(f)?;
+            }
+            if self.modes.contains(Modes::LEFT_RIGHT_MARGINS) {
+                EnableLeftRightMargins.write_ansi(f)?;
+                if let Some((left, right)) = self.left_right_margins {
+                    SetLeftRightMargins(left, right).write_ansi(f)?;
+                }
+            }
+            RestorePosition.write_ansi(f)?;
+        }
+        #[cfg(feature = "events")]
+        {
+            if self.modes.contains(Modes::MOUSE_CAPTURE) {
+                EnableMouseCapture.write_ansi(f)?;
+            }
+            if self.modes.contains(Modes::FOCUS_CHANGE) {
+                EnableFocusChange.write_ansi(f)?;
+            }
+            #[cfg(feature = "bracketed-paste")]
+            if self.modes.contains(Modes::BRACKETED_PASTE) {
+                EnableBracketedPaste.write_ansi(f)?;
+            }
+            for bits in &self.keyboard_enhancement_flags {
+                PushKeyboardEnhancementFlags(KeyboardEnhancementFlags::from_bits_retain(*bits))
+                    .write_ansi(f)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Modes, TerminalModes};
+
+    #[test]
+    fn test_write_disable_and_enable() {
+        let modes = TerminalModes {
+            modes: Modes::ALTERNATE_SCREEN | Modes::HIDDEN_CURSOR,
+            keyboard_enhancement_flags: vec![1],
+            scroll_region: None,
+            left_right_margins: None,
+        };
+
+        let mut disable = String::new();
+        modes.write_disable(&mut disable).unwrap();
+        let mut enable = String::new();
+        modes.write_enable(&mut enable).unwrap();
+
+        #[cfg(feature = "events")]
+        {
+            assert_eq!(disable, "\x1B[<1u\x1B[?25h\x1B[?1049l");
+            assert_eq!(enable, "\x1B[?1049h\x1B[?25l\x1B[>1u");
+        }
+        #[cfg(not(feature = "events"))]
+        {
+            assert_eq!(disable, "\x1B[?25h\x1B[?1049l");
+            assert_eq!(enable, "\x1B[?1049h\x1B[?25l");
+        }
+    }
+
+    #[test]
+    fn test_write_scroll_region_and_margins() {
+        let modes = TerminalModes {
+            modes: Modes::SCROLL_REGION | Modes::LEFT_RIGHT_MARGINS,
+            keyboard_enhancement_flags: Vec::new(),
+            scroll_region: Some((2, 10)),
+            left_right_margins: Some((0, 39)),
+        };
+
+        let mut disable = String::new();
+        modes.write_disable(&mut disable).unwrap();
+        let mut enable = String::new();
+        modes.write_enable(&mut enable).unwrap();
+
+        assert_eq!(disable, "\x1B[?69l\x1B7\x1B[r\x1B8");
+        assert_eq!(enable, "\x1B7\x1B[3;11r\x1B[?69h\x1B[1;40s\x1B8");
+    }
+}
@@ -1,22 +1,28 @@
 //! This module provides platform related functions.
 
-#[cfg(unix)]
-#[cfg(feature = "events")]
-pub use self::unix::supports_keyboard_enhancement;
 #[cfg(unix)]
 pub(crate) use self::unix::{
-    disable_raw_mode, enable_raw_mode, is_raw_mode_enabled, size, window_size,
+    disable_raw_mode, drain_output, enable_raw_mode, enable_raw_mode_with, flush_input,
+    is_raw_mode_enabled, size, window_size,
 };
-#[cfg(windows)]
+#[cfg(unix)]
 #[cfg(feature = "events")]
-pub use self::windows::supports_keyboard_enhancement;
+pub use self::unix::{query_grapheme_clustering, supports_keyboard_enhancement};
+#[cfg(unix)]
+#[cfg(feature = "events")]
+pub(crate) use self::unix::{raw_mode_options, refresh_raw_mode, suspend};
 #[cfg(all(windows, test))]
 pub(crate) use self::windows::temp_screen_buffer;
 #[cfg(windows)]
 pub(crate) use self::windows::{
-    clear, disable_raw_mode, enable_raw_mode, is_raw_mode_enabled, scroll_down, scroll_up,
-    set_size, set_window_title, size, window_size,
+    clear, delete_chars, delete_lines, disable_raw_mode, drain_output, enable_raw_mode,
+    enable_raw_mode_with, erase_chars, flush_input, insert_chars, insert_lines,
+    is_raw_mode_enabled, repeat_char, scroll_down, scroll_up, set_scroll_region, set_size,
+    set_window_title, size, window_size,
 };
+#[cfg(windows)]
+#[cfg(feature = "events")]
+pub use self::windows::{query_grapheme_clustering, supports_keyboard_enhancement};
 
 #[cfg(windows)]
 mod windows;
@@ -1,19 +1,25 @@
 //! UNIX related logic for terminal manipulation.
 
+#[cfg(feature = "events")]
+use crate::terminal::modes;
+#[cfg(feature = "events")]
+use crate::terminal::ModeSetting;
 use crate::terminal::{
     sys::file_descriptor::{tty_fd, FileDesc},
-    WindowSize,
+    RawModeOptions, WindowSize,
 };
 #[cfg(feature = "libc")]
 use libc::{
-    cfmakeraw, ioctl, tcgetattr, tcsetattr, termios as Termios, winsize, STDOUT_FILENO, TCSANOW,
-    TIOCGWINSZ,
+    cfmakeraw, ioctl, tcdrain, tcflush, tcgetattr, tcsetattr, termios as Termios, winsize, ICRNL,
+    ISIG, IXON, OPOST, STDOUT_FILENO, TCIFLUSH, TCSANOW, TIOCGWINSZ, VMIN, VTIME,
 };
 use parking_lot::Mutex;
 #[cfg(not(feature = "libc"))]
 use rustix::{
     fd::AsFd,
-    termios::{Termios, Winsize},
+    termios::{
+        InputModes, LocalModes, OutputModes, QueueSelector, SpecialCodeIndex, Termios, Winsize,
+    },
 };
 
 use std::{fs::File, io, process};
@@ -23,14 +29,25 @@ use std::{
     os::unix::io::{IntoRawFd, RawFd},
 };
 
-// Some(Termios) -> we're in the raw mode and this is the previous mode
+// Some((Termios, RawModeOptions)) -> we're in the raw mode, this is the previous mode and the
+// options the raw mode was enabled with
 // None -> we're not in the raw mode
-static TERMINAL_MODE_PRIOR_RAW_MODE: Mutex<Option<Termios>> = parking_lot::const_mutex(None);
+static TERMINAL_MODE_PRIOR_RAW_MODE: Mutex<Option<(Termios, RawModeOptions)>> =
+    parking_lot::const_mutex(None);
 
 pub(crate) fn is_raw_mode_enabled() -> bool {
     TERMINAL_MODE_PRIOR_RAW_MODE.lock().is_some()
 }
 
+/// Returns the options of the raw mode, `None` if it's disabled.
+#[cfg(feature = "events")]
+pub(crate) fn raw_mode_options() -> Option<RawModeOptions> {
+    TERMINAL_MODE_PRIOR_RAW_MODE
+        .lock()
+        .as_ref()
+        .map(|(_, options)| *options)
+}
+
 #[cfg(feature = "libc")]
 impl From<winsize> for WindowSize {
     fn from(size: winsize) -> WindowSize {
@@ -102,38 +119,45 @@ pub(crate) fn size() -> io::Result<(u16, u16)> {
     tput_size().ok_or_else(|| std::io::Error::last_os_error().into())
 }
 
-#[cfg(feature = "libc")]
 pub(crate) fn enable_raw_mode() -> io::Result<()> {
-    let mut original_mode = TERMINAL_MODE_PRIOR_RAW_MODE.lock();
-    if original_mode.is_some() {
+    if is_raw_mode_enabled() {
         return Ok(());
     }
+    enable_raw_mode_with(RawModeOptions::default())
+}
+
+#[cfg(feature = "libc")]
+pub(crate) fn enable_raw_mode_with(options: RawModeOptions) -> io::Result<()> {
+    let mut original_mode = TERMINAL_MODE_PRIOR_RAW_MODE.lock();
 
     let tty = tty_fd()?;
     let fd = tty.raw_fd();
-    let mut ios = get_terminal_attr(fd)?;
-    let original_mode_ios = ios;
-    raw_terminal_attr(&mut ios);
+    let original_mode_ios = match original_mode.as_ref() {
+        Some((original_mode_ios, _)) => *original_mode_ios,
+        None => get_terminal_attr(fd)?,
+    };
+    let mut ios = original_mode_ios;
+    raw_terminal_attr(&mut ios, &options);
     set_terminal_attr(fd, &ios)?;
     // Keep it last - set the original mode only if we were able to switch to the raw mode
-    *original_mode = Some(original_mode_ios);
+    *original_mode = Some((original_mode_ios, options));
     Ok(())
 }
 
 #[cfg(not(feature = "libc"))]
-pub(crate) fn enable_raw_mode() -> io::Result<()> {
+pub(crate) fn enable_raw_mode_with(options: RawModeOptions) -> io::Result<()> {
     let mut original_mode = TERMINAL_MODE_PRIOR_RAW_MODE.lock();
-    if original_mode.is_some() {
-        return Ok(());
-    }
 
     let tty = tty_fd()?;
-    let mut ios = get_terminal_attr(&tty)?;
-    let original_mode_ios = ios.clone();
-    ios.make_raw();
+    let original_mode_ios = match original_mode.as_ref() {
+        Some((original_mode_ios, _)) => original_mode_ios.clone(),
+        None => get_terminal_attr(&tty)?,
+    };
+    let mut ios = original_mode_ios.clone();
+    raw_terminal_attr(&mut ios, &options);
     set_terminal_attr(&tty, &ios)?;
     // Keep it last - set the original mode only if we were able to switch to the raw mode
-    *original_mode = Some(original_mode_ios);
+    *original_mode = Some((original_mode_ios, options));
     Ok(())
 }
 
@@ -145,7 +169,7 @@ pub(crate) fn enable_raw_mode() -> io::Result<()> {
 #[cfg(feature = "libc")]
 pub(crate) fn disable_raw_mode() -> io::Result<()> {
     let mut original_mode = TERMINAL_MODE_PRIOR_RAW_MODE.lock();
-    if let Some(original_mode_ios) = original_mode.as_ref() {
+    if let Some((original_mode_ios, _)) = original_mode.as_ref() {
         let tty = tty_fd()?;
         set_terminal_attr(tty.raw_fd(), original_mode_ios)?;
         // Keep it last - remove the original mode only if we were able to switch back
@@ -157,7 +181,7 @@ pub(crate) fn disable_raw_mode() -> io::Result<()> {
 #[cfg(not(feature = "libc"))]
 pub(crate) fn disable_raw_mode() -> io::Result<()> {
     let mut original_mode = TERMINAL_MODE_PRIOR_RAW_MODE.lock();
-    if let Some(original_mode_ios) = original_mode.as_ref() {
+    if let Some((original_mode_ios, _)) = original_mode.as_ref() {
         let tty = tty_fd()?;
         set_terminal_attr(&tty, original_mode_ios)?;
         // Keep it last - remove the original mode only if we were able to switch back
@@ -166,6 +190,95 @@ pub(crate) fn disable_raw_mode() -> io::Result<()> {
     Ok(())
 }
 
+#[cfg(feature = "libc")]
+pub(crate) fn flush_input() -> io::Result<()> {
+    let tty = tty_fd()?;
+    wrap_with_result(unsafe { tcflush(tty.raw_fd(), TCIFLUSH) })
+}
+
+#[cfg(not(feature = "libc"))]
+pub(crate) fn flush_input() -> io::Result<()> {
+    let tty = tty_fd()?;
+    rustix::termios::tcflush(&tty, QueueSelector::IFlush)?;
+    Ok(())
+}
+
+#[cfg(feature = "libc")]
+pub(crate) fn drain_output() -> io::Result<()> {
+    let tty = tty_fd()?;
+    wrap_with_result(unsafe { tcdrain(tty.raw_fd()) })
+}
+
+#[cfg(not(feature = "libc"))]
+pub(crate) fn drain_output() -> io::Result<()> {
+    let tty = tty_fd()?;
+    rustix::termios::tcdrain(&tty)?;
+    Ok(())
+}
+
+/// Switches the terminal to the raw mode again if it's enabled, e.g. after the shell changed the
+/// mode while the process was stopped.
+#[cfg(feature = "events")]
+pub(crate) fn refresh_raw_mode() -> io::Result<()> {
+    match raw_mode_options() {
+        Some(options) => enable_raw_mode_with(options),
+        None => Ok(()),
+    }
+}
+
+/// Stops the process like the default `SIGTSTP` handler does, disabling the terminal modes
+/// enabled with crossterm before and enabling them again after the process is continued.
+#[cfg(feature = "events")]
+pub(crate) fn suspend() -> io::Result<()> {
+    let modes = modes::current();
+    let raw_mode = raw_mode_options();
+
+    let mut commands = String::new();
+    modes.write_disable(&mut commands).map_err(fmt_error)?;
+    write_to_tty(commands.as_bytes())?;
+    if raw_mode.is_some() {
+        disable_raw_mode()?;
+    }
+
+    // Stops the process until it receives SIGCONT.
+    signal_hook::low_level::emulate_default_handler(signal_hook::consts::SIGTSTP)?;
+
+    if let Some(options) = raw_mode {
+        enable_raw_mode_with(options)?;
+    }
+    let mut commands = String::new();
+    modes.write_enable(&mut commands).map_err(fmt_error)?;
+    write_to_tty(commands.as_bytes())
+}
+
+#[cfg(feature = "events")]
+fn fmt_error(_: std::fmt::Error) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::Other,
+        "Failed to format the terminal commands.",
+    )
+}
+
+/// Writes to the terminal, falling back to the standard output if there's no `/dev/tty`.
+#[cfg(feature = "events")]
+fn write_to_tty(bytes: &[u8]) -> io::Result<()> {
+    use std::io::Write;
+
+    let result = File::options()
+        .write(true)
+        .open("/dev/tty")
+        .and_then(|mut file| {
+            file.write_all(bytes)?;
+            file.flush()
+        });
+    if result.is_err() {
+        let mut stdout = io::stdout();
+        stdout.write_all(bytes)?;
+        stdout.flush()?;
+    }
+    Ok(())
+}
+
 #[cfg(not(feature = "libc"))]
 fn get_terminal_attr(fd: impl AsFd) -> io::Result<Termios> {
     let result = rustix::termios::tcgetattr(fd)?;
@@ -182,8 +295,12 @@ fn set_terminal_attr(fd: impl AsFd, termios: &Termios) -> io::Result<()> {
 ///
 /// On unix systems, this function will block and possibly time out while
 /// [`crossterm::event::read`](crate::event::read) or [`crossterm::event::poll`](crate::event::poll) are being called.
+/// Use
+/// [`EventStream::supports_keyboard_enhancement`](../event/struct.EventStream.html#method.supports_keyboard_enhancement)
+/// in async code.
 #[cfg(feature = "events")]
 pub fn supports_keyboard_enhancement() -> io::Result<bool> {
+    crate::event::check_blocking_read()?;
     if is_raw_mode_enabled() {
         read_supports_keyboard_enhancement_raw()
     } else {
@@ -203,9 +320,10 @@ fn read_supports_keyboard_enhancement_flags() -> io::Result<bool> {
 fn read_supports_keyboard_enhancement_raw() -> io::Result<bool> {
     use crate::event::{
         filter::{KeyboardEnhancementFlagsFilter, PrimaryDeviceAttributesFilter},
-        poll_internal, read_internal, InternalEvent,
+        poll_internal, read_internal,
+        timeout::PollTimeout,
+        InternalEvent,
     };
-    use std::io::Write;
     use std::time::Duration;
 
     // This is the recommended method for testing support for the keyboard enhancement protocol.
@@ -219,21 +337,11 @@ fn read_supports_keyboard_enhancement_raw() -> io::Result<bool> {
     // ESC [ c          Query primary device attributes.
     const QUERY: &[u8] = b"\x1B[?u\x1B[c";
 
-    let result = File::open("/dev/tty").and_then(|mut file| {
-        file.write_all(QUERY)?;
-        file.flush()
-    });
-    if result.is_err() {
-        let mut stdout = io::stdout();
-        stdout.write_all(QUERY)?;
-        stdout.flush()?;
-    }
+    write_query(QUERY)?;
 
+    let timeout = PollTimeout::new(Some(Duration::from_millis(2000)));
     loop {
-        match poll_internal(
-            Some(Duration::from_millis(2000)),
-            &KeyboardEnhancementFlagsFilter,
-        ) {
+        match poll_internal(timeout.leftover(), &KeyboardEnhancementFlagsFilter) {
             Ok(true) => {
                 match read_internal(&KeyboardEnhancementFlagsFilter) {
                     Ok(InternalEvent::KeyboardEnhancementFlags(_current_flags)) => {
@@ -244,6 +352,8 @@ fn read_supports_keyboard_enhancement_raw() -> io::Result<bool> {
                     _ => return Ok(false),
                 }
             }
+            // Polling also stops early when the event reader is woken up, e.g. by an `EventStream`.
+            Ok(false) if !timeout.elapsed() => {}
             Ok(false) => {
                 return Err(io::Error::new(
                     io::ErrorKind::Other,
@@ -255,6 +365,86 @@ fn read_supports_keyboard_enhancement_raw() -> io::Result<bool> {
     }
 }
 
+/// Queries whether the terminal has grapheme clustering (mode 2027) enabled.
+///
+/// Like [`supports_keyboard_enhancement`], this function will block and possibly time out while
+/// [`crossterm::event::read`](crate::event::read) or [`crossterm::event::poll`](crate::event::poll)
+/// are being called.
+#[cfg(feature = "events")]
+pub fn query_grapheme_clustering() -> io::Result<ModeSetting> {
+    query_mode(2027)
+}
+
+#[cfg(feature = "events")]
+fn query_mode(mode: u16) -> io::Result<ModeSetting> {
+    crate::event::check_blocking_read()?;
+    if is_raw_mode_enabled() {
+        query_mode_raw(mode)
+    } else {
+        enable_raw_mode()?;
+        let setting = query_mode_raw(mode);
+        disable_raw_mode()?;
+        setting
+    }
+}
+
+#[cfg(feature = "events")]
+fn query_mode_raw(mode: u16) -> io::Result<ModeSetting> {
+    use crate::event::{
+        filter::{ModeReportFilter, PrimaryDeviceAttributesFilter},
+        poll_internal, read_internal,
+        timeout::PollTimeout,
+        InternalEvent,
+    };
+    use std::time::Duration;
+
+    // ESC [ ? Pd $ p   Request the setting of the DEC private mode Pd (DECRQM).
+    // ESC [ c          Query primary device attributes, all terminals reply to it.
+    write_query(format!("\x1B[?{}$p\x1B[c", mode).as_bytes())?;
+
+    let timeout = PollTimeout::new(Some(Duration::from_millis(2000)));
+    loop {
+        match poll_internal(timeout.leftover(), &ModeReportFilter) {
+            Ok(true) => match read_internal(&ModeReportFilter) {
+                Ok(InternalEvent::ModeReport(reported, setting)) if reported == mode => {
+                    // Flush the PrimaryDeviceAttributes out of the event queue.
+                    read_internal(&PrimaryDeviceAttributesFilter).ok();
+                    return Ok(ModeSetting::from_report(setting));
+                }
+                // A report of another mode, queried by someone else.
+                Ok(InternalEvent::ModeReport(_, _)) => {}
+                _ => return Ok(ModeSetting::NotRecognized),
+            },
+            // Polling also stops early when the event reader is woken up, e.g. by an `EventStream`.
+            Ok(false) if !timeout.elapsed() => {}
+            Ok(false) => {
+                return Err(io::Error::new(
+                    io::ErrorKind::Other,
+                    "The mode setting could not be read within a normal duration",
+                ));
+            }
+            Err(_) => {}
+        }
+    }
+}
+
+/// Writes a query to the terminal, to stdout if there's no controlling terminal.
+#[cfg(feature = "events")]
+fn write_query(query: &[u8]) -> io::Result<()> {
+    use std::io::Write;
+
+    let result = File::open("/dev/tty").and_then(|mut file| {
+        file.write_all(query)?;
+        file.flush()
+    });
+    if result.is_err() {
+        let mut stdout = io::stdout();
+        stdout.write_all(query)?;
+        stdout.flush()?;
+    }
+    Ok(())
+}
+
 /// execute tput with the given argument and parse
 /// the output as a u16.
 ///
@@ -287,8 +477,40 @@ fn tput_size() -> Option<(u16, u16)> {
 
 #[cfg(feature = "libc")]
 // Transform the given mode into an raw mode (non-canonical) mode.
-fn raw_terminal_attr(termios: &mut Termios) {
+fn raw_terminal_attr(termios: &mut Termios, options: &RawModeOptions) {
+    let original = *termios;
     unsafe { cfmakeraw(termios) }
+
+    let keep = |keep: bool, flag| if keep { flag } else { 0 };
+    termios.c_lflag |= original.c_lflag & keep(options.keep_signals, ISIG);
+    termios.c_oflag |= original.c_oflag & keep(options.keep_output_processing, OPOST);
+    termios.c_iflag |= original.c_iflag
+        & (keep(options.keep_flow_control, IXON)
+            | keep(options.keep_carriage_return_translation, ICRNL));
+    termios.c_cc[VMIN] = options.min_bytes;
+    termios.c_cc[VTIME] = options.timeout;
+}
+
+#[cfg(not(feature = "libc"))]
+// Transform the given mode into an raw mode (non-canonical) mode.
+fn raw_terminal_attr(termios: &mut Termios, options: &RawModeOptions) {
+    let original = termios.clone();
+    termios.make_raw();
+
+    if options.keep_signals {
+        termios.local_modes |= original.local_modes & LocalModes::ISIG;
+    }
+    if options.keep_output_processing {
+        termios.output_modes |= original.output_modes & OutputModes::OPOST;
+    }
+    if options.keep_flow_control {
+        termios.input_modes |= original.input_modes & InputModes::IXON;
+    }
+    if options.keep_carriage_return_translation {
+        termios.input_modes |= original.input_modes & InputModes::ICRNL;
+    }
+    termios.special_codes[SpecialCodeIndex::VMIN] = options.min_bytes;
+    termios.special_codes[SpecialCodeIndex::VTIME] = options.timeout;
 }
 
 #[cfg(feature = "libc")]
@@ -2,18 +2,27 @@
 
 use std::fmt::{self, Write};
 use std::io::{self};
+use std::mem;
 
 use crossterm_winapi::{Console, ConsoleMode, Coord, Handle, ScreenBuffer, Size};
+use parking_lot::Mutex;
 use winapi::{
     shared::minwindef::DWORD,
-    um::wincon::{SetConsoleTitleW, ENABLE_ECHO_INPUT, ENABLE_LINE_INPUT, ENABLE_PROCESSED_INPUT},
+    um::wincon::{
+        FlushConsoleInputBuffer, ScrollConsoleScreenBufferW, SetConsoleTitleW, CHAR_INFO,
+        ENABLE_ECHO_INPUT, ENABLE_LINE_INPUT, ENABLE_PROCESSED_INPUT, SMALL_RECT,
+    },
 };
 
 use crate::{
     cursor,
-    terminal::{ClearType, WindowSize},
+    terminal::{ClearType, ModeSetting, RawModeOptions, WindowSize},
 };
 
+// The rows (top, bottom) of the window set with `SetScrollRegion`, used by `InsertLines` and
+// `DeleteLines`.
+static SCROLL_REGION: Mutex<Option<(u16, u16)>> = parking_lot::const_mutex(None);
+
 /// bits which can't be set in raw mode
 const NOT_RAW_MODE_MASK: DWORD = ENABLE_LINE_INPUT | ENABLE_ECHO_INPUT | ENABLE_PROCESSED_INPUT;
 
@@ -23,17 +32,24 @@ pub(crate) fn is_raw_mode_enabled() -> std::io::Result<bool> {
     let dw_mode = console_mode.mode()?;
 
     Ok(
-        // check none of the "not raw" bits is set
-        dw_mode & NOT_RAW_MODE_MASK == 0,
+        // check none of the "not raw" bits is set, processing CTRL+C may be kept
+        dw_mode & (NOT_RAW_MODE_MASK & !ENABLE_PROCESSED_INPUT) == 0,
     )
 }
 
 pub(crate) fn enable_raw_mode() -> std::io::Result<()> {
+    enable_raw_mode_with(RawModeOptions::default())
+}
+
+pub(crate) fn enable_raw_mode_with(options: RawModeOptions) -> std::io::Result<()> {
     let console_mode = ConsoleMode::from(Handle::current_in_handle()?);
 
     let dw_mode = console_mode.mode()?;
 
-    let new_mode = dw_mode & !NOT_RAW_MODE_MASK;
+    let mut new_mode = dw_mode & !NOT_RAW_MODE_MASK;
+    if options.keep_signals {
+        new_mode |= dw_mode & ENABLE_PROCESSED_INPUT;
+    }
 
     console_mode.set_mode(new_mode)?;
 
@@ -52,6 +68,20 @@ pub(crate) fn disable_raw_mode() -> std::io::Result<()> {
     Ok(())
 }
 
+pub(crate) fn flush_input() -> std::io::Result<()> {
+    let handle = Handle::current_in_handle()?;
+    if unsafe { FlushConsoleInputBuffer(*handle) } != 0 {
+        Ok(())
+    } else {
+        Err(io::Error::last_os_error())
+    }
+}
+
+pub(crate) fn drain_output() -> std::io::Result<()> {
+    // Writes to the console are synchronous, there's no output queue to wait for.
+    Ok(())
+}
+
 pub(crate) fn size() -> io::Result<(u16, u16)> {
     let terminal_size = ScreenBuffer::current()?.info()?.terminal_size();
     // windows starts counting at 0, unix at 1, add one to replicated unix behaviour.
@@ -76,6 +106,14 @@ pub fn supports_keyboard_enhancement() -> std::io::Result<bool> {
     Ok(false)
 }
 
+/// Queries whether the terminal has grapheme clustering (mode 2027) enabled.
+///
+/// This always returns `Ok(ModeSetting::NotRecognized)` on Windows.
+#[cfg(feature = "events")]
+pub fn query_grapheme_clustering() -> std::io::Result<ModeSetting> {
+    Ok(ModeSetting::NotRecognized)
+}
+
 pub(crate) fn clear(clear_type: ClearType) -> std::io::Result<()> {
     let screen_buffer = ScreenBuffer::current()?;
     let csbi = screen_buffer.info()?;
@@ -131,6 +169,128 @@ pub(crate) fn scroll_down(row_count: u16) -> std::io::Result<()> {
     Ok(())
 }
 
+pub(crate) fn set_scroll_region(region: Option<(u16, u16)>) -> std::io::Result<()> {
+    *SCROLL_REGION.lock() = region;
+    cursor::sys::move_to(0, 0)
+}
+
+pub(crate) fn insert_lines(count: u16) -> std::io::Result<()> {
+    scroll_lines(count, true)
+}
+
+pub(crate) fn delete_lines(count: u16) -> std::io::Result<()> {
+    scroll_lines(count, false)
+}
+
+/// Moves the rows from the cursor row to the bottom of the scroll region by `count` rows, down
+/// when inserting and up when deleting, filling the vacated rows with blanks.
+fn scroll_lines(count: u16, insert: bool) -> std::io::Result<()> {
+    let screen_buffer = ScreenBuffer::current()?;
+    let csbi = screen_buffer.info()?;
+    let window = csbi.terminal_window();
+    let cursor = csbi.cursor_pos();
+
+    let (top, bottom) = match *SCROLL_REGION.lock() {
+        Some((top, bottom)) => {
+            let height = (window.bottom - window.top) as u16;
+            (
+                window.top + top.min(height) as i16,
+                window.top + bottom.min(height) as i16,
+            )
+        }
+        None => (window.top, window.bottom),
+    };
+    // Like in terminals, rows outside of the scroll region aren't moved.
+    if count == 0 || cursor.y < top || cursor.y > bottom {
+        return Ok(());
+    }
+
+    // Moving more rows than there are below the cursor blanks all of them.
+    let count = count.min((bottom - cursor.y + 1) as u16) as i16;
+    let rect = SMALL_RECT {
+        Left: 0,
+        Top: cursor.y,
+        Right: csbi.buffer_size().width - 1,
+        Bottom: bottom,
+    };
+    let destination = if insert {
+        cursor.y + count
+    } else {
+        cursor.y - count
+    };
+    scroll_rect(rect, Coord::new(0, destination), csbi.attributes())
+}
+
+pub(crate) fn insert_chars(count: u16) -> std::io::Result<()> {
+    scroll_chars(count, true)
+}
+
+pub(crate) fn delete_chars(count: u16) -> std::io::Result<()> {
+    scroll_chars(count, false)
+}
+
+/// Moves the cells from the cursor to the end of the row by `count` columns, right when
+/// inserting and left when deleting, filling the vacated cells with blanks.
+fn scroll_chars(count: u16, insert: bool) -> std::io::Result<()> {
+    let screen_buffer = ScreenBuffer::current()?;
+    let csbi = screen_buffer.info()?;
+    let cursor = csbi.cursor_pos();
+    let width = csbi.buffer_size().width;
+    if count == 0 {
+        return Ok(());
+    }
+
+    // Moving more cells than there are right of the cursor blanks all of them.
+    let count = count.min((width - cursor.x) as u16) as i16;
+    let rect = SMALL_RECT {
+        Left: cursor.x,
+        Top: cursor.y,
+        Right: width - 1,
+        Bottom: cursor.y,
+    };
+    let destination = if insert {
+        cursor.x + count
+    } else {
+        cursor.x - count
+    };
+    scroll_rect(rect, Coord::new(destination, cursor.y), csbi.attributes())
+}
+
+/// Moves the cells of `rect` to `destination`, clipped to `rect`, filling the vacated cells with
+/// blanks.
+fn scroll_rect(rect: SMALL_RECT, destination: Coord, attributes: u16) -> std::io::Result<()> {
+    let handle = Handle::current_out_handle()?;
+
+    let mut fill: CHAR_INFO = unsafe { mem::zeroed() };
+    unsafe {
+        *fill.Char.UnicodeChar_mut() = ' ' as u16;
+    }
+    fill.Attributes = attributes;
+
+    let result =
+        unsafe { ScrollConsoleScreenBufferW(*handle, &rect, &rect, destination.into(), &fill) };
+    if result != 0 {
+        Ok(())
+    } else {
+        Err(io::Error::last_os_error())
+    }
+}
+
+pub(crate) fn erase_chars(count: u16) -> std::io::Result<()> {
+    let screen_buffer = ScreenBuffer::current()?;
+    let csbi = screen_buffer.info()?;
+    let cursor = csbi.cursor_pos();
+
+    let cells_to_write = count.min((csbi.buffer_size().width - cursor.x) as u16);
+    clear_winapi(cursor, cells_to_write as u32, csbi.attributes())
+}
+
+pub(crate) fn repeat_char(c: char, count: u16) -> std::io::Result<()> {
+    let text = c.to_string().repeat(usize::from(count));
+    Console::from(Handle::current_out_handle()?).write_char_buffer(text.as_bytes())?;
+    Ok(())
+}
+
 pub(crate) fn set_size(width: u16, height: u16) -> std::io::Result<()> {
     if width <= 1 {
         return Err(io::Error::new(